@@ -0,0 +1,117 @@
+//! Command-line interface to a file-system ontology registry.
+//!
+//! Ontologies are cached in `$XDG_CACHE_HOME/ontology-registry` (falling back to
+//! `~/.cache/ontology-registry`) unless `--registry-path` is given.
+
+use clap::{Parser, Subcommand};
+use ontology_registry::prelude::*;
+use std::env;
+use std::io;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(version, about = "Download, cache and manage ontologies")]
+struct Cli {
+    /// Directory in which ontologies are cached.
+    #[arg(long, global = true)]
+    registry_path: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Download an ontology into the registry.
+    Register(KeyArgs),
+    /// List all registered ontologies.
+    List,
+    /// Write a registered ontology to stdout.
+    Get(KeyArgs),
+    /// Remove an ontology from the registry.
+    Unregister(KeyArgs),
+}
+
+#[derive(clap::Args)]
+struct KeyArgs {
+    /// Ontology id, e.g. `hp` or `mondo`.
+    ontology_id: String,
+    /// Release to use. Defaults to the latest release; `unversioned` stores the current file
+    /// without a version.
+    #[arg(long = "version", default_value = "latest")]
+    version: String,
+    /// File format: json, obo, owl or ttl.
+    #[arg(long, default_value = "obo", value_parser = parse_file_type)]
+    format: FileType,
+}
+
+impl KeyArgs {
+    fn into_registry_key(self) -> RegistryKey {
+        let version = match self.version.as_str() {
+            "latest" => Version::Latest,
+            "unversioned" => Version::Unversioned,
+            v => Version::from(v),
+        };
+        RegistryKey::new(self.ontology_id, version, self.format)
+    }
+}
+
+fn parse_file_type(raw: &str) -> Result<FileType, String> {
+    FileType::from_file_ending(&format!(".{}", raw.trim_start_matches('.')))
+        .map_err(|err| err.to_string())
+}
+
+fn default_registry_path() -> PathBuf {
+    let cache_home = env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .unwrap_or_else(env::temp_dir);
+    cache_home.join("ontology-registry")
+}
+
+fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
+    let registry = FileSystemOntologyRegistry::new(
+        cli.registry_path.unwrap_or_else(default_registry_path),
+        BioRegistryMetadataProvider::default(),
+        OboLibraryProvider::default(),
+    );
+
+    match cli.command {
+        Command::Register(args) => {
+            let registry_key = args.into_registry_key();
+            registry.register(registry_key.clone())?;
+            println!("Registered {registry_key}");
+        }
+        Command::List => {
+            for registry_key in registry.list()? {
+                println!("{registry_key}");
+            }
+        }
+        Command::Get(args) => {
+            let registry_key = args.into_registry_key();
+            let mut file = registry
+                .get(registry_key.clone())
+                .ok_or_else(|| format!("{registry_key} is not registered"))?;
+            io::copy(&mut file, &mut io::stdout().lock())?;
+        }
+        Command::Unregister(args) => {
+            let registry_key = args.into_registry_key();
+            registry.unregister(registry_key.clone())?;
+            println!("Unregistered {registry_key}");
+        }
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
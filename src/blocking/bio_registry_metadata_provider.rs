@@ -1,8 +1,17 @@
+use crate::Version;
+use crate::blocking::credentials::{self, Credentials};
+use crate::blocking::provider_client::ProviderClient;
+use crate::blocking::retry_policy::RetryPolicy;
+use crate::blocking::{DEFAULT_USER_AGENT, parse_proxy};
 use crate::error::OntologyRegistryError;
 use crate::ontology_metadata::OntologyMetadata;
 use crate::traits::OntologyMetadataProviding;
-use reqwest::blocking::Client;
+use reqwest::blocking::{Client, RequestBuilder};
+use reqwest::header::USER_AGENT;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct BioRegistryResource {
@@ -16,12 +25,28 @@ struct BioRegistryResource {
     pub download_json: Option<String>,
     pub download_rdf: Option<String>,
     pub preferred_prefix: Option<String>,
+    pub deprecated: Option<bool>,
+    pub replaced_by: Option<String>,
+    pub depends_on: Option<Vec<String>>,
+}
+
+/// The download locations of a resource in the bulk listing of the registry.
+#[derive(Debug, Deserialize)]
+struct BioRegistryDownloads {
+    download_owl: Option<String>,
+    download_obo: Option<String>,
+    download_json: Option<String>,
 }
 
 #[derive(Clone, Debug)]
 pub struct BioRegistryMetadataProvider {
     api_url: String,
-    client: Client,
+    client: ProviderClient,
+    retry_policy: RetryPolicy,
+    user_agent: String,
+    timeout: Option<Duration>,
+    /// Redacted in `Debug` output.
+    credentials: Option<Credentials>,
 }
 
 impl BioRegistryMetadataProvider {
@@ -32,9 +57,172 @@ impl BioRegistryMetadataProvider {
         }
         BioRegistryMetadataProvider {
             api_url: url,
-            client: Client::new(),
+            client: ProviderClient::default(),
+            retry_policy: RetryPolicy::default(),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            timeout: None,
+            credentials: None,
         }
     }
+
+    /// Aborts a request that has not completed within `timeout`.
+    ///
+    /// Without this option the client's own default applies. A timed out request surfaces as
+    /// `OntologyRegistryError::ProvidingMetadata`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Authenticates every request with `token`, sent as `Authorization: Bearer <token>`, e.g.
+    /// for a self-hosted registry. Replaces any basic auth credentials.
+    ///
+    /// The token is never logged. A `401` or `403` answer fails with
+    /// `OntologyRegistryError::HttpError` and logs a warning about the rejected credentials.
+    pub fn with_bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.credentials = Some(Credentials::Bearer(token.into()));
+        self
+    }
+
+    /// Authenticates every request with HTTP basic auth. Replaces any bearer token.
+    ///
+    /// The password is never logged. A `401` or `403` answer fails with
+    /// `OntologyRegistryError::HttpError` and logs a warning about the rejected credentials.
+    pub fn with_basic_auth(
+        mut self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        self.credentials = Some(Credentials::Basic {
+            username: username.into(),
+            password: password.into(),
+        });
+        self
+    }
+
+    fn request(&self, url: &str) -> RequestBuilder {
+        let request = self.client.get(url).header(USER_AGENT, &self.user_agent);
+        let request = match &self.credentials {
+            Some(credentials) => credentials.apply(request),
+            None => request,
+        };
+        match self.timeout {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        }
+    }
+
+    /// Sends every request through the HTTP(S) proxy at `proxy_url`, e.g.
+    /// `http://proxy.example.com:3128`. Not applied to a client passed to [`Self::with_client`];
+    /// see [`ProviderClient`].
+    ///
+    /// # Errors
+    /// Returns `OntologyRegistryError::InvalidProxy` if `proxy_url` is not a valid proxy URL.
+    pub fn with_proxy(mut self, proxy_url: &str) -> Result<Self, OntologyRegistryError> {
+        self.client = self.client.with_proxy(parse_proxy(proxy_url)?);
+        Ok(self)
+    }
+
+    /// Uses `client` for all requests, regardless of the proxy and redirect settings; see
+    /// [`ProviderClient`].
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = self.client.with_client(client);
+        self
+    }
+
+    /// Follows at most `limit` redirects per request instead of reqwest's default of 10. A
+    /// limit of `0` disables following redirects, so that a redirect fails with
+    /// `OntologyRegistryError::HttpError`. Not applied to a client passed to
+    /// [`Self::with_client`]; see [`ProviderClient`].
+    pub fn with_redirect_policy(mut self, limit: usize) -> Self {
+        self.client = self.client.with_redirect_limit(limit);
+        self
+    }
+
+    /// Sets the User-Agent header sent with every request. Defaults to `ontology-registry/<version>`.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Retries failed requests up to `max_retries` times with exponential backoff starting at `base_delay`.
+    ///
+    /// Connection errors and retryable status codes (5xx, 408, 429) are retried. Other statuses fail immediately.
+    pub fn with_retries(mut self, max_retries: u32, base_delay: Duration) -> Self {
+        self.retry_policy = self.retry_policy.with_backoff(max_retries, base_delay);
+        self
+    }
+
+    /// Retries a request answered with `429 Too Many Requests` up to `max_retries` times after
+    /// its `Retry-After` delay. Disabled by default; see [`RetryPolicy`].
+    pub fn with_rate_limit_retries(mut self, max_retries: u32) -> Self {
+        self.retry_policy = self.retry_policy.with_rate_limit_retries(max_retries);
+        self
+    }
+
+    /// Fails instead of waiting when a `Retry-After` delay exceeds `max_retry_after`. Defaults
+    /// to 60 seconds; see [`RetryPolicy`].
+    pub fn with_max_retry_after(mut self, max_retry_after: Duration) -> Self {
+        self.retry_policy = self.retry_policy.with_max_retry_after(max_retry_after);
+        self
+    }
+
+    /// Fetches the bulk listing of all resources, keyed by prefix.
+    fn fetch_registry<T: DeserializeOwned>(
+        &self,
+    ) -> Result<BTreeMap<String, T>, OntologyRegistryError> {
+        let url = self.api_url.clone() + "registry";
+
+        let response = self
+            .retry_policy
+            .send(|| self.request(&url))?
+            .map_err(|err| {
+                if err.is_timeout() {
+                    OntologyRegistryError::ProvidingMetadata {
+                        reason: format!("Request for {url} timed out"),
+                    }
+                } else {
+                    err.into()
+                }
+            })?;
+
+        credentials::warn_if_rejected(self.credentials.as_ref(), &url, response.status());
+        if !response.status().is_success() {
+            return Err(OntologyRegistryError::HttpError {
+                status: response.status().as_u16(),
+                url,
+            });
+        }
+
+        response
+            .json()
+            .map_err(|_| OntologyRegistryError::ProvidingMetadata {
+                reason: format!("Cant convert to json for {url}"),
+            })
+    }
+
+    fn metadata_from_resource(
+        resource: BioRegistryResource,
+        ontology_id: &str,
+    ) -> Result<OntologyMetadata, OntologyRegistryError> {
+        let Some(version) = resource.version else {
+            return Err(OntologyRegistryError::ProvidingMetadata {
+                reason: format!("Version not found for {}", ontology_id),
+            });
+        };
+        Ok(OntologyMetadata {
+            // The preferred prefix carries the canonical casing, e.g. `GO` for `go`.
+            ontology_id: resource.preferred_prefix.unwrap_or(resource.prefix),
+            version,
+            json_file_location: resource.download_json,
+            owl_file_location: resource.download_owl,
+            obo_file_location: resource.download_obo,
+            title: resource.name,
+            is_obsolete: resource.deprecated.unwrap_or(false),
+            replaced_by: resource.replaced_by,
+            dependencies: resource.depends_on.unwrap_or_default(),
+        })
+    }
 }
 
 impl Default for BioRegistryMetadataProvider {
@@ -50,14 +238,32 @@ impl OntologyMetadataProviding for BioRegistryMetadataProvider {
         let url = self.api_url.clone() + "registry/" + ontology_id;
 
         let response = self
-            .client
-            .get(url.clone())
-            .header("User-Agent", "ontology-registry")
-            .send()
-            .map_err(|err| OntologyRegistryError::ProvidingMetadata {
-                reason: err.to_string(),
+            .retry_policy
+            .send(|| self.request(&url))?
+            .map_err(|err| {
+                if err.is_timeout() {
+                    OntologyRegistryError::ProvidingMetadata {
+                        reason: format!("Request for {ontology_id} timed out"),
+                    }
+                } else {
+                    err.into()
+                }
             })?;
 
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(OntologyRegistryError::NotFound {
+                ontology_id: ontology_id.to_string(),
+                version: Version::Latest.to_string(),
+            });
+        }
+        credentials::warn_if_rejected(self.credentials.as_ref(), &url, response.status());
+        if !response.status().is_success() {
+            return Err(OntologyRegistryError::HttpError {
+                status: response.status().as_u16(),
+                url,
+            });
+        }
+
         let bio_registry_metadata: BioRegistryResource =
             response
                 .json()
@@ -65,20 +271,65 @@ impl OntologyMetadataProviding for BioRegistryMetadataProvider {
                     reason: format!("Cant convert to json for {ontology_id}"),
                 })?;
 
-        if let Some(version) = bio_registry_metadata.version {
-            Ok(OntologyMetadata {
-                ontology_id: bio_registry_metadata.prefix,
-                version,
-                json_file_location: bio_registry_metadata.download_json,
-                owl_file_location: bio_registry_metadata.download_owl,
-                obo_file_location: bio_registry_metadata.download_obo,
-                title: bio_registry_metadata.name,
+        Self::metadata_from_resource(bio_registry_metadata, ontology_id)
+    }
+
+    /// Looks up all ids in the bulk listing of the registry, fetched in a single request.
+    ///
+    /// Ids are matched case-insensitively against the resource prefixes; unlike
+    /// `provide_metadata`, synonyms of a prefix are not resolved. If the listing cannot be
+    /// fetched, every id fails with the reason.
+    fn provide_metadata_batch(
+        &self,
+        ontology_ids: &[&str],
+    ) -> Vec<(String, Result<OntologyMetadata, OntologyRegistryError>)> {
+        let resources = match self.fetch_registry::<BioRegistryResource>() {
+            Ok(resources) => resources,
+            Err(err) => {
+                let reason = err.to_string();
+                return ontology_ids
+                    .iter()
+                    .map(|id| {
+                        let err = OntologyRegistryError::ProvidingMetadata {
+                            reason: reason.clone(),
+                        };
+                        (id.to_string(), Err(err))
+                    })
+                    .collect();
+            }
+        };
+
+        ontology_ids
+            .iter()
+            .map(|id| {
+                let result = match resources.get(&id.to_lowercase()).cloned() {
+                    Some(resource) => Self::metadata_from_resource(resource, id),
+                    None => Err(OntologyRegistryError::NotFound {
+                        ontology_id: id.to_string(),
+                        version: Version::Latest.to_string(),
+                    }),
+                };
+                (id.to_string(), result)
             })
-        } else {
-            Err(OntologyRegistryError::ProvidingMetadata {
-                reason: format!("Version not found for {}", ontology_id),
+            .collect()
+    }
+
+    /// Returns the prefixes of all resources with a downloadable OWL, OBO or JSON file, sorted.
+    ///
+    /// The Bioregistry also lists many identifier schemes that are not ontologies; those are
+    /// left out.
+    fn list_available(&self) -> Result<Vec<String>, OntologyRegistryError> {
+        let resources = self.fetch_registry::<BioRegistryDownloads>()?;
+
+        Ok(resources
+            .into_iter()
+            .filter(|(_, downloads)| {
+                downloads.download_owl.is_some()
+                    || downloads.download_obo.is_some()
+                    || downloads.download_json.is_some()
             })
-        }
+            .map(|(prefix, _)| prefix)
+            .collect())
     }
 }
 
@@ -99,6 +350,89 @@ mod tests {
         .to_string()
     }
 
+    #[test]
+    fn test_list_available_skips_resources_without_downloads() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/registry")
+            .with_status(200)
+            .with_body(
+                r#"{
+                    "mondo": {"prefix": "mondo", "download_obo": "http://purl.obolibrary.org/obo/mondo.obo"},
+                    "doi": {"prefix": "doi"},
+                    "go": {"prefix": "go", "download_owl": "http://purl.obolibrary.org/obo/go.owl"}
+                }"#,
+            )
+            .create();
+
+        let provider = BioRegistryMetadataProvider::new(&server.url());
+
+        assert_eq!(provider.list_available().unwrap(), vec!["go", "mondo"]);
+        mock.assert();
+    }
+
+    #[test]
+    fn test_provide_metadata_batch_uses_one_bulk_request() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/registry")
+            .with_status(200)
+            .with_body(
+                r#"{
+                    "mondo": {"prefix": "mondo", "version": "2024-01-04"},
+                    "go": {"prefix": "go", "preferred_prefix": "GO", "version": "2024-01-17"}
+                }"#,
+            )
+            .expect(1)
+            .create();
+
+        let provider = BioRegistryMetadataProvider::new(&server.url());
+        let results = provider.provide_metadata_batch(&["GO", "chebi", "mondo"]);
+
+        mock.assert();
+        let ids: Vec<_> = results.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["GO", "chebi", "mondo"]);
+        assert_eq!(results[0].1.as_ref().unwrap().version, "2024-01-17");
+        assert!(matches!(
+            results[1].1,
+            Err(OntologyRegistryError::NotFound { .. })
+        ));
+        assert_eq!(results[2].1.as_ref().unwrap().ontology_id, "mondo");
+    }
+
+    #[test]
+    fn test_provide_metadata_batch_answers_repeated_ids() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/registry")
+            .with_status(200)
+            .with_body(r#"{"go": {"prefix": "go", "version": "2024-01-17"}}"#)
+            .create();
+
+        let provider = BioRegistryMetadataProvider::new(&server.url());
+        let results = provider.provide_metadata_batch(&["go", "GO", "go"]);
+
+        assert_eq!(results.len(), 3);
+        for (_, result) in &results {
+            assert_eq!(result.as_ref().unwrap().version, "2024-01-17");
+        }
+    }
+
+    #[test]
+    fn test_provide_metadata_batch_reports_failed_bulk_request_per_id() {
+        let mut server = Server::new();
+        let _m = server.mock("GET", "/registry").with_status(404).create();
+
+        let provider = BioRegistryMetadataProvider::new(&server.url());
+        let results = provider.provide_metadata_batch(&["go", "mondo"]);
+
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|(_, result)| matches!(
+            result,
+            Err(OntologyRegistryError::ProvidingMetadata { .. })
+        )));
+    }
+
     #[test]
     fn test_new_adds_trailing_slash() {
         let provider = BioRegistryMetadataProvider::new("https://bioregistry.io/api");
@@ -108,6 +442,41 @@ mod tests {
         assert_eq!(provider_existing.api_url, "https://bioregistry.io/api/");
     }
 
+    #[test]
+    fn test_provide_metadata_sends_user_agent() {
+        let mut server = Server::new();
+
+        let mock = server
+            .mock("GET", "/registry/mondo")
+            .match_header("user-agent", "my-lab-pipeline/2.0")
+            .with_status(200)
+            .with_body(valid_response_json())
+            .create();
+
+        let provider =
+            BioRegistryMetadataProvider::new(&server.url()).with_user_agent("my-lab-pipeline/2.0");
+        provider.provide_metadata("mondo").unwrap();
+
+        mock.assert();
+    }
+
+    #[test]
+    fn test_provide_metadata_with_shared_client() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/registry/mondo")
+            .with_status(200)
+            .with_body(valid_response_json())
+            .create();
+
+        let client = Client::new();
+        let provider = BioRegistryMetadataProvider::new(&server.url()).with_client(client);
+        let metadata = provider.provide_metadata("mondo").unwrap();
+
+        mock.assert();
+        assert_eq!(metadata.ontology_id, "mondo");
+    }
+
     #[test]
     fn test_provide_metadata_success() {
         let mut server = Server::new();
@@ -137,6 +506,50 @@ mod tests {
         assert!(metadata.obo_file_location.is_none());
     }
 
+    #[test]
+    fn test_provide_metadata_prefers_preferred_prefix() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/registry/go")
+            .with_status(200)
+            .with_body(
+                r#"{
+                    "prefix": "go",
+                    "preferred_prefix": "GO",
+                    "version": "2024-01-17"
+                }"#,
+            )
+            .create();
+
+        let provider = BioRegistryMetadataProvider::new(&server.url());
+        let metadata = provider.provide_metadata("go").unwrap();
+
+        assert_eq!(metadata.ontology_id, "GO");
+    }
+
+    #[test]
+    fn test_provide_metadata_deprecated_resource() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/registry/mp")
+            .with_status(200)
+            .with_body(
+                r#"{
+                    "prefix": "mp",
+                    "version": "2024-02-07",
+                    "deprecated": true,
+                    "replaced_by": "upheno"
+                }"#,
+            )
+            .create();
+
+        let provider = BioRegistryMetadataProvider::new(&server.url());
+        let metadata = provider.provide_metadata("mp").unwrap();
+
+        assert!(metadata.is_obsolete);
+        assert_eq!(metadata.replaced_by.as_deref(), Some("upheno"));
+    }
+
     #[test]
     fn test_provide_metadata_missing_version() {
         let mut server = Server::new();
@@ -189,13 +602,106 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_provide_metadata_retries_until_success() {
+        let mut server = Server::new();
+
+        let failing = server
+            .mock("GET", "/registry/mondo")
+            .with_status(503)
+            .expect(2)
+            .create();
+        let succeeding = server
+            .mock("GET", "/registry/mondo")
+            .with_status(200)
+            .with_body(valid_response_json())
+            .expect(1)
+            .create();
+
+        let provider = BioRegistryMetadataProvider::new(&server.url())
+            .with_retries(3, Duration::from_millis(1));
+        let metadata = provider.provide_metadata("mondo").unwrap();
+
+        failing.assert();
+        succeeding.assert();
+        assert_eq!(metadata.version, "2024-01-04");
+    }
+
+    #[test]
+    fn test_provide_metadata_honors_retry_after() {
+        let mut server = Server::new();
+
+        let rate_limited = server
+            .mock("GET", "/registry/mondo")
+            .with_status(429)
+            .with_header("retry-after", "1")
+            .expect(1)
+            .create();
+        let succeeding = server
+            .mock("GET", "/registry/mondo")
+            .with_status(200)
+            .with_body(valid_response_json())
+            .expect(1)
+            .create();
+
+        let provider = BioRegistryMetadataProvider::new(&server.url()).with_rate_limit_retries(2);
+        let started = std::time::Instant::now();
+        let metadata = provider.provide_metadata("mondo").unwrap();
+
+        rate_limited.assert();
+        succeeding.assert();
+        assert!(started.elapsed() >= Duration::from_secs(1));
+        assert_eq!(metadata.version, "2024-01-04");
+    }
+
+    #[test]
+    fn test_provide_metadata_timeout() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/registry/mondo")
+            .with_status(200)
+            .with_body_from_request(|_| {
+                std::thread::sleep(Duration::from_millis(500));
+                valid_response_json().into_bytes()
+            })
+            .create();
+
+        let provider =
+            BioRegistryMetadataProvider::new(&server.url()).with_timeout(Duration::from_millis(50));
+        let result = provider.provide_metadata("mondo");
+
+        match result.unwrap_err() {
+            OntologyRegistryError::ProvidingMetadata { reason } => {
+                assert!(reason.contains("timed out"));
+            }
+            _ => panic!("Wrong error type"),
+        }
+    }
+
+    #[test]
+    fn test_provide_metadata_unknown_ontology() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/registry/unknown")
+            .with_status(404)
+            .create();
+
+        let provider = BioRegistryMetadataProvider::new(&server.url());
+        let result = provider.provide_metadata("unknown");
+
+        assert!(matches!(
+            result,
+            Err(OntologyRegistryError::NotFound { ontology_id, .. }) if ontology_id == "unknown"
+        ));
+    }
+
     #[test]
     fn test_provide_metadata_network_error() {
         let mut server = Server::new();
         let _m = server
             .mock("GET", "/registry/mondo")
-            .with_status(500)
-            .with_body("Internal Server Error")
+            .with_status(503)
+            .with_body("Service Unavailable")
             .create();
 
         let provider = BioRegistryMetadataProvider::new(&server.url());
@@ -203,10 +709,105 @@ mod tests {
 
         assert!(result.is_err());
         match result.unwrap_err() {
-            OntologyRegistryError::ProvidingMetadata { reason } => {
-                assert!(reason.contains("Cant convert to json"));
+            OntologyRegistryError::HttpError { status, url } => {
+                assert_eq!(status, 503);
+                assert!(url.ends_with("/registry/mondo"));
             }
             _ => panic!("Wrong error type"),
         }
     }
+
+    #[test]
+    fn test_provide_metadata_sends_bearer_token() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/registry/mondo")
+            .match_header("authorization", "Bearer secret")
+            .with_status(200)
+            .with_body(valid_response_json())
+            .create();
+
+        let provider = BioRegistryMetadataProvider::new(&server.url()).with_bearer_token("secret");
+        let metadata = provider.provide_metadata("mondo").unwrap();
+
+        mock.assert();
+        assert_eq!(metadata.version, "2024-01-04");
+        assert!(!format!("{provider:?}").contains("secret"));
+    }
+
+    #[test]
+    fn test_provide_metadata_reports_rejected_credentials() {
+        let mut server = Server::new();
+        let _mock = server
+            .mock("GET", "/registry/mondo")
+            .with_status(403)
+            .create();
+
+        let provider =
+            BioRegistryMetadataProvider::new(&server.url()).with_basic_auth("alice", "secret");
+
+        assert!(matches!(
+            provider.provide_metadata("mondo"),
+            Err(OntologyRegistryError::HttpError { status: 403, .. })
+        ));
+    }
+
+    #[test]
+    fn test_provide_metadata_through_proxy() {
+        let mut proxy = Server::new();
+        let mock = proxy
+            .mock("GET", mockito::Matcher::Any)
+            .match_header("host", "bioregistry.example.org")
+            .with_status(200)
+            .with_body(valid_response_json())
+            .create();
+
+        let provider = BioRegistryMetadataProvider::new("http://bioregistry.example.org/api")
+            .with_proxy(&proxy.url())
+            .unwrap();
+
+        assert_eq!(
+            provider.provide_metadata("mondo").unwrap().version,
+            "2024-01-04"
+        );
+        mock.assert();
+    }
+
+    #[test]
+    fn test_redirect_policy_disabled() {
+        let mut server = Server::new();
+        let _redirect = server
+            .mock("GET", "/registry/mondo")
+            .with_status(301)
+            .with_header("location", "/registry/MONDO")
+            .create();
+
+        let provider = BioRegistryMetadataProvider::new(&server.url()).with_redirect_policy(0);
+
+        assert!(matches!(
+            provider.provide_metadata("mondo"),
+            Err(OntologyRegistryError::HttpError { status: 301, .. })
+        ));
+    }
+
+    #[test]
+    fn test_with_client_takes_precedence_over_proxy() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/registry/mondo")
+            .with_status(200)
+            .with_body(valid_response_json())
+            .create();
+
+        let provider = BioRegistryMetadataProvider::new(&server.url())
+            .with_client(Client::new())
+            .with_proxy("http://127.0.0.1:9")
+            .unwrap();
+
+        assert_eq!(
+            provider.provide_metadata("mondo").unwrap().ontology_id,
+            "mondo"
+        );
+        mock.assert();
+    }
 }
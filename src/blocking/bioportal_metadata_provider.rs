@@ -0,0 +1,343 @@
+use crate::Version;
+use crate::blocking::DEFAULT_USER_AGENT;
+use crate::blocking::provider_client::ProviderClient;
+use crate::blocking::retry_policy::RetryPolicy;
+use crate::error::OntologyRegistryError;
+use crate::ontology_metadata::OntologyMetadata;
+use crate::traits::OntologyMetadataProviding;
+use reqwest::StatusCode;
+use reqwest::blocking::{Client, RequestBuilder};
+use reqwest::header::{AUTHORIZATION, USER_AGENT};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct BioPortalSubmission {
+    version: Option<String>,
+    released: Option<String>,
+    has_ontology_language: Option<String>,
+    ontology: Option<BioPortalOntology>,
+    links: Option<BioPortalLinks>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct BioPortalOntology {
+    acronym: String,
+    name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct BioPortalLinks {
+    download: Option<String>,
+}
+
+/// Resolves ontology metadata from the latest submission of an ontology on
+/// [BioPortal](https://bioportal.bioontology.org).
+///
+/// BioPortal requires an API key, which is sent as `Authorization: apikey token=<key>` with
+/// every request. Ontology ids are looked up by their upper-cased BioPortal acronym. If a
+/// submission declares no version, its release date is used instead.
+///
+/// BioPortal download links require the API key as well, so the download locations reported
+/// here are only usable by an ontology provider that authenticates against BioPortal.
+#[derive(Clone)]
+pub struct BioPortalMetadataProvider {
+    api_url: String,
+    api_key: String,
+    client: ProviderClient,
+    retry_policy: RetryPolicy,
+    user_agent: String,
+    timeout: Option<Duration>,
+}
+
+impl BioPortalMetadataProvider {
+    pub fn new(api_url: &str, api_key: impl Into<String>) -> Self {
+        let mut url = api_url.to_string();
+        if !url.ends_with('/') {
+            url += "/";
+        }
+        BioPortalMetadataProvider {
+            api_url: url,
+            api_key: api_key.into(),
+            client: ProviderClient::default(),
+            retry_policy: RetryPolicy::default(),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            timeout: None,
+        }
+    }
+
+    pub fn with_default_url(api_key: impl Into<String>) -> Self {
+        BioPortalMetadataProvider::new("https://data.bioontology.org/", api_key)
+    }
+
+    /// Uses `client` for all requests, regardless of the redirect settings; see
+    /// [`ProviderClient`].
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = self.client.with_client(client);
+        self
+    }
+
+    /// Follows at most `limit` redirects per request instead of reqwest's default of 10. A
+    /// limit of `0` disables following redirects, so that a redirect fails with
+    /// `OntologyRegistryError::HttpError`. Not applied to a client passed to
+    /// [`Self::with_client`]; see [`ProviderClient`].
+    pub fn with_redirect_policy(mut self, limit: usize) -> Self {
+        self.client = self.client.with_redirect_limit(limit);
+        self
+    }
+
+    /// Sets the User-Agent header sent with every request. Defaults to `ontology-registry/<version>`.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Aborts a request that has not completed within `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Retries failed requests up to `max_retries` times with exponential backoff starting at `base_delay`.
+    pub fn with_retries(mut self, max_retries: u32, base_delay: Duration) -> Self {
+        self.retry_policy = self.retry_policy.with_backoff(max_retries, base_delay);
+        self
+    }
+
+    /// Retries a request answered with `429 Too Many Requests` up to `max_retries` times after
+    /// its `Retry-After` delay. Disabled by default; see [`RetryPolicy`].
+    pub fn with_rate_limit_retries(mut self, max_retries: u32) -> Self {
+        self.retry_policy = self.retry_policy.with_rate_limit_retries(max_retries);
+        self
+    }
+
+    /// Fails instead of waiting when a `Retry-After` delay exceeds `max_retry_after`. Defaults
+    /// to 60 seconds; see [`RetryPolicy`].
+    pub fn with_max_retry_after(mut self, max_retry_after: Duration) -> Self {
+        self.retry_policy = self.retry_policy.with_max_retry_after(max_retry_after);
+        self
+    }
+
+    fn request(&self, url: &str) -> RequestBuilder {
+        let request = self
+            .client
+            .get(url)
+            .header(USER_AGENT, &self.user_agent)
+            .header(AUTHORIZATION, format!("apikey token={}", self.api_key));
+        match self.timeout {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        }
+    }
+
+    fn metadata_from_submission(
+        submission: BioPortalSubmission,
+        ontology_id: &str,
+    ) -> Result<OntologyMetadata, OntologyRegistryError> {
+        let version = submission
+            .version
+            .filter(|v| !v.trim().is_empty())
+            .or_else(|| {
+                submission
+                    .released
+                    .map(|released| released.chars().take(10).collect())
+            })
+            .ok_or_else(|| OntologyRegistryError::ProvidingMetadata {
+                reason: format!("Version not found for {ontology_id}"),
+            })?;
+
+        let download = submission.links.and_then(|links| links.download);
+        let language = submission.has_ontology_language.unwrap_or_default();
+        let (owl_file_location, obo_file_location) = match language.to_uppercase().as_str() {
+            "OWL" => (download, None),
+            "OBO" => (None, download),
+            _ => (None, None),
+        };
+
+        let (ontology_id, title) = match submission.ontology {
+            Some(ontology) => (ontology.acronym, ontology.name),
+            None => (ontology_id.to_string(), None),
+        };
+
+        Ok(OntologyMetadata {
+            ontology_id,
+            version,
+            json_file_location: None,
+            owl_file_location,
+            obo_file_location,
+            title,
+            ..Default::default()
+        })
+    }
+}
+
+impl fmt::Debug for BioPortalMetadataProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BioPortalMetadataProvider")
+            .field("api_url", &self.api_url)
+            .field("api_key", &"<redacted>")
+            .field("retry_policy", &self.retry_policy)
+            .field("user_agent", &self.user_agent)
+            .field("timeout", &self.timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+impl OntologyMetadataProviding for BioPortalMetadataProvider {
+    fn provide_metadata(
+        &self,
+        ontology_id: &str,
+    ) -> Result<OntologyMetadata, OntologyRegistryError> {
+        if self.api_key.trim().is_empty() {
+            return Err(OntologyRegistryError::ProvidingMetadata {
+                reason: "No BioPortal API key configured".to_string(),
+            });
+        }
+
+        let url = format!(
+            "{}ontologies/{}/latest_submission?display=all",
+            self.api_url,
+            ontology_id.to_uppercase()
+        );
+
+        let response = self
+            .retry_policy
+            .send(|| self.request(&url))?
+            .map_err(|err| {
+                if err.is_timeout() {
+                    OntologyRegistryError::ProvidingMetadata {
+                        reason: format!("Request for {ontology_id} timed out"),
+                    }
+                } else {
+                    err.into()
+                }
+            })?;
+
+        match response.status() {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                return Err(OntologyRegistryError::ProvidingMetadata {
+                    reason: format!(
+                        "BioPortal rejected the API key while resolving {ontology_id} (HTTP {})",
+                        response.status().as_u16()
+                    ),
+                });
+            }
+            StatusCode::NOT_FOUND => {
+                return Err(OntologyRegistryError::NotFound {
+                    ontology_id: ontology_id.to_string(),
+                    version: Version::Latest.to_string(),
+                });
+            }
+            status if !status.is_success() => {
+                return Err(OntologyRegistryError::HttpError {
+                    status: status.as_u16(),
+                    url,
+                });
+            }
+            _ => {}
+        }
+
+        let submission: BioPortalSubmission =
+            response
+                .json()
+                .map_err(|_| OntologyRegistryError::ProvidingMetadata {
+                    reason: format!("Cant convert to json for {ontology_id}"),
+                })?;
+
+        Self::metadata_from_submission(submission, ontology_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Matcher, Server};
+
+    fn submission_json() -> String {
+        r#"{
+            "version": "v2.1",
+            "released": "2024-03-01T00:00:00-08:00",
+            "hasOntologyLanguage": "OWL",
+            "ontology": {"acronym": "NCIT", "name": "National Cancer Institute Thesaurus"},
+            "links": {"download": "https://data.bioontology.org/ontologies/NCIT/submissions/1/download"}
+        }"#
+        .to_string()
+    }
+
+    #[test]
+    fn test_provide_metadata_sends_api_key() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/ontologies/NCIT/latest_submission")
+            .match_query(Matcher::UrlEncoded("display".into(), "all".into()))
+            .match_header("authorization", "apikey token=secret")
+            .with_status(200)
+            .with_body(submission_json())
+            .create();
+
+        let provider = BioPortalMetadataProvider::new(&server.url(), "secret");
+        let metadata = provider.provide_metadata("ncit").unwrap();
+
+        mock.assert();
+        assert_eq!(metadata.ontology_id, "NCIT");
+        assert_eq!(metadata.version, "v2.1");
+        assert_eq!(
+            metadata.owl_file_location.as_deref(),
+            Some("https://data.bioontology.org/ontologies/NCIT/submissions/1/download")
+        );
+        assert!(metadata.obo_file_location.is_none());
+        assert_eq!(
+            metadata.title.as_deref(),
+            Some("National Cancer Institute Thesaurus")
+        );
+    }
+
+    #[test]
+    fn test_provide_metadata_falls_back_to_release_date() {
+        let submission = BioPortalSubmission {
+            version: None,
+            released: Some("2024-03-01T00:00:00-08:00".to_string()),
+            has_ontology_language: Some("OBO".to_string()),
+            ontology: None,
+            links: None,
+        };
+
+        let metadata =
+            BioPortalMetadataProvider::metadata_from_submission(submission, "ncit").unwrap();
+
+        assert_eq!(metadata.ontology_id, "ncit");
+        assert_eq!(metadata.version, "2024-03-01");
+    }
+
+    #[test]
+    fn test_provide_metadata_invalid_api_key() {
+        let mut server = Server::new();
+        let _mock = server
+            .mock("GET", "/ontologies/NCIT/latest_submission")
+            .match_query(Matcher::Any)
+            .with_status(401)
+            .create();
+
+        let provider = BioPortalMetadataProvider::new(&server.url(), "wrong");
+        let result = provider.provide_metadata("ncit");
+
+        match result.unwrap_err() {
+            OntologyRegistryError::ProvidingMetadata { reason } => {
+                assert!(reason.contains("API key"));
+            }
+            _ => panic!("Wrong error type"),
+        }
+    }
+
+    #[test]
+    fn test_provide_metadata_missing_api_key() {
+        let provider = BioPortalMetadataProvider::with_default_url("");
+
+        assert!(matches!(
+            provider.provide_metadata("ncit"),
+            Err(OntologyRegistryError::ProvidingMetadata { .. })
+        ));
+    }
+}
@@ -0,0 +1,141 @@
+use crate::error::OntologyRegistryError;
+use crate::ontology_metadata::OntologyMetadata;
+use crate::traits::OntologyMetadataProviding;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A metadata provider decorator that memoizes results of an inner provider per ontology id.
+///
+/// Metadata younger than `ttl` is served from memory. Older or missing entries are fetched from
+/// the inner provider and the cache is refreshed. Errors are never cached.
+///
+/// This is useful to avoid a network round trip on every `register`/`get`/`unregister`
+/// call that resolves `Version::Latest`.
+#[derive(Debug)]
+pub struct CachingMetadataProvider<MDP> {
+    inner: MDP,
+    ttl: Duration,
+    cache: Mutex<HashMap<String, (OntologyMetadata, Instant)>>,
+}
+
+impl<MDP: OntologyMetadataProviding> CachingMetadataProvider<MDP> {
+    pub fn new(inner: MDP, ttl: Duration) -> Self {
+        CachingMetadataProvider {
+            inner,
+            ttl,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<MDP: OntologyMetadataProviding> OntologyMetadataProviding for CachingMetadataProvider<MDP> {
+    fn provide_metadata(
+        &self,
+        ontology_id: &str,
+    ) -> Result<OntologyMetadata, OntologyRegistryError> {
+        {
+            let cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some((metadata, fetched_at)) = cache.get(ontology_id)
+                && fetched_at.elapsed() < self.ttl
+            {
+                return Ok(metadata.clone());
+            }
+        }
+
+        let metadata = self.inner.provide_metadata(ontology_id)?;
+
+        self.cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(ontology_id.to_string(), (metadata.clone(), Instant::now()));
+
+        Ok(metadata)
+    }
+
+    /// Forwards to the inner provider. The list is not cached.
+    fn list_available(&self) -> Result<Vec<String>, OntologyRegistryError> {
+        self.inner.list_available()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Debug, Default)]
+    struct CountingMetadataProvider {
+        calls: AtomicUsize,
+    }
+
+    impl OntologyMetadataProviding for CountingMetadataProvider {
+        fn provide_metadata(
+            &self,
+            ontology_id: &str,
+        ) -> Result<OntologyMetadata, OntologyRegistryError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(OntologyMetadata {
+                ontology_id: ontology_id.to_string(),
+                version: "2024-01-01".to_string(),
+                ..Default::default()
+            })
+        }
+    }
+
+    #[test]
+    fn test_inner_provider_called_once_within_ttl() {
+        let provider = CachingMetadataProvider::new(
+            CountingMetadataProvider::default(),
+            Duration::from_secs(60),
+        );
+
+        let first = provider.provide_metadata("hp").unwrap();
+        let second = provider.provide_metadata("hp").unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(provider.inner.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_cache_is_per_ontology_id() {
+        let provider = CachingMetadataProvider::new(
+            CountingMetadataProvider::default(),
+            Duration::from_secs(60),
+        );
+
+        provider.provide_metadata("hp").unwrap();
+        provider.provide_metadata("mondo").unwrap();
+        provider.provide_metadata("hp").unwrap();
+
+        assert_eq!(provider.inner.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_batch_loops_through_the_cache() {
+        let provider = CachingMetadataProvider::new(
+            CountingMetadataProvider::default(),
+            Duration::from_secs(60),
+        );
+
+        let results = provider.provide_metadata_batch(&["hp", "mondo", "hp"]);
+
+        let ids: Vec<_> = results
+            .iter()
+            .map(|(id, result)| (id.as_str(), result.as_ref().unwrap().ontology_id.as_str()))
+            .collect();
+        assert_eq!(ids, vec![("hp", "hp"), ("mondo", "mondo"), ("hp", "hp")]);
+        assert_eq!(provider.inner.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_expired_entries_are_refreshed() {
+        let provider =
+            CachingMetadataProvider::new(CountingMetadataProvider::default(), Duration::ZERO);
+
+        provider.provide_metadata("hp").unwrap();
+        provider.provide_metadata("hp").unwrap();
+
+        assert_eq!(provider.inner.calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,51 @@
+use crate::error::OntologyRegistryError;
+use std::io::{self, Read};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Wraps a download stream and fails the next read once `cancel_flag` is set.
+///
+/// The error is an `io::Error` wrapping `OntologyRegistryError::Cancelled`, which the registry
+/// unwraps again when the stream is copied to disk.
+pub(crate) struct CancellableReader<'a, R> {
+    inner: R,
+    cancel_flag: Option<&'a AtomicBool>,
+}
+
+impl<'a, R> CancellableReader<'a, R> {
+    pub(crate) fn new(inner: R, cancel_flag: Option<&'a AtomicBool>) -> Self {
+        CancellableReader { inner, cancel_flag }
+    }
+}
+
+impl<R: Read> Read for CancellableReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self
+            .cancel_flag
+            .is_some_and(|flag| flag.load(Ordering::Relaxed))
+        {
+            return Err(io::Error::other(OntologyRegistryError::Cancelled));
+        }
+        self.inner.read(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_through_until_cancelled() {
+        let cancel_flag = AtomicBool::new(false);
+        let mut reader = CancellableReader::new(&b"12345"[..], Some(&cancel_flag));
+        let mut buf = [0; 2];
+
+        assert_eq!(reader.read(&mut buf).unwrap(), 2);
+        cancel_flag.store(true, Ordering::Relaxed);
+        let err = reader.read(&mut buf).unwrap_err();
+
+        assert!(matches!(
+            OntologyRegistryError::from_stream_error(err),
+            OntologyRegistryError::Cancelled
+        ));
+    }
+}
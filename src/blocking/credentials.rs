@@ -0,0 +1,68 @@
+use log::warn;
+use reqwest::StatusCode;
+use reqwest::blocking::RequestBuilder;
+use std::fmt;
+
+/// Credentials an HTTP provider sends in the `Authorization` header of every request.
+///
+/// `Debug` redacts the secrets, so providers holding credentials can still derive it.
+#[derive(Clone)]
+pub(crate) enum Credentials {
+    Bearer(String),
+    Basic { username: String, password: String },
+}
+
+impl Credentials {
+    /// Adds the `Authorization` header to `request`, marked as sensitive.
+    pub(crate) fn apply(&self, request: RequestBuilder) -> RequestBuilder {
+        match self {
+            Credentials::Bearer(token) => request.bearer_auth(token),
+            Credentials::Basic { username, password } => {
+                request.basic_auth(username, Some(password))
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Credentials::Bearer(_) => f.write_str("Bearer(<redacted>)"),
+            Credentials::Basic { username, .. } => f
+                .debug_struct("Basic")
+                .field("username", username)
+                .field("password", &"<redacted>")
+                .finish(),
+        }
+    }
+}
+
+/// Warns that `url` answered `401 Unauthorized` or `403 Forbidden` to a request carrying
+/// `credentials`, which are then the likely cause. Does nothing without credentials or for any
+/// other status. The message never contains the credentials themselves.
+pub(crate) fn warn_if_rejected(credentials: Option<&Credentials>, url: &str, status: StatusCode) {
+    if credentials.is_some() && matches!(status, StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN) {
+        warn!(
+            "{url} rejected the request (HTTP {}); check the configured credentials",
+            status.as_u16()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_redacts_secrets() {
+        let bearer = Credentials::Bearer("secret".to_string());
+        let basic = Credentials::Basic {
+            username: "alice".to_string(),
+            password: "secret".to_string(),
+        };
+
+        assert!(!format!("{bearer:?}").contains("secret"));
+        assert!(!format!("{basic:?}").contains("secret"));
+        assert!(format!("{basic:?}").contains("alice"));
+    }
+}
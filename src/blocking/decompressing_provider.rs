@@ -0,0 +1,358 @@
+use crate::Version;
+use crate::cache_validators::{CacheValidators, ConditionalFetch};
+use crate::enums::FileType;
+use crate::error::OntologyRegistryError;
+use crate::ontology_metadata::OntologyMetadata;
+use crate::traits::OntologyProviding;
+use std::io::{self, Cursor, Read};
+
+/// Compression formats recognized by [`DecompressingProvider`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Compression {
+    Gzip,
+    Zstd,
+    Bzip2,
+}
+
+impl Compression {
+    /// Number of leading bytes needed to recognize every format.
+    const MAGIC_LENGTH: usize = 4;
+
+    fn from_magic(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(&[0x1f, 0x8b]) {
+            Some(Compression::Gzip)
+        } else if bytes.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Some(Compression::Zstd)
+        } else if bytes.starts_with(b"BZh") {
+            Some(Compression::Bzip2)
+        } else {
+            None
+        }
+    }
+
+    fn from_file_name(file_name: &str) -> Option<Self> {
+        if file_name.ends_with(".gz") {
+            Some(Compression::Gzip)
+        } else if file_name.ends_with(".zst") {
+            Some(Compression::Zstd)
+        } else if file_name.ends_with(".bz2") {
+            Some(Compression::Bzip2)
+        } else {
+            None
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+            Compression::Bzip2 => "bzip2",
+        }
+    }
+
+    /// Wraps `reader` in a decoder, or fails if the format's feature is not enabled.
+    fn decoder<R: Read>(
+        self,
+        reader: R,
+        file_name: &str,
+    ) -> Result<Decoder<R>, OntologyRegistryError> {
+        let unsupported = || OntologyRegistryError::ProvidingOntology {
+            reason: format!(
+                "{file_name} is {} compressed, but the `{}` feature is not enabled",
+                self.name(),
+                self.name()
+            ),
+        };
+
+        match self {
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => Ok(Decoder::Gzip(flate2::read::MultiGzDecoder::new(reader))),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => Ok(Decoder::Zstd(zstd::stream::read::Decoder::new(reader)?)),
+            #[cfg(feature = "bzip2")]
+            Compression::Bzip2 => Ok(Decoder::Bzip2(bzip2::read::MultiBzDecoder::new(reader))),
+            #[allow(unreachable_patterns)]
+            _ => {
+                drop(reader);
+                Err(unsupported())
+            }
+        }
+    }
+}
+
+/// The content of a provider's reader, decoded if it is compressed.
+///
+/// An enum rather than a boxed reader, so it borrows exactly what the wrapped reader borrows.
+enum Decoder<R: Read> {
+    Plain(R),
+    #[cfg(feature = "gzip")]
+    Gzip(flate2::read::MultiGzDecoder<R>),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::stream::read::Decoder<'static, io::BufReader<R>>),
+    #[cfg(feature = "bzip2")]
+    Bzip2(bzip2::read::MultiBzDecoder<R>),
+}
+
+impl<R: Read> Read for Decoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Decoder::Plain(reader) => reader.read(buf),
+            #[cfg(feature = "gzip")]
+            Decoder::Gzip(reader) => reader.read(buf),
+            #[cfg(feature = "zstd")]
+            Decoder::Zstd(reader) => reader.read(buf),
+            #[cfg(feature = "bzip2")]
+            Decoder::Bzip2(reader) => reader.read(buf),
+        }
+    }
+}
+
+/// The reader returned by [`DecompressingProvider`] for content read from `R`.
+type Decompressed<R> = DecodeErrorReader<Decoder<io::Chain<Cursor<Vec<u8>>, R>>>;
+
+/// Wraps an ontology provider and transparently decompresses what it returns.
+///
+/// The format is recognized by the leading magic bytes of the content, so a provider may serve
+/// compressed bytes under a plain file name such as `hp.obo`. Gzip, zstd and bzip2 are supported
+/// behind the `gzip`, `zstd` and `bzip2` features. Content without a known signature is passed
+/// through unchanged, unless the requested file name ends in `.gz`, `.zst` or `.bz2`, in which
+/// case it is rejected as not being what was asked for.
+///
+/// Corrupt compressed data surfaces while the stream is read, as
+/// `OntologyRegistryError::ProvidingOntology`.
+#[derive(Debug, Clone)]
+pub struct DecompressingProvider<P> {
+    inner: P,
+}
+
+impl<P> DecompressingProvider<P> {
+    pub fn new(inner: P) -> Self {
+        DecompressingProvider { inner }
+    }
+
+    /// Returns the wrapped provider.
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+
+    /// Detects the compression of `reader` and returns a reader of the decompressed content.
+    fn decompress<R: Read>(
+        mut reader: R,
+        file_name: &str,
+    ) -> Result<Decompressed<R>, OntologyRegistryError> {
+        let mut magic = Vec::with_capacity(Compression::MAGIC_LENGTH);
+        reader
+            .by_ref()
+            .take(Compression::MAGIC_LENGTH as u64)
+            .read_to_end(&mut magic)
+            .map_err(OntologyRegistryError::from_stream_error)?;
+
+        let compression = Compression::from_magic(&magic);
+        let reader = Cursor::new(magic).chain(reader);
+
+        match (compression, Compression::from_file_name(file_name)) {
+            (Some(compression), _) => Ok(DecodeErrorReader {
+                inner: compression.decoder(reader, file_name)?,
+                compression: Some(compression),
+                file_name: file_name.to_string(),
+            }),
+            (None, Some(expected)) => Err(OntologyRegistryError::ProvidingOntology {
+                reason: format!(
+                    "{file_name} is not {} compressed as its name suggests",
+                    expected.name()
+                ),
+            }),
+            (None, None) => Ok(DecodeErrorReader {
+                inner: Decoder::Plain(reader),
+                compression: None,
+                file_name: file_name.to_string(),
+            }),
+        }
+    }
+}
+
+impl<P: OntologyProviding> OntologyProviding for DecompressingProvider<P> {
+    fn provide_ontology(
+        &self,
+        ontology_id: &str,
+        file_name: &str,
+        version: &Version,
+    ) -> Result<impl Read, OntologyRegistryError> {
+        let reader = self
+            .inner
+            .provide_ontology(ontology_id, file_name, version)?;
+        Self::decompress(reader, file_name)
+    }
+
+    fn file_name_for(
+        &self,
+        ontology_id: &str,
+        file_type: &FileType,
+        metadata: Option<&OntologyMetadata>,
+    ) -> String {
+        self.inner.file_name_for(ontology_id, file_type, metadata)
+    }
+
+    fn provide_latest(
+        &self,
+        ontology_id: &str,
+        file_name: &str,
+    ) -> Result<(String, impl Read), OntologyRegistryError> {
+        let (version, reader) = self.inner.provide_latest(ontology_id, file_name)?;
+        Ok((version, Self::decompress(reader, file_name)?))
+    }
+
+    fn latest_version(
+        &self,
+        ontology_id: &str,
+        file_name: &str,
+    ) -> Result<String, OntologyRegistryError> {
+        self.inner.latest_version(ontology_id, file_name)
+    }
+
+    /// Forwards the conditional request to the wrapped provider and decompresses a modified
+    /// result. Its content length is dropped, as it describes the compressed size.
+    fn provide_ontology_if_modified(
+        &self,
+        ontology_id: &str,
+        file_name: &str,
+        version: &Version,
+        validators: &CacheValidators,
+    ) -> Result<ConditionalFetch<impl Read>, OntologyRegistryError> {
+        match self.inner.provide_ontology_if_modified(
+            ontology_id,
+            file_name,
+            version,
+            validators,
+        )? {
+            ConditionalFetch::Modified {
+                reader, validators, ..
+            } => Ok(ConditionalFetch::Modified {
+                reader: Self::decompress(reader, file_name)?,
+                validators,
+                content_length: None,
+            }),
+            ConditionalFetch::NotModified => Ok(ConditionalFetch::NotModified),
+        }
+    }
+}
+
+/// Reports decoder failures as `OntologyRegistryError::ProvidingOntology` wrapped in the
+/// `io::Error`, which the registry unwraps again when the stream is copied to disk. Errors of
+/// uncompressed content are passed through.
+struct DecodeErrorReader<R> {
+    inner: R,
+    compression: Option<Compression>,
+    file_name: String,
+}
+
+impl<R: Read> Read for DecodeErrorReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf).map_err(|err| {
+            let Some(compression) = self.compression else {
+                return err;
+            };
+            let is_registry_error = err
+                .get_ref()
+                .is_some_and(|inner| inner.is::<OntologyRegistryError>());
+            if is_registry_error
+                || !matches!(
+                    err.kind(),
+                    io::ErrorKind::InvalidData
+                        | io::ErrorKind::InvalidInput
+                        | io::ErrorKind::UnexpectedEof
+                )
+            {
+                return err;
+            }
+            io::Error::other(OntologyRegistryError::ProvidingOntology {
+                reason: format!(
+                    "Invalid {} data in {}: {err}",
+                    compression.name(),
+                    self.file_name
+                ),
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct MockProvider {
+        content: Vec<u8>,
+    }
+
+    impl OntologyProviding for MockProvider {
+        fn provide_ontology(
+            &self,
+            _ontology_id: &str,
+            _file_name: &str,
+            _version: &Version,
+        ) -> Result<impl Read, OntologyRegistryError> {
+            Ok(&self.content[..])
+        }
+    }
+
+    fn provide(content: Vec<u8>, file_name: &str) -> Result<String, OntologyRegistryError> {
+        let provider = DecompressingProvider::new(MockProvider { content });
+        let mut reader = provider.provide_ontology("hp", file_name, &Version::Latest)?;
+        let mut text = String::new();
+        reader
+            .read_to_string(&mut text)
+            .map_err(OntologyRegistryError::from_stream_error)?;
+        Ok(text)
+    }
+
+    #[cfg(feature = "gzip")]
+    fn gzip(content: &[u8]) -> Vec<u8> {
+        use std::io::Write;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(content).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "gzip")]
+    fn test_decompresses_gzip_by_magic_bytes() {
+        let compressed = gzip(b"format-version: 1.2");
+
+        assert_eq!(
+            provide(compressed.clone(), "hp.obo").unwrap(),
+            "format-version: 1.2"
+        );
+        assert_eq!(
+            provide(compressed, "hp.obo.gz").unwrap(),
+            "format-version: 1.2"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "gzip")]
+    fn test_rejects_corrupt_gzip() {
+        let mut compressed = gzip(b"format-version: 1.2");
+        compressed.truncate(12);
+
+        assert!(matches!(
+            provide(compressed, "hp.obo"),
+            Err(OntologyRegistryError::ProvidingOntology { .. })
+        ));
+    }
+
+    #[test]
+    fn test_passes_uncompressed_content_through() {
+        assert_eq!(provide(b"BZ".to_vec(), "hp.obo").unwrap(), "BZ");
+        assert_eq!(provide(Vec::new(), "hp.obo").unwrap(), "");
+    }
+
+    #[test]
+    fn test_rejects_uncompressed_content_with_compressed_name() {
+        assert!(matches!(
+            provide(b"format-version: 1.2".to_vec(), "hp.obo.zst"),
+            Err(OntologyRegistryError::ProvidingOntology { .. })
+        ));
+    }
+}
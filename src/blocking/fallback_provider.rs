@@ -0,0 +1,340 @@
+use crate::Version;
+use crate::error::OntologyRegistryError;
+use crate::ontology_metadata::OntologyMetadata;
+use crate::traits::{DynOntologyProviding, OntologyMetadataProviding, OntologyProviding};
+use std::fmt::{Debug, Formatter};
+use std::io::Read;
+use std::marker::PhantomData;
+
+/// An ontology provider that tries several providers in order and returns the first success.
+///
+/// If every provider fails, the returned `ProvidingOntology` error lists each underlying reason.
+#[derive(Default)]
+pub struct FallbackOntologyProvider {
+    providers: Vec<Box<dyn DynOntologyProviding>>,
+}
+
+impl FallbackOntologyProvider {
+    pub fn new(providers: Vec<Box<dyn DynOntologyProviding>>) -> Self {
+        FallbackOntologyProvider { providers }
+    }
+
+    /// Appends a provider that is tried after all previously added ones.
+    pub fn with_provider(mut self, provider: impl OntologyProviding + 'static) -> Self {
+        self.providers.push(Box::new(provider));
+        self
+    }
+}
+
+impl Debug for FallbackOntologyProvider {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FallbackOntologyProvider")
+            .field("providers", &self.providers.len())
+            .finish()
+    }
+}
+
+impl OntologyProviding for FallbackOntologyProvider {
+    fn provide_ontology<'s>(
+        &'s self,
+        ontology_id: &str,
+        file_name: &str,
+        version: &Version,
+    ) -> Result<impl Read, OntologyRegistryError> {
+        let mut reasons = Vec::new();
+
+        for provider in &self.providers {
+            match provider.provide_ontology_boxed(ontology_id, file_name, version) {
+                Ok(reader) => {
+                    return Ok(FallbackReader::<'s, '_, '_, '_>::new(
+                        reader,
+                        ontology_id,
+                        file_name,
+                        version,
+                    ));
+                }
+                Err(err) => reasons.push(err.to_string()),
+            }
+        }
+
+        Err(OntologyRegistryError::ProvidingOntology {
+            reason: aggregate_reasons(ontology_id, &reasons),
+        })
+    }
+}
+
+/// The boxed reader of a provider of a [`FallbackOntologyProvider`].
+///
+/// The reader may borrow from the provider and from each argument, so its lifetime is their
+/// intersection, which the `impl Read` returned by `provide_ontology` cannot name. This type
+/// names each of them instead and keeps the reader from outliving any.
+struct FallbackReader<'s, 'i, 'f, 'v> {
+    reader: Box<dyn Read + 's>,
+    borrows: PhantomData<(&'i str, &'f str, &'v Version)>,
+}
+
+impl<'s, 'i, 'f, 'v> FallbackReader<'s, 'i, 'f, 'v> {
+    fn new<'r>(
+        reader: Box<dyn Read + 'r>,
+        _ontology_id: &'i str,
+        _file_name: &'f str,
+        _version: &'v Version,
+    ) -> Self
+    where
+        's: 'r,
+        'i: 'r,
+        'f: 'r,
+        'v: 'r,
+    {
+        // SAFETY: only the lifetime bound of the trait object changes. `'r` is outlived by all
+        // of `'s`, `'i`, `'f` and `'v`, and the reader can only be used or dropped through
+        // `Self`, which borrows every one of them.
+        let reader =
+            unsafe { std::mem::transmute::<Box<dyn Read + 'r>, Box<dyn Read + 's>>(reader) };
+        FallbackReader {
+            reader,
+            borrows: PhantomData,
+        }
+    }
+}
+
+impl Read for FallbackReader<'_, '_, '_, '_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.reader.read(buf)
+    }
+}
+
+impl Drop for FallbackReader<'_, '_, '_, '_> {
+    // Requires every borrowed lifetime to be alive when the reader is dropped.
+    fn drop(&mut self) {}
+}
+
+/// A metadata provider that tries several providers in order and returns the first success.
+///
+/// If every provider fails, the returned `ProvidingMetadata` error lists each underlying reason.
+#[derive(Default)]
+pub struct FallbackMetadataProvider {
+    providers: Vec<Box<dyn OntologyMetadataProviding>>,
+}
+
+impl FallbackMetadataProvider {
+    pub fn new(providers: Vec<Box<dyn OntologyMetadataProviding>>) -> Self {
+        FallbackMetadataProvider { providers }
+    }
+
+    /// Appends a provider that is tried after all previously added ones.
+    pub fn with_provider(mut self, provider: impl OntologyMetadataProviding + 'static) -> Self {
+        self.providers.push(Box::new(provider));
+        self
+    }
+}
+
+impl Debug for FallbackMetadataProvider {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FallbackMetadataProvider")
+            .field("providers", &self.providers.len())
+            .finish()
+    }
+}
+
+impl OntologyMetadataProviding for FallbackMetadataProvider {
+    fn provide_metadata(
+        &self,
+        ontology_id: &str,
+    ) -> Result<OntologyMetadata, OntologyRegistryError> {
+        let mut reasons = Vec::new();
+
+        for provider in &self.providers {
+            match provider.provide_metadata(ontology_id) {
+                Ok(metadata) => return Ok(metadata),
+                Err(err) => reasons.push(err.to_string()),
+            }
+        }
+
+        Err(OntologyRegistryError::ProvidingMetadata {
+            reason: aggregate_reasons(ontology_id, &reasons),
+        })
+    }
+
+    /// Returns the list of the first provider that can list its ontologies.
+    fn list_available(&self) -> Result<Vec<String>, OntologyRegistryError> {
+        let mut reasons = Vec::new();
+
+        for provider in &self.providers {
+            match provider.list_available() {
+                Ok(ontology_ids) => return Ok(ontology_ids),
+                Err(err) => reasons.push(err.to_string()),
+            }
+        }
+
+        Err(OntologyRegistryError::ProvidingMetadata {
+            reason: aggregate_reasons("the list of available ontologies", &reasons),
+        })
+    }
+}
+
+fn aggregate_reasons(ontology_id: &str, reasons: &[String]) -> String {
+    if reasons.is_empty() {
+        return format!("No providers configured for {ontology_id}");
+    }
+
+    let listed = reasons
+        .iter()
+        .enumerate()
+        .map(|(i, reason)| format!("[{}] {}", i + 1, reason))
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    format!(
+        "All {} providers failed for {}: {}",
+        reasons.len(),
+        ontology_id,
+        listed
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    struct FailingProvider(&'static str);
+
+    impl OntologyProviding for FailingProvider {
+        fn provide_ontology(
+            &self,
+            _ontology_id: &str,
+            _file_name: &str,
+            _version: &Version,
+        ) -> Result<impl Read, OntologyRegistryError> {
+            Err::<Cursor<Vec<u8>>, _>(OntologyRegistryError::ProvidingOntology {
+                reason: self.0.to_string(),
+            })
+        }
+    }
+
+    impl OntologyMetadataProviding for FailingProvider {
+        fn provide_metadata(
+            &self,
+            _ontology_id: &str,
+        ) -> Result<OntologyMetadata, OntologyRegistryError> {
+            Err(OntologyRegistryError::ProvidingMetadata {
+                reason: self.0.to_string(),
+            })
+        }
+    }
+
+    struct SucceedingProvider;
+
+    impl OntologyProviding for SucceedingProvider {
+        fn provide_ontology(
+            &self,
+            _ontology_id: &str,
+            _file_name: &str,
+            _version: &Version,
+        ) -> Result<impl Read, OntologyRegistryError> {
+            Ok(Cursor::new(b"content".to_vec()))
+        }
+    }
+
+    impl OntologyMetadataProviding for SucceedingProvider {
+        fn provide_metadata(
+            &self,
+            ontology_id: &str,
+        ) -> Result<OntologyMetadata, OntologyRegistryError> {
+            Ok(OntologyMetadata {
+                ontology_id: ontology_id.to_string(),
+                version: "1.0".to_string(),
+                ..Default::default()
+            })
+        }
+    }
+
+    #[test]
+    fn test_ontology_provider_falls_back_to_next() {
+        let provider = FallbackOntologyProvider::default()
+            .with_provider(FailingProvider("mirror down"))
+            .with_provider(SucceedingProvider);
+
+        let mut reader = provider
+            .provide_ontology("go", "go.owl", &Version::Latest)
+            .unwrap();
+        let mut content = String::new();
+        reader.read_to_string(&mut content).unwrap();
+
+        assert_eq!(content, "content");
+    }
+
+    struct EchoingProvider;
+
+    impl OntologyProviding for EchoingProvider {
+        fn provide_ontology(
+            &self,
+            _ontology_id: &str,
+            file_name: &str,
+            _version: &Version,
+        ) -> Result<impl Read, OntologyRegistryError> {
+            Ok(file_name.as_bytes())
+        }
+    }
+
+    #[test]
+    fn test_ontology_provider_returns_reader_borrowing_arguments() {
+        let provider = FallbackOntologyProvider::default()
+            .with_provider(FailingProvider("mirror down"))
+            .with_provider(EchoingProvider);
+        let file_name = String::from("go.owl");
+
+        let mut reader = provider
+            .provide_ontology("go", &file_name, &Version::Latest)
+            .unwrap();
+        let mut content = String::new();
+        reader.read_to_string(&mut content).unwrap();
+
+        assert_eq!(content, "go.owl");
+    }
+
+    #[test]
+    fn test_ontology_provider_aggregates_errors() {
+        let provider = FallbackOntologyProvider::new(vec![
+            Box::new(FailingProvider("first down")),
+            Box::new(FailingProvider("second down")),
+        ]);
+
+        let result = provider.provide_ontology("go", "go.owl", &Version::Latest);
+
+        match result {
+            Err(OntologyRegistryError::ProvidingOntology { reason }) => {
+                assert!(reason.contains("first down"));
+                assert!(reason.contains("second down"));
+            }
+            _ => panic!("Wrong error type returned"),
+        }
+    }
+
+    #[test]
+    fn test_metadata_provider_falls_back_to_next() {
+        let provider = FallbackMetadataProvider::default()
+            .with_provider(FailingProvider("registry down"))
+            .with_provider(SucceedingProvider);
+
+        let metadata = provider.provide_metadata("go").unwrap();
+
+        assert_eq!(metadata.version, "1.0");
+    }
+
+    #[test]
+    fn test_metadata_provider_aggregates_errors() {
+        let provider = FallbackMetadataProvider::default()
+            .with_provider(FailingProvider("first down"))
+            .with_provider(FailingProvider("second down"));
+
+        match provider.provide_metadata("go") {
+            Err(OntologyRegistryError::ProvidingMetadata { reason }) => {
+                assert!(reason.contains("first down"));
+                assert!(reason.contains("second down"));
+            }
+            _ => panic!("Wrong error type"),
+        }
+    }
+}
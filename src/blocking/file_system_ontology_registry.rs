@@ -1,13 +1,64 @@
 use crate::RegistryKey;
-use crate::enums::Version;
+use crate::blocking::cancellable_reader::CancellableReader;
+use crate::blocking::file_system_ontology_registry_builder::FileSystemOntologyRegistryBuilder;
+use crate::blocking::progress_reader::ProgressReader;
+use crate::blocking::registry_manifest::{MANIFEST_FILE_NAME, RegistryManifest};
+#[cfg(feature = "http")]
+use crate::blocking::url_ontology_provider::UrlOntologyProvider;
+use crate::cache_validators::{CacheValidators, ConditionalFetch};
+use crate::enums::{FileType, RegistryLayout, Version};
 use crate::error::OntologyRegistryError;
-use crate::traits::{OntologyMetadataProviding, OntologyProviding, OntologyRegistration};
+use crate::metrics;
+use crate::obo_header::OboHeader;
+use crate::ontology_id::OntologyId;
+use crate::ontology_metadata::OntologyMetadata;
+use crate::traits::{
+    FileNamingStrategy, OntologyMetadataProviding, OntologyProviding, OntologyRegistration,
+    RegistryObserver,
+};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs::File;
-use std::io::{Read, Write};
-use std::path::PathBuf;
-use std::sync::Mutex;
-use std::time::{SystemTime, UNIX_EPOCH};
-use std::{fs, process};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::{fs, iter, process, thread};
+
+/// Default upper bound for registry file names, matching the limit of most filesystems.
+pub const DEFAULT_MAX_FILE_NAME_LENGTH: usize = 255;
+
+/// Name of the file locked by `with_file_lock` inside a registry root. Hidden, so `list` skips it.
+const LOCK_FILE_NAME: &str = ".lock";
+
+/// Log target of cache hits and unchanged refreshes.
+const CACHE_LOG_TARGET: &str = "ontology_registry::cache";
+/// Log target of started and finished downloads.
+const DOWNLOAD_LOG_TARGET: &str = "ontology_registry::download";
+/// Log target of `Version::Latest` resolutions.
+const RESOLVE_LOG_TARGET: &str = "ontology_registry::resolve";
+/// Log target of files evicted to stay within `max_bytes`.
+const EVICT_LOG_TARGET: &str = "ontology_registry::evict";
+/// Log target of failed registrations.
+const ERROR_LOG_TARGET: &str = "ontology_registry::error";
+
+/// Number of worker threads `register_many` uses at most.
+const MAX_CONCURRENT_REGISTRATIONS: usize = 4;
+
+/// Signature of the closures accepted by `with_validator`.
+type ValidateFn = dyn Fn(&FileType, &mut dyn Read) -> Result<(), String> + Send + Sync;
+
+/// Checks downloaded content before it is committed, see `with_validator`.
+struct ContentValidator(Box<ValidateFn>);
+
+impl std::fmt::Debug for ContentValidator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ContentValidator")
+    }
+}
 
 #[derive(Debug)]
 /// A registry implementation that manages ontologies as files on the local filesystem.
@@ -18,11 +69,33 @@ use std::{fs, process};
 /// # Features
 ///
 /// * **Thread Safety:** Registration is guarded by a mutex to prevent race conditions when
-///   multiple threads attempt to download/write the same ontology simultaneously.
+///   multiple threads attempt to download/write the same ontology simultaneously. With
+///   `with_file_lock`, an advisory file lock extends this to several processes sharing a
+///   registry directory.
 /// * **Atomic Writes:** Files are written to a temporary location first and then renamed.
 ///   This ensures that the registry never contains partially written or corrupted ontology files.
 /// * **Version Resolution:** Supports resolving `Version::Latest` dynamically via the
-///   `OntologyMetadataProvider`.
+///   `OntologyMetadataProvider`, or from the files already registered with `with_offline`.
+/// * **Long Keys:** File names longer than `max_file_name_length` bytes are replaced by a
+///   deterministic hash. The original key is recorded in a hidden manifest so `list` still
+///   reports it.
+/// * **Layouts:** Files are stored flat in `registry_path` by default, or in one subdirectory
+///   per ontology with [`RegistryLayout::Nested`]. Any other scheme can be plugged in as a
+///   [`FileNamingStrategy`].
+/// * **Size Budget:** With `with_max_bytes`, the least recently used files are evicted after
+///   each download so the registry stays within a fixed size.
+/// * **Logging:** Cache hits, downloads, version resolution, evictions and failed registrations
+///   are logged through the `log` crate under the targets `ontology_registry::cache`,
+///   `::download`, `::resolve`, `::evict` and `::error`.
+/// * **Layers:** With `with_read_paths`, lookups fall back to further read-only registry
+///   directories (e.g. a shared system cache), while downloads still go to `registry_path`.
+/// * **Id Validation:** Every ontology id, after normalization, must be a valid
+///   [`OntologyId`]. Otherwise `register`, `unregister`, `register_bytes` and the other
+///   operations fail with `OntologyRegistryError::InvalidOntologyId`, and `get` returns `None`,
+///   before any provider or file is touched.
+///   The entry points taking an id on its own, `register_bytes`, `import_file`,
+///   `get_or_register` and `unregister_all`, accept anything that converts into an
+///   `OntologyId` and use the lowercased id.
 ///
 /// # Type Parameters
 ///
@@ -36,555 +109,6375 @@ pub struct FileSystemOntologyRegistry<MDP, OP> {
     /// The provider used to resolve ontology metadata (versions).
     metadata_provider: MDP,
     /// A lock used to ensure thread-safe file writing operations.
+    ///
+    /// It guards no data, and files are only moved into place once complete, so a poisoned
+    /// lock is always recovered.
     write_lock: Mutex<()>,
+    /// File names longer than this (in bytes) are replaced by a hash.
+    max_file_name_length: usize,
+    /// Upper bound for the total size of the ontology files in a registry root.
+    max_bytes: Option<u64>,
+    /// Files older than this are refreshed by `get_fresh` before being served.
+    refresh_after: Option<Duration>,
+    /// Whether `register` maintains a `<id>@latest<ext>` alias per ontology and file type.
+    latest_alias: bool,
+    /// How files are named and arranged below a registry root.
+    naming_strategy: Box<dyn FileNamingStrategy>,
+    /// Whether modifications of a registry root also take an OS-level advisory lock.
+    file_lock: bool,
+    /// Whether versions are resolved from local files only, without calling any provider.
+    offline: bool,
+    /// Further registry roots searched, in order, after `registry_path`. Never written to.
+    read_paths: Vec<PathBuf>,
+    /// Applied to every ontology id before it is resolved, stored or passed to a provider.
+    id_normalizer: Option<fn(&str) -> String>,
+    /// Whether `Version::Latest` of an obsolete ontology resolves to its replacement.
+    follow_replaced_by: bool,
+    /// Whether `Version::Latest` is downloaded through `OntologyProviding::provide_latest`.
+    provider_latest: bool,
+    /// Downloads the locations used by `register_from_metadata`.
+    #[cfg(feature = "http")]
+    url_provider: UrlOntologyProvider,
+    /// Once set, downloads abort and no further downloads start.
+    cancel_flag: Option<Arc<AtomicBool>>,
+    /// Run against every downloaded file before it is moved into place.
+    validator: Option<ContentValidator>,
+    /// Whether files are stored gzip-compressed, with a `.gz` suffix.
+    compressed_storage: bool,
+    /// Whether a downloaded file identical to one already registered is hard-linked to it.
+    deduplicate: bool,
+    /// Notified of downloads, cache hits, evictions and errors.
+    observer: Option<Box<dyn RegistryObserver>>,
 }
 
 impl<MDP, OP> FileSystemOntologyRegistry<MDP, OP> {
-    fn create_temp_dir(&self) -> Result<PathBuf, OntologyRegistryError> {
+    /// Creates an empty temporary directory in `root` that no other writer uses.
+    ///
+    /// The name combines a timestamp, the process id and a per-process sequence number, so
+    /// concurrent writers in one or several processes never share a directory. Creation fails
+    /// rather than reusing a directory that exists already.
+    fn create_temp_dir(&self, root: &Path) -> Result<PathBuf, OntologyRegistryError> {
+        static SEQUENCE: AtomicUsize = AtomicUsize::new(0);
+
         let timestamp = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .map(|d| d.as_nanos())
             .unwrap_or(0);
 
         let pid = process::id();
-        let dir_name = format!("tmp_{}_{}", timestamp, pid);
-        let tmp_dir = self.registry_path.join(dir_name);
-        fs::create_dir_all(&tmp_dir).map_err(|_| OntologyRegistryError::NoRegistry)?;
+        let sequence = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        // Hidden, so it is never mistaken for an ontology file or directory.
+        let dir_name = format!(".tmp_{}_{}_{}", timestamp, pid, sequence);
+        let tmp_dir = root.join(dir_name);
+        fs::create_dir_all(root).map_err(|_| OntologyRegistryError::NoRegistry)?;
+        fs::create_dir(&tmp_dir).map_err(|err| OntologyRegistryError::io_at(&tmp_dir, err))?;
 
         Ok(tmp_dir)
     }
-}
 
-impl<MDP: OntologyMetadataProviding, OP: OntologyProviding> FileSystemOntologyRegistry<MDP, OP> {
-    /// Creates a new `FileSystemOntologyRegistry`.
-    ///
-    /// # Arguments
+    /// Takes the advisory lock on the lock file of `root` if `file_lock` is enabled, creating
+    /// `root` if needed. The lock is released when the returned file is dropped.
     ///
-    /// * `registry_path` - The directory path where ontologies will be saved.
-    /// * `metadata_provider` - The service to query for ontology version metadata.
-    /// * `ontology_provider` - The service to download ontology content from.
-    pub fn new(registry_path: PathBuf, metadata_provider: MDP, ontology_provider: OP) -> Self {
-        FileSystemOntologyRegistry {
-            registry_path,
-            metadata_provider,
-            ontology_provider,
-            write_lock: Mutex::new(()),
+    /// Callers must hold `write_lock` first, so threads of one process queue on the mutex.
+    fn lock_file(&self, root: &Path) -> Result<Option<File>, OntologyRegistryError> {
+        if !self.file_lock {
+            return Ok(None);
         }
+
+        fs::create_dir_all(root).map_err(|_| OntologyRegistryError::NoRegistry)?;
+        let lock_path = root.join(LOCK_FILE_NAME);
+        let lock_file = File::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .map_err(|err| OntologyRegistryError::io_at(&lock_path, err))?;
+        lock_file
+            .lock()
+            .map_err(|err| OntologyRegistryError::io_at(&lock_path, err))?;
+        Ok(Some(lock_file))
     }
-    fn resolve_version(
-        &self,
-        ontology_id: &str,
-        version: &Version,
-    ) -> Result<String, OntologyRegistryError> {
-        match version {
-            Version::Latest => {
-                let meta_data = self.metadata_provider.provide_metadata(ontology_id)?;
-                Ok(meta_data.version)
-            }
-            Version::Declared(v) => Ok(v.to_string()),
-        }
+
+    /// Returns the registry roots searched for existing files: `registry_path` first, then the
+    /// read paths in order.
+    fn search_roots(&self) -> impl Iterator<Item = &Path> {
+        iter::once(self.registry_path.as_path()).chain(self.read_paths.iter().map(PathBuf::as_path))
     }
-}
 
-impl<MDP: OntologyMetadataProviding, OP: OntologyProviding> OntologyRegistration
-    for FileSystemOntologyRegistry<MDP, OP>
-{
-    /// Registers an ontology by downloading it and saving it to the local filesystem.
-    ///
-    /// # Behavior
-    ///
-    /// 1.  Resolves the version (fetching metadata if `Latest` is requested).
-    /// 2.  Checks if the file already exists locally. If so, returns the path immediately.
-    /// 3.  Fetches the content from the `OntologyProvider`.
-    /// 4.  **Atomic Write:** Writes content to a `.tmp` file, then renames it to the final destination.
-    ///     This prevents other threads/processes from reading incomplete files.
-    ///
-    /// # Errors
+    /// Returns the first file stored as `file_name` in any of the search roots.
+    fn find_file(&self, file_name: &str) -> Option<PathBuf> {
+        self.search_roots()
+            .find_map(|root| Self::stored_path(root, file_name))
+    }
+
+    /// Returns the path of the file stored as `file_name` below `root`, if there is one.
     ///
-    /// Returns `OntologyRegistryError` if:
-    /// * The metadata cannot be resolved.
-    /// * The registry directory cannot be created.
-    /// * The ontology provider fails to return data.
-    /// * File I/O operations (creation, writing, renaming) fail.
-    fn register(&self, registry_key: RegistryKey) -> Result<File, OntologyRegistryError> {
-        if !self.registry_path.exists() {
-            fs::create_dir_all(&self.registry_path)
-                .map_err(|_| OntologyRegistryError::NoRegistry)?;
+    /// File names are lowercased, but earlier versions kept the casing of the id, e.g.
+    /// `HP@1.0.obo`. Such a file is found by comparing each path segment case-insensitively,
+    /// so it is served the same way on case-sensitive and case-insensitive file systems.
+    fn stored_path(root: &Path, file_name: &str) -> Option<PathBuf> {
+        let path = root.join(file_name);
+        if path.is_file() {
+            return Some(path);
         }
 
-        let mut out_path = self.registry_path.clone();
+        let mut path = root.to_path_buf();
+        for segment in file_name.split('/') {
+            let exact = path.join(segment);
+            path = if exact.exists() {
+                exact
+            } else {
+                fs::read_dir(&path)
+                    .ok()?
+                    .flatten()
+                    .find(|entry| {
+                        entry
+                            .file_name()
+                            .to_str()
+                            .is_some_and(|name| name.eq_ignore_ascii_case(segment))
+                    })?
+                    .path()
+            };
+        }
+        path.is_file().then_some(path)
+    }
 
-        let resolved_version = Version::Declared(
-            self.resolve_version(registry_key.ontology_id(), registry_key.version())?,
-        );
+    /// Returns the path, relative to a registry root, under which `registry_key` is stored.
+    ///
+    /// Path segments are separated by `/`. The file name below the strategy's directories falls
+    /// back to a hash of itself if it exceeds `max_file_name_length`.
+    fn file_name(&self, registry_key: &RegistryKey) -> String {
+        let unhashed_file_name = self.unhashed_file_name(registry_key);
+        let (directory, file_name) = self.split_file_name(&unhashed_file_name);
+        if file_name.len() <= self.max_file_name_length {
+            return unhashed_file_name;
+        }
 
-        let resolved_registry_key = RegistryKey::new(
-            registry_key.ontology_id(),
-            resolved_version,
-            registry_key.file_type(),
+        let digest = Sha256::digest(file_name.as_bytes());
+        let hashed_file_name = format!(
+            "{}{}{}",
+            hex(&digest[..16]),
+            registry_key.file_type().as_file_ending(),
+            self.storage_suffix()
         );
+        match directory {
+            Some(directory) => format!("{directory}/{hashed_file_name}"),
+            None => hashed_file_name,
+        }
+    }
 
-        let registry_file_name = resolved_registry_key.as_file_name();
-        out_path.push(registry_file_name.clone());
-
-        if out_path.exists() {
-            return File::open(&out_path).map_err(|e| OntologyRegistryError::UnableToRegister {
-                reason: format!(
-                    "Unable to open existing file '{}': {}",
-                    out_path.display(),
-                    e
-                ),
+    /// Returns `file_name(registry_key)`, failing if it would not end up in its place below a
+    /// registry root.
+    ///
+    /// Ids and versions come from callers and metadata providers, so a version like `../evil`
+    /// or `2024/01` must neither escape the root nor add directories the naming strategy does
+    /// not expect.
+    fn checked_file_name(
+        &self,
+        registry_key: &RegistryKey,
+    ) -> Result<String, OntologyRegistryError> {
+        let file_name = self.file_name(registry_key);
+        let segments: Vec<&str> = file_name.split('/').collect();
+        let is_safe = segments.len() == self.naming_strategy.directory_depth() + 1
+            && segments.iter().all(|segment| {
+                !segment.is_empty()
+                    && *segment != "."
+                    && *segment != ".."
+                    && !segment.contains(['\\', '\0'])
             });
+
+        if is_safe {
+            Ok(file_name)
+        } else {
+            Err(OntologyRegistryError::UnsafeFileName { file_name })
         }
+    }
 
-        let provider_file_name = format!(
-            "{}{}",
-            resolved_registry_key.ontology_id(),
-            resolved_registry_key.file_type().as_file_ending()
-        );
+    fn unhashed_file_name(&self, registry_key: &RegistryKey) -> String {
+        let file_name = match registry_key.version() {
+            Version::Unversioned => self
+                .naming_strategy
+                .unversioned_file_name(registry_key.ontology_id(), &registry_key.file_type()),
+            version => self.naming_strategy.file_name(
+                registry_key.ontology_id(),
+                &version.to_string(),
+                &registry_key.file_type(),
+            ),
+        };
+        file_name + self.storage_suffix()
+    }
 
-        let mut ontology_reader = self.ontology_provider.provide_ontology(
-            resolved_registry_key.ontology_id(),
-            &provider_file_name,
-            resolved_registry_key.version(),
-        )?;
+    /// Returns the suffix appended to every file name by the storage format.
+    fn storage_suffix(&self) -> &'static str {
+        if self.compressed_storage { ".gz" } else { "" }
+    }
 
-        let _guard =
-            self.write_lock
-                .lock()
-                .map_err(|_| OntologyRegistryError::UnableToRegister {
-                    reason: provider_file_name.clone(),
-                })?;
+    fn is_hashed_file_name(&self, registry_key: &RegistryKey) -> bool {
+        let unhashed_file_name = self.unhashed_file_name(registry_key);
+        let (_, file_name) = self.split_file_name(&unhashed_file_name);
+        file_name.len() > self.max_file_name_length
+    }
 
-        if out_path.exists() {
-            return File::open(&out_path).map_err(|e| OntologyRegistryError::UnableToRegister {
-                reason: format!(
-                    "Unable to open existing file '{}': {}",
-                    out_path.display(),
-                    e
-                ),
-            });
+    /// Splits a path produced by the naming strategy into its directories and the file name.
+    ///
+    /// Only the first `directory_depth` segments count as directories, since versions may
+    /// contain `/` themselves.
+    fn split_file_name<'a>(&self, file_name: &'a str) -> (Option<&'a str>, &'a str) {
+        let depth = self.naming_strategy.directory_depth();
+        if depth == 0 {
+            return (None, file_name);
         }
+        match file_name.match_indices('/').nth(depth - 1) {
+            Some((index, _)) => (Some(&file_name[..index]), &file_name[index + 1..]),
+            None => (None, file_name),
+        }
+    }
 
-        let temp_file_name = format!("{}.tmp", registry_file_name);
-        let temp_dir = self.create_temp_dir()?;
-        let temp_file_dir = temp_dir.join(temp_file_name);
-
-        let mut temp_file =
-            File::create(&temp_file_dir).map_err(|_| OntologyRegistryError::UnableToRegister {
-                reason: format!(
-                    "Unable to create temporary file '{}'",
-                    temp_file_dir.display()
-                ),
+    /// Parses a path produced by `file_name` back into its registry key.
+    fn parse_file_name(&self, file_name: &str) -> Result<RegistryKey, OntologyRegistryError> {
+        let stored_name = file_name
+            .strip_suffix(self.storage_suffix())
+            .ok_or_else(|| OntologyRegistryError::CantParseRegistryKey {
+                raw_key: file_name.to_string(),
             })?;
+        self.naming_strategy.parse_file_name(stored_name)
+    }
 
-        let mut buffer: Vec<u8> = vec![];
-        let _ = ontology_reader.read_to_end(&mut buffer).map_err(|err| {
-            OntologyRegistryError::UnableToRegister {
-                reason: err.to_string(),
-            }
-        })?;
+    /// Opens a stored ontology file. With compressed storage, its content is decompressed into
+    /// an anonymous temporary file first, so callers always read the original content.
+    fn open_stored(&self, path: &Path) -> io::Result<File> {
+        #[cfg(feature = "gzip")]
+        if self.compressed_storage {
+            use std::io::Seek;
 
-        if temp_file.write_all(buffer.as_slice()).is_err() {
-            let _ = fs::remove_file(&temp_file_dir);
-            return Err(OntologyRegistryError::UnableToRegister {
-                reason: format!(
-                    "Unable to write to temporary file '{}'",
-                    temp_file_dir.display()
-                ),
-            });
+            let mut decompressed = tempfile::tempfile()?;
+            io::copy(&mut self.read_stored(path)?, &mut decompressed)?;
+            decompressed.rewind()?;
+            return Ok(decompressed);
+        }
+        File::open(path)
+    }
+
+    /// Returns a reader of the original content of a stored ontology file.
+    fn read_stored(&self, path: &Path) -> io::Result<Box<dyn Read>> {
+        let file = File::open(path)?;
+        #[cfg(feature = "gzip")]
+        if self.compressed_storage {
+            return Ok(Box::new(flate2::read::GzDecoder::new(file)));
         }
+        Ok(Box::new(file))
+    }
 
-        drop(temp_file);
+    /// Returns the checksum of the original content of a stored ontology file.
+    fn stored_sha256(&self, path: &Path) -> io::Result<String> {
+        sha256_reader(self.read_stored(path)?)
+    }
 
-        fs::rename(&temp_file_dir, &out_path).map_err(|err| {
-            let _ = fs::remove_file(&temp_file_dir);
-            OntologyRegistryError::UnableToRegister {
-                reason: format!(
-                    "Unable to rename temporary file '{}'. Error: {}",
-                    temp_file_dir.display(),
-                    err
-                ),
-            }
-        })?;
+    /// Streams `reader` into `file`, compressing it for compressed storage. Returns the number
+    /// of bytes read and the checksum of the original content.
+    fn store(&self, reader: &mut impl Read, file: File) -> io::Result<(u64, String)> {
+        #[cfg(feature = "gzip")]
+        if self.compressed_storage {
+            let mut writer = HashingWriter::new(flate2::write::GzEncoder::new(
+                file,
+                flate2::Compression::default(),
+            ));
+            let bytes_read = io::copy(reader, &mut writer)?;
+            let (encoder, checksum) = writer.into_parts();
+            encoder.finish()?;
+            return Ok((bytes_read, checksum));
+        }
+        let mut writer = HashingWriter::new(file);
+        let bytes_read = io::copy(reader, &mut writer)?;
+        Ok((bytes_read, writer.finish()))
+    }
 
-        fs::remove_dir_all(&temp_dir).map_err(|err| OntologyRegistryError::UnableToRegister {
-            reason: format!(
-                "Unable to delete temp directory '{}': {}",
-                temp_dir.display(),
-                err
-            ),
-        })?;
+    /// Finds a file in `root` other than `file_name` whose recorded checksum is `checksum`.
+    /// Files whose stored size `len` differs, e.g. because they changed since, are skipped.
+    fn identical_file(
+        root: &Path,
+        file_name: &str,
+        checksum: &str,
+        len: u64,
+    ) -> Result<Option<PathBuf>, OntologyRegistryError> {
+        let manifest = RegistryManifest::load(root)?;
+        Ok(manifest
+            .checksums
+            .iter()
+            .filter(|(other, other_checksum)| *other != file_name && *other_checksum == checksum)
+            .map(|(other, _)| root.join(other))
+            .find(|path| fs::metadata(path).is_ok_and(|m| m.is_file() && m.len() == len)))
+    }
 
-        File::open(&out_path).map_err(|err| OntologyRegistryError::UnableToRegister {
-            reason: format!(
-                "Unable to open final file '{}': {}",
-                out_path.display(),
-                err
+    /// Replaces the downloaded `temp_path` by a hard link to the identical `existing_path`. The
+    /// download is kept if linking fails.
+    fn link_identical_file(existing_path: &Path, temp_path: &Path) {
+        let link_path = temp_path.with_extension("link");
+        match fs::hard_link(existing_path, &link_path)
+            .and_then(|()| fs::rename(&link_path, temp_path))
+        {
+            Ok(()) => debug!(
+                target: CACHE_LOG_TARGET,
+                "Linked identical content from {}",
+                existing_path.display()
             ),
-        })
+            Err(err) => debug!(
+                target: CACHE_LOG_TARGET,
+                "Keeping a copy, linking {} failed: {err}",
+                existing_path.display()
+            ),
+        }
     }
 
-    /// Removes an ontology from the local filesystem registry.
+    /// Converts an absolute path below `root` into the `/`-separated form used by `file_name`.
+    fn relative_file_name(root: &Path, path: &Path) -> Option<String> {
+        let segments: Option<Vec<&str>> = path
+            .strip_prefix(root)
+            .ok()?
+            .components()
+            .map(|component| component.as_os_str().to_str())
+            .collect();
+        Some(segments?.join("/"))
+    }
+
+    /// Deletes an ontology file. Directories created for it by the naming strategy are removed
+    /// as well once they are empty.
+    fn remove_ontology_file(&self, path: &Path) -> Result<(), OntologyRegistryError> {
+        fs::remove_file(path).map_err(|err| OntologyRegistryError::io_at(path, err))?;
+        for directory in path
+            .ancestors()
+            .skip(1)
+            .take(self.naming_strategy.directory_depth())
+        {
+            // Fails if other releases remain, which is fine.
+            if fs::remove_dir(directory).is_err() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Deletes the least recently used ontology files in `root` until their total size fits
+    /// into `max_bytes`. `keep` is never deleted.
     ///
-    /// Logs a warning if the version cannot be resolved or if deletion fails.
-    /// This operation is thread-safe regarding the `write_lock`.
-    fn unregister(&self, registry_key: RegistryKey) -> Result<(), OntologyRegistryError> {
-        let resolved_version =
-            self.resolve_version(registry_key.ontology_id(), registry_key.version())?;
+    /// Recency is the later of a file's access and modification time, so filesystems mounted
+    /// with `noatime` fall back to the time a file was written.
+    fn evict_to_fit(&self, root: &Path, keep: &Path) -> Result<(), OntologyRegistryError> {
+        let Some(max_bytes) = self.max_bytes else {
+            return Ok(());
+        };
 
-        let resolved_registry_key = RegistryKey::new(
-            registry_key.ontology_id(),
-            Version::Declared(resolved_version),
-            registry_key.file_type(),
-        );
+        // Hard-linked names share their content, which only counts once and is only freed
+        // once its last name is evicted.
+        let mut names_per_content = HashMap::new();
+        let mut files = Vec::new();
+        let mut total_bytes = 0;
+        for (path, metadata) in self.ontology_files(root)? {
+            if !metadata.is_file() {
+                continue;
+            }
+            let names = names_per_content
+                .entry(content_key(&path, &metadata))
+                .or_insert(0);
+            if *names == 0 {
+                total_bytes += metadata.len();
+            }
+            *names += 1;
+            let last_used = match (metadata.accessed(), metadata.modified()) {
+                (Ok(accessed), Ok(modified)) => accessed.max(modified),
+                (accessed, modified) => accessed.or(modified).unwrap_or(UNIX_EPOCH),
+            };
+            files.push((last_used, metadata, path));
+        }
 
-        let file_path = self
-            .registry_path
-            .clone()
-            .join(resolved_registry_key.as_file_name());
+        if total_bytes <= max_bytes {
+            return Ok(());
+        }
 
-        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        files.sort_by_key(|(last_used, _, _)| *last_used);
 
-        if file_path.exists() {
-            fs::remove_file(&file_path).map_err(|err| OntologyRegistryError::UnableToRegister {
-                reason: format!("Unable to rename temporary file '{}'", err),
-            })?;
+        let mut evicted = Vec::new();
+        for (_, metadata, path) in files {
+            if total_bytes <= max_bytes {
+                break;
+            }
+            if path == keep {
+                continue;
+            }
+
+            let len = metadata.len();
+            self.remove_ontology_file(&path)?;
+            info!(
+                target: EVICT_LOG_TARGET,
+                "Evicted {} ({len} bytes) to stay within {max_bytes} bytes",
+                path.display()
+            );
+            if let Some(observer) = &self.observer {
+                observer.on_evict(&path, len);
+            }
+            if let Some(names) = names_per_content.get_mut(&content_key(&path, &metadata)) {
+                *names -= 1;
+                if *names == 0 {
+                    total_bytes -= len;
+                }
+            }
+            evicted.push(path);
         }
 
-        Ok(())
+        Self::forget_file_names(root, &evicted)
     }
 
-    /// Retrieves the local filesystem path for a specific ontology.
+    /// Returns the path and metadata of every ontology file in `root`, according to the
+    /// directory depth of the naming strategy.
     ///
-    /// Returns `None` if the ontology is not currently found in the local registry
-    /// or if the version could not be resolved.
-    fn get(&self, registry_key: RegistryKey) -> Option<File> {
-        let resolved_version = self
-            .resolve_version(registry_key.ontology_id(), registry_key.version())
-            .ok()?;
+    /// Symlinks (`latest` aliases) are included with the metadata of the link itself.
+    /// Hidden files and directories (such as the manifest) are skipped. A missing `root`
+    /// yields no files.
+    fn ontology_files(
+        &self,
+        root: &Path,
+    ) -> Result<Vec<(PathBuf, fs::Metadata)>, OntologyRegistryError> {
+        let mut directories = vec![root.to_path_buf()];
+        for _ in 0..self.naming_strategy.directory_depth() {
+            let mut subdirectories = Vec::new();
+            for directory in &directories {
+                subdirectories.extend(
+                    Self::visible_entries(directory, fs::Metadata::is_dir)?
+                        .into_iter()
+                        .map(|(path, _)| path),
+                );
+            }
+            directories = subdirectories;
+        }
 
-        let resolved_registry_key = RegistryKey::new(
-            registry_key.ontology_id(),
-            Version::Declared(resolved_version),
-            registry_key.file_type(),
-        );
+        let mut files = Vec::new();
+        for directory in &directories {
+            files.extend(Self::visible_entries(directory, |metadata| {
+                !metadata.is_dir()
+            })?);
+        }
+        Ok(files)
+    }
 
-        let file_path = self
-            .registry_path
-            .join(resolved_registry_key.as_file_name());
+    /// Lists the keys of the ontology files stored in `root`.
+    fn list_root(&self, root: &Path) -> Result<Vec<RegistryKey>, OntologyRegistryError> {
+        let manifest = RegistryManifest::load(root)?;
 
-        File::open(file_path).ok()
+        self.ontology_files(root)?
+            .into_iter()
+            .filter_map(|(path, _)| Self::relative_file_name(root, &path))
+            .filter(|file_name| file_name.ends_with(".gz") == self.compressed_storage)
+            .map(
+                |file_name| match manifest.hashed_file_names.get(&file_name) {
+                    Some(registry_key) => Ok(registry_key.clone()),
+                    None => self.parse_file_name(&file_name),
+                },
+            )
+            .collect()
     }
 
-    /// Lists all files currently stored in the registry directory.
+    /// Returns the non-hidden entries directly inside `dir` whose metadata matches `filter`.
     ///
-    /// Returns a vector of strings representing the absolute paths of the files.
-    fn list(&self) -> Result<Vec<RegistryKey>, OntologyRegistryError> {
-        let mut files = Vec::new();
+    /// Leftover `.tmp` files of interrupted registrations are skipped as well.
+    fn visible_entries(
+        dir: &Path,
+        filter: impl Fn(&fs::Metadata) -> bool,
+    ) -> Result<Vec<(PathBuf, fs::Metadata)>, OntologyRegistryError> {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
 
-        if let Ok(entries) = fs::read_dir(self.registry_path.clone()) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-
-                if path.is_file()
-                    && let Some(file_name) = path.file_name()
-                    && let Some(file_name_str) = file_name.to_str()
-                    // Ignoring hidden files
-                    && !file_name_str.starts_with('.')
-                {
-                    files.push(RegistryKey::from_file_name(file_name_str)?);
-                }
+        let mut visible = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if filter(&metadata) && !name.starts_with('.') && !name.ends_with(".tmp") {
+                visible.push((entry.path(), metadata));
             }
         }
 
-        Ok(files)
+        Ok(visible)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::FileType;
-    use crate::ontology_metadata::OntologyMetadata;
-    use std::collections::HashMap;
-    use std::io::Cursor;
-    use std::sync::Arc;
-    use tempfile::tempdir;
+    /// Returns the temporary files and directories in `root` left behind by interrupted
+    /// registrations: hidden `.tmp_*` directories and files ending in `.tmp`.
+    fn stray_temporaries(root: &Path) -> Result<Vec<PathBuf>, OntologyRegistryError> {
+        let entries = match fs::read_dir(root) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
 
-    #[derive(Clone, Debug)]
-    struct MockMetadataProvider {
-        data: HashMap<String, String>,
+        let mut temporaries = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            let is_temp_dir = name.starts_with(".tmp_") && entry.file_type()?.is_dir();
+            if is_temp_dir || name.ends_with(".tmp") {
+                temporaries.push(entry.path());
+            }
+        }
+
+        Ok(temporaries)
     }
 
-    impl MockMetadataProvider {
-        fn new() -> Self {
-            Self {
-                data: HashMap::new(),
-            }
+    /// Drops the manifest entries of deleted files.
+    fn forget_file_names(root: &Path, removed: &[PathBuf]) -> Result<(), OntologyRegistryError> {
+        if removed.is_empty() {
+            return Ok(());
         }
-        fn with_version(mut self, id: &str, version: &str) -> Self {
-            self.data.insert(id.to_string(), version.to_string());
-            self
+
+        let mut manifest = RegistryManifest::load(root)?;
+        let mut manifest_changed = false;
+        for file_name in removed
+            .iter()
+            .filter_map(|path| Self::relative_file_name(root, path))
+        {
+            manifest_changed |= manifest.forget(&file_name);
         }
-    }
 
-    impl OntologyMetadataProviding for MockMetadataProvider {
-        fn provide_metadata(
-            &self,
-            ontology_id: &str,
-        ) -> Result<OntologyMetadata, OntologyRegistryError> {
-            match self.data.get(ontology_id) {
-                Some(v) => Ok(OntologyMetadata {
-                    ontology_id: "".to_string(),
-                    version: v.clone(),
-                    json_file_location: None,
-                    owl_file_location: None,
-                    obo_file_location: None,
-                    title: None,
-                }),
-                None => Err(OntologyRegistryError::UnableToRegister {
-                    reason: "Metadata not found".into(),
-                }),
-            }
+        if manifest_changed {
+            manifest.save(root)?;
         }
-    }
 
-    #[derive(Clone, Debug)]
-    struct MockOntologyProvider {
-        content: HashMap<String, String>,
+        Ok(())
     }
 
-    impl MockOntologyProvider {
-        fn new() -> Self {
-            Self {
-                content: HashMap::new(),
-            }
+    /// Points the `latest` alias of `registry_key`'s ontology and file type at the file stored
+    /// under `target_file_name`.
+    ///
+    /// The alias only moves forward: it is left alone if a newer release of the ontology and
+    /// file type is registered, unless `registry_key` was `Version::Latest` before resolving.
+    /// It is created in a temporary directory and renamed into place, so readers never observe
+    /// a missing alias. Unversioned files are no release and get no alias.
+    fn update_latest_alias(
+        &self,
+        root: &Path,
+        registry_key: &RegistryKey,
+        target_file_name: &str,
+        resolved_from_latest: bool,
+    ) -> Result<(), OntologyRegistryError> {
+        if !matches!(registry_key.version(), Version::Declared(_)) {
+            return Ok(());
         }
-        fn with_content(mut self, id: &str, content: &str) -> Self {
-            self.content.insert(id.to_string(), content.to_string());
-            self
+        if !resolved_from_latest
+            && self.list_root(root)?.iter().any(|key| {
+                key.ontology_id() == registry_key.ontology_id()
+                    && key.file_type() == registry_key.file_type()
+                    && matches!(key.version(), Version::Declared(_))
+                    && key.version() > registry_key.version()
+            })
+        {
+            return Ok(());
+        }
+        let alias_key = RegistryKey::new(
+            registry_key.ontology_id(),
+            Version::Latest,
+            registry_key.file_type(),
+        );
+        let alias_file_name = self.file_name(&alias_key);
+        let link_target = Self::relative_link_target(&alias_file_name, target_file_name);
+        let link_name = alias_file_name
+            .rsplit('/')
+            .next()
+            .unwrap_or(&alias_file_name);
+        let alias_path = root.join(&alias_file_name);
+        if let Some(parent) = alias_path.parent() {
+            fs::create_dir_all(parent).map_err(|err| OntologyRegistryError::io_at(parent, err))?;
         }
-    }
 
-    impl OntologyProviding for MockOntologyProvider {
-        fn provide_ontology(
-            &self,
-            ontology_id: &str,
-            _file_name: &str,
-            _version: &Version,
-        ) -> Result<impl Read, OntologyRegistryError> {
-            Ok(Cursor::new(
-                self.content
-                    .get(ontology_id)
-                    .cloned()
-                    .ok_or(OntologyRegistryError::UnableToRegister {
-                        reason: "Content not found".into(),
-                    })?
-                    .into_bytes(),
-            ))
+        let temp_dir = self.create_temp_dir(root)?;
+        let temp_alias_path = temp_dir.join(link_name);
+        let result = Self::write_alias(&temp_alias_path, &link_target)
+            .and_then(|()| fs::rename(&temp_alias_path, &alias_path));
+        let _ = fs::remove_dir_all(&temp_dir);
+        result?;
+
+        if self.is_hashed_file_name(&alias_key) {
+            let mut manifest = RegistryManifest::load(root)?;
+            manifest
+                .hashed_file_names
+                .insert(alias_file_name, alias_key);
+            manifest.save(root)?;
         }
+
+        Ok(())
     }
 
-    #[test]
-    fn test_register_declared_version_success() {
-        let temp_dir = tempdir().unwrap();
-        let registry_path = temp_dir.path().to_path_buf();
+    /// Atomically stores the content of `reader` under `resolved_registry_key` in `root`,
+    /// replacing any existing file, and returns the number of bytes written.
+    ///
+    /// Content is streamed into a temporary directory and renamed into place. Afterwards the
+    /// manifest (including the file's cache `validators`), the `latest` alias and the size
+    /// budget are updated. Callers must hold `write_lock`.
+    fn write_ontology_file(
+        &self,
+        root: &Path,
+        resolved_registry_key: &RegistryKey,
+        reader: &mut impl Read,
+        validators: &CacheValidators,
+    ) -> Result<u64, OntologyRegistryError> {
+        let registry_file_name = self.file_name(resolved_registry_key);
+        let out_path = root.join(&registry_file_name);
 
-        let content = "<rdf>content</rdf>";
+        let temp_dir = self.create_temp_dir(root)?;
+        let placed = self.place_ontology_file(
+            root,
+            &temp_dir,
+            resolved_registry_key,
+            &registry_file_name,
+            reader,
+        );
+        let removed = fs::remove_dir_all(&temp_dir);
+        let (bytes_written, checksum) = placed?;
+        removed.map_err(|err| OntologyRegistryError::io_at(&temp_dir, err))?;
+
+        let mut manifest = RegistryManifest::load(root)?;
+        if self.is_hashed_file_name(resolved_registry_key) {
+            manifest
+                .hashed_file_names
+                .insert(registry_file_name.clone(), resolved_registry_key.clone());
+        }
+        if validators.is_empty() {
+            manifest.cache_validators.remove(&registry_file_name);
+        } else {
+            manifest
+                .cache_validators
+                .insert(registry_file_name.clone(), validators.clone());
+        }
+        manifest
+            .checked_at
+            .insert(registry_file_name.clone(), SystemTime::now());
+        manifest
+            .checksums
+            .insert(registry_file_name.clone(), checksum);
+        manifest.save(root)?;
+
+        if self.latest_alias {
+            self.update_latest_alias(root, resolved_registry_key, &registry_file_name, false)?;
+        }
+
+        self.evict_to_fit(root, &out_path)?;
+
+        Ok(bytes_written)
+    }
+
+    /// Streams the content of `reader` into `temp_dir`, validates it and renames it to
+    /// `registry_file_name` below `root`. Returns the number of bytes written and the checksum
+    /// of the content.
+    ///
+    /// The caller removes `temp_dir` afterwards, whether this succeeded or not.
+    fn place_ontology_file(
+        &self,
+        root: &Path,
+        temp_dir: &Path,
+        resolved_registry_key: &RegistryKey,
+        registry_file_name: &str,
+        reader: &mut impl Read,
+    ) -> Result<(u64, String), OntologyRegistryError> {
+        let out_path = root.join(registry_file_name);
+        let temp_file_name = format!(
+            "{}.tmp",
+            registry_file_name
+                .rsplit('/')
+                .next()
+                .unwrap_or(registry_file_name)
+        );
+        let temp_file_dir = temp_dir.join(temp_file_name);
+
+        let temp_file = File::create(&temp_file_dir)
+            .map_err(|err| OntologyRegistryError::io_at(&temp_file_dir, err))?;
+
+        let mut reader = CancellableReader::new(reader, self.cancel_flag.as_deref());
+        let (bytes_written, checksum) = self
+            .store(&mut reader, temp_file)
+            .map_err(OntologyRegistryError::from_stream_error)?;
+
+        if let Some(ContentValidator(validator)) = &self.validator {
+            self.read_stored(&temp_file_dir)
+                .map_err(|err| OntologyRegistryError::io_at(&temp_file_dir, err))
+                .and_then(|mut stored| {
+                    validator(&resolved_registry_key.file_type(), &mut stored).map_err(|reason| {
+                        OntologyRegistryError::UnableToRegister {
+                            reason: format!("{resolved_registry_key} failed validation: {reason}"),
+                        }
+                    })
+                })?;
+        }
+
+        let stored_len = fs::metadata(&temp_file_dir)
+            .map_err(|err| OntologyRegistryError::io_at(&temp_file_dir, err))?
+            .len();
+        if self.deduplicate
+            && let Some(identical_path) =
+                Self::identical_file(root, registry_file_name, &checksum, stored_len)?
+        {
+            Self::link_identical_file(&identical_path, &temp_file_dir);
+        }
+
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent).map_err(|err| OntologyRegistryError::io_at(parent, err))?;
+        }
+        fs::rename(&temp_file_dir, &out_path)
+            .map_err(|err| OntologyRegistryError::io_at(&out_path, err))?;
+
+        Ok((bytes_written, checksum))
+    }
+
+    /// Returns the path of `target_file_name` relative to the directory of `alias_file_name`.
+    /// Both are `/`-separated paths relative to the same registry root.
+    fn relative_link_target(alias_file_name: &str, target_file_name: &str) -> String {
+        let alias_dirs: Vec<&str> = alias_file_name.split('/').collect();
+        let alias_dirs = &alias_dirs[..alias_dirs.len() - 1];
+        let target_segments: Vec<&str> = target_file_name.split('/').collect();
+        let shared = alias_dirs
+            .iter()
+            .zip(&target_segments)
+            .take_while(|(alias_dir, target_segment)| alias_dir == target_segment)
+            .count();
+
+        let mut segments = vec![".."; alias_dirs.len() - shared];
+        segments.extend(&target_segments[shared..]);
+        segments.join("/")
+    }
+
+    /// Creates a relative symlink at `path`, falling back to a pointer file holding
+    /// `target_file_name` if the platform or filesystem does not allow symlinks.
+    fn write_alias(path: &Path, target_file_name: &str) -> io::Result<()> {
+        #[cfg(unix)]
+        let linked = std::os::unix::fs::symlink(target_file_name, path).is_ok();
+        #[cfg(windows)]
+        let linked = std::os::windows::fs::symlink_file(target_file_name, path).is_ok();
+        #[cfg(not(any(unix, windows)))]
+        let linked = false;
+
+        if linked {
+            Ok(())
+        } else {
+            fs::write(path, target_file_name)
+        }
+    }
+}
+
+impl<MDP: OntologyMetadataProviding, OP: OntologyProviding> FileSystemOntologyRegistry<MDP, OP> {
+    /// Creates a new `FileSystemOntologyRegistry`.
+    ///
+    /// # Arguments
+    ///
+    /// * `registry_path` - The directory path where ontologies will be saved.
+    /// * `metadata_provider` - The service to query for ontology version metadata.
+    /// * `ontology_provider` - The service to download ontology content from.
+    pub fn new(registry_path: PathBuf, metadata_provider: MDP, ontology_provider: OP) -> Self {
+        FileSystemOntologyRegistry {
+            registry_path,
+            metadata_provider,
+            ontology_provider,
+            write_lock: Mutex::new(()),
+            max_file_name_length: DEFAULT_MAX_FILE_NAME_LENGTH,
+            max_bytes: None,
+            refresh_after: None,
+            latest_alias: false,
+            naming_strategy: Box::new(RegistryLayout::default()),
+            file_lock: false,
+            offline: false,
+            read_paths: Vec::new(),
+            id_normalizer: None,
+            follow_replaced_by: false,
+            provider_latest: false,
+            #[cfg(feature = "http")]
+            url_provider: UrlOntologyProvider::default(),
+            cancel_flag: None,
+            validator: None,
+            compressed_storage: false,
+            deduplicate: false,
+            observer: None,
+        }
+    }
+
+    /// Creates a registry like [`Self::new`], but first checks that `registry_path` is either
+    /// missing, in which case it is created on first write, or a directory.
+    ///
+    /// # Errors
+    /// Returns `OntologyRegistryError::NotADirectory` if `registry_path` is an existing file,
+    /// and `Io` if it cannot be inspected.
+    pub fn try_new(
+        registry_path: PathBuf,
+        metadata_provider: MDP,
+        ontology_provider: OP,
+    ) -> Result<Self, OntologyRegistryError> {
+        Self::check_registry_path(&registry_path)?;
+        Ok(Self::new(
+            registry_path,
+            metadata_provider,
+            ontology_provider,
+        ))
+    }
+
+    /// Starts a [`FileSystemOntologyRegistryBuilder`], which collects the options otherwise set
+    /// through the `with_*` methods and checks the path like [`Self::try_new`] when built.
+    pub fn builder(
+        registry_path: PathBuf,
+        metadata_provider: MDP,
+        ontology_provider: OP,
+    ) -> FileSystemOntologyRegistryBuilder<MDP, OP> {
+        FileSystemOntologyRegistryBuilder::new(registry_path, metadata_provider, ontology_provider)
+    }
+
+    /// The directory `register` writes to.
+    pub fn registry_path(&self) -> &Path {
+        &self.registry_path
+    }
+
+    /// Fails unless `registry_path` is missing or a directory.
+    pub(crate) fn check_registry_path(registry_path: &Path) -> Result<(), OntologyRegistryError> {
+        match fs::metadata(registry_path) {
+            Ok(metadata) if !metadata.is_dir() => Err(OntologyRegistryError::NotADirectory {
+                path: registry_path.display().to_string(),
+            }),
+            Ok(_) => Ok(()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Creates a registry like [`Self::try_new`] and removes leftovers of registrations that
+    /// were interrupted in an earlier run, see [`Self::cleanup_temp_files`].
+    pub fn open(
+        registry_path: PathBuf,
+        metadata_provider: MDP,
+        ontology_provider: OP,
+    ) -> Result<Self, OntologyRegistryError> {
+        let registry = Self::try_new(registry_path, metadata_provider, ontology_provider)?;
+        registry.cleanup_temp_files()?;
+        Ok(registry)
+    }
+
+    /// Sets the maximum length in bytes of a registry file name. Longer names are replaced by a
+    /// deterministic hash. Defaults to [`DEFAULT_MAX_FILE_NAME_LENGTH`].
+    pub fn with_max_file_name_length(mut self, max_file_name_length: usize) -> Self {
+        self.max_file_name_length = max_file_name_length;
+        self
+    }
+
+    /// Limits the total size of the ontology files in the registry to `max_bytes`.
+    ///
+    /// After every download, the least recently used files are deleted until the registry fits
+    /// into the budget again. The file just registered is never evicted, even if it alone
+    /// exceeds the budget.
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Makes [`Self::get_fresh`] refresh files whose [`Self::cached_at`] is more than
+    /// `refresh_after` ago before serving them. `get` itself never contacts a provider.
+    pub fn with_refresh_after(mut self, refresh_after: Duration) -> Self {
+        self.refresh_after = Some(refresh_after);
+        self
+    }
+
+    /// Maintains a `<id>@latest<ext>` alias pointing at the newest registered release of each
+    /// ontology and file type, so downstream tools can open it without resolving versions.
+    /// Registering an older release leaves the alias alone; registering `Version::Latest` points
+    /// it at the release `Version::Latest` resolved to.
+    ///
+    /// The alias is a relative symlink. Where symlinks cannot be created, it is a small pointer
+    /// file containing the file name of the release instead. Aliases show up in `list` as keys
+    /// with `Version::Latest`; use `list_releases` to leave them out.
+    pub fn with_latest_alias(mut self, latest_alias: bool) -> Self {
+        self.latest_alias = latest_alias;
+        self
+    }
+
+    /// Guards every modification of the registry directory with an advisory lock on a hidden
+    /// `.lock` file in it, so several processes can share the directory (e.g. parallel CI jobs
+    /// on one cache volume). Disabled by default.
+    ///
+    /// The lock is advisory: it only excludes other registries with the file lock enabled.
+    pub fn with_file_lock(mut self, file_lock: bool) -> Self {
+        self.file_lock = file_lock;
+        self
+    }
+
+    /// Serves ontologies from the registry directory only and never calls the metadata or
+    /// ontology provider, e.g. in sandboxed builds without network access. Disabled by default.
+    ///
+    /// A declared version must already be registered. `Version::Latest` resolves to the newest
+    /// release returned by [`Self::latest_local`]. Anything that would need a provider fails with
+    /// `OntologyRegistryError::Offline` instead, including [`Self::refresh`].
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Adds registry directories that are searched, in order, after the registry path, e.g. a
+    /// read-only cache shared by all users behind a writable per-user cache.
+    ///
+    /// `get`, `contains`, `path` and `list` see the files of every directory. `register`,
+    /// `unregister` and all other modifications only ever act on the registry path, so a release
+    /// found in a read path alone is downloaded again by `register`; use
+    /// [`Self::get_or_register`] to avoid that. Read paths must use the same layout as the
+    /// registry path.
+    pub fn with_read_paths(mut self, read_paths: Vec<PathBuf>) -> Self {
+        self.read_paths = read_paths;
+        self
+    }
+
+    /// Lowercases ontology ids before they are resolved, stored or passed to a provider, so
+    /// `HP`, `Hp` and `hp` all refer to the same ontology. Disabled by default.
+    ///
+    /// The default layouts already lowercase file names, but without this option the providers
+    /// and `list` still see the id as given. Shorthand for
+    /// `with_id_normalizer(|id| id.to_lowercase())`.
+    pub fn with_lowercase_ids(mut self, lowercase_ids: bool) -> Self {
+        self.id_normalizer = if lowercase_ids {
+            Some(str::to_lowercase)
+        } else {
+            None
+        };
+        self
+    }
+
+    /// Applies `normalizer` to every ontology id given to `register`, `get`, `unregister`,
+    /// `resolve_version` and the other lookups, and to the ids reported by `list`.
+    ///
+    /// The normalizer must be idempotent, as an id may pass through it more than once.
+    pub fn with_id_normalizer(mut self, normalizer: fn(&str) -> String) -> Self {
+        self.id_normalizer = Some(normalizer);
+        self
+    }
+
+    /// Registers the replacement when `Version::Latest` of an ontology is requested that the
+    /// metadata provider marks as obsolete and names a `replaced_by` for. Disabled by default.
+    ///
+    /// Obsolete ontologies are logged as a warning either way. Only one replacement is followed,
+    /// and declared versions always refer to the requested ontology. [`Self::register_detailed`]
+    /// reports the id the file was registered under in its metadata.
+    pub fn with_follow_replaced_by(mut self, follow_replaced_by: bool) -> Self {
+        self.follow_replaced_by = follow_replaced_by;
+        self
+    }
+
+    /// Resolves `Version::Latest` by asking the ontology provider for the version of its newest
+    /// release via [`OntologyProviding::latest_version`] instead of asking the metadata provider.
+    /// Disabled by default.
+    ///
+    /// The release is then registered like a declared version: a cached copy is served as is,
+    /// anything else is downloaded through `provide_ontology`. `get` and [`Self::path_for`]
+    /// resolve `Version::Latest` the same way. Offline registries keep using their newest local
+    /// release.
+    pub fn with_provider_latest(mut self, provider_latest: bool) -> Self {
+        self.provider_latest = provider_latest;
+        self
+    }
+
+    /// Downloads the locations used by [`Self::register_from_metadata`] with `url_provider`,
+    /// e.g. one configured with a timeout, retries, a proxy or a user agent. Defaults to
+    /// `UrlOntologyProvider::default()`.
+    #[cfg(feature = "http")]
+    pub fn with_url_provider(mut self, url_provider: UrlOntologyProvider) -> Self {
+        self.url_provider = url_provider;
+        self
+    }
+
+    /// Aborts downloads once `cancel_flag` is set, e.g. from a shutdown handler.
+    ///
+    /// An in-flight download stops at its next read, its temporary file is removed and the
+    /// registration fails with `OntologyRegistryError::Cancelled`. Downloads that have not
+    /// started yet fail the same way, so [`Self::register_many`] winds down quickly. Ontologies
+    /// that are already registered are still served. Reset the flag to resume.
+    pub fn with_cancel_flag(mut self, cancel_flag: Arc<AtomicBool>) -> Self {
+        self.cancel_flag = Some(cancel_flag);
+        self
+    }
+
+    /// Checks the content of every file before it is committed to the registry, e.g. to catch
+    /// an error page a server returned with status 200. No validation happens by default;
+    /// [`validate_not_empty_or_html`] covers the common cases.
+    ///
+    /// The validator receives the file type and a reader of the content, streamed from the
+    /// file not yet committed, so it reads only as much as it needs. If it returns an error,
+    /// the file is discarded and the registration fails with
+    /// `OntologyRegistryError::UnableToRegister`. Applies to downloads, `refresh` and
+    /// `register_bytes` alike.
+    pub fn with_validator(
+        mut self,
+        validator: impl Fn(&FileType, &mut dyn Read) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        self.validator = Some(ContentValidator(Box::new(validator)));
+        self
+    }
+
+    /// Stores files gzip-compressed, with a `.gz` suffix such as `mondo@2024-01-04.owl.gz`,
+    /// trading CPU time for disk space. Disabled by default.
+    ///
+    /// Readers returned by `register`, `get` and friends, [`Self::export_to`] and
+    /// [`Self::read_obo_header`] still see the original content. As `get` returns a `File`,
+    /// its content is decompressed into an anonymous temporary file first;
+    /// [`Self::get_reader`] decompresses while reading instead. Paths, such as those returned
+    /// by [`Self::path`], point at the compressed file, while checksums cover the original
+    /// content, so lock files work with and without compression.
+    ///
+    /// Like the layout, this setting is not recorded on disk. `list` only reports files stored
+    /// the way the registry is configured, i.e. `.gz` files with compression and all other
+    /// files without it.
+    #[cfg(feature = "gzip")]
+    pub fn with_compressed_storage(mut self, compressed_storage: bool) -> Self {
+        self.compressed_storage = compressed_storage;
+        self
+    }
+
+    /// Stores byte-identical files only once. When a download has the same checksum as a file
+    /// already in the registry, e.g. two releases that did not change, the new name becomes a
+    /// hard link to the existing file instead of a second copy. If linking fails, for example
+    /// on a file system without hard links, the downloaded copy is kept.
+    ///
+    /// Linked names share one file on disk. The registry never writes a stored file in place,
+    /// so unregistering, evicting or refreshing one name only removes or replaces that name
+    /// and the content stays available under the others, and freshness is tracked per name.
+    /// Changing the file through any name outside the registry changes all of them, though.
+    /// Size limits set with [`Self::with_max_bytes`] count the shared file once, and evicting
+    /// a name only frees space once no other name links to it.
+    pub fn with_deduplication(mut self, deduplicate: bool) -> Self {
+        self.deduplicate = deduplicate;
+        self
+    }
+
+    /// Notifies `observer` of every download, cache hit, eviction and failed registration, with
+    /// the affected key, path or byte count. Replaces any previously set observer.
+    pub fn with_observer(mut self, observer: impl RegistryObserver + 'static) -> Self {
+        self.observer = Some(Box::new(observer));
+        self
+    }
+
+    /// Sets how files are arranged below the registry root. Defaults to [`RegistryLayout::Flat`].
+    ///
+    /// The layout is not recorded on disk, so a registry directory must always be opened with
+    /// the layout it was populated with.
+    pub fn with_layout(self, layout: RegistryLayout) -> Self {
+        self.with_naming_strategy(layout)
+    }
+
+    /// Sets the scheme used to name files below the registry root, replacing the layout.
+    ///
+    /// Like the layout, the strategy is not recorded on disk, so a registry directory must
+    /// always be opened with the strategy it was populated with.
+    pub fn with_naming_strategy(
+        mut self,
+        naming_strategy: impl FileNamingStrategy + 'static,
+    ) -> Self {
+        self.naming_strategy = Box::new(naming_strategy);
+        self
+    }
+
+    /// Registers an ontology under `root` instead of the configured registry path.
+    ///
+    /// This lets a single registry, with its providers and configuration, serve several
+    /// storage locations (e.g. one cache directory per tenant). Behaves exactly like
+    /// [`OntologyRegistration::register`] otherwise.
+    pub fn register_in(
+        &self,
+        root: &Path,
+        registry_key: RegistryKey,
+    ) -> Result<File, OntologyRegistryError> {
+        let result = self.register_file(root, registry_key.clone(), &mut |_, _| {});
+        if let Err(err) = &result {
+            self.report_error(&registry_key, err);
+        }
+        result
+    }
+
+    /// Registers an ontology like [`OntologyRegistration::register`], reporting download progress.
+    ///
+    /// `on_progress` is called after every chunk written with the number of bytes downloaded so
+    /// far and the total size, if the provider announced one. It is not called if the ontology
+    /// is already registered, and never again once the download failed.
+    pub fn register_with_progress(
+        &self,
+        registry_key: RegistryKey,
+        mut on_progress: impl FnMut(u64, Option<u64>),
+    ) -> Result<File, OntologyRegistryError> {
+        let result =
+            self.register_file(&self.registry_path, registry_key.clone(), &mut on_progress);
+        if let Err(err) = &result {
+            self.report_error(&registry_key, err);
+        }
+        result
+    }
+
+    /// Registers an ontology like [`OntologyRegistration::register`] and reports where it is
+    /// stored, the metadata of the ontology and whether it was already registered.
+    ///
+    /// The metadata is the one used to resolve the version or the download location. It is only
+    /// requested separately if registration did not need it, e.g. for a declared version that
+    /// is already registered. In that case a failing metadata provider fails the call, and so
+    /// does [offline mode](Self::with_offline).
+    pub fn register_detailed(
+        &self,
+        registry_key: RegistryKey,
+    ) -> Result<RegisteredOntology, OntologyRegistryError> {
+        let result = self
+            .register_entry(&self.registry_path, registry_key.clone(), &mut |_, _| {})
+            .and_then(|entry| {
+                let metadata = match entry.metadata {
+                    Some(metadata) => metadata,
+                    None if self.offline => return Err(Self::offline_error(&registry_key)),
+                    None => self
+                        .metadata_provider
+                        .provide_metadata(registry_key.ontology_id())?,
+                };
+                Ok(RegisteredOntology {
+                    path: entry.path,
+                    metadata,
+                    was_cached: entry.was_cached,
+                })
+            });
+        if let Err(err) = &result {
+            self.report_error(&registry_key, err);
+        }
+        result
+    }
+
+    /// Registers an ontology like [`OntologyRegistration::register`] and also returns the version
+    /// it was stored under, e.g. to pin what `Version::Latest` meant at build time.
+    ///
+    /// For a declared version, that is the declared version itself.
+    pub fn register_resolved(
+        &self,
+        registry_key: RegistryKey,
+    ) -> Result<(File, String), OntologyRegistryError> {
+        let result = self
+            .register_entry(&self.registry_path, registry_key.clone(), &mut |_, _| {})
+            .and_then(|entry| {
+                let file = self
+                    .open_stored(&entry.path)
+                    .map_err(|err| OntologyRegistryError::io_at(&entry.path, err))?;
+                Ok((file, entry.registry_key.version().to_string()))
+            });
+        if let Err(err) = &result {
+            self.report_error(&registry_key, err);
+        }
+        result
+    }
+
+    /// Registers `ontology_id` and, transitively, the ontologies it depends on according to the
+    /// metadata provider. Returns the paths of all registered files, `ontology_id` first.
+    ///
+    /// `version` only applies to `ontology_id`; dependencies are registered at their latest
+    /// release in the same `file_type`. Each ontology is registered once, even if dependencies
+    /// form a cycle. A dependency whose metadata cannot be provided is skipped with a warning,
+    /// any other failure fails the call.
+    pub fn register_with_dependencies(
+        &self,
+        ontology_id: &str,
+        version: &Version,
+        file_type: &FileType,
+    ) -> Result<Vec<PathBuf>, OntologyRegistryError> {
+        let registry_key = RegistryKey::new(ontology_id, version.clone(), *file_type);
+        let result = self.register_dependency_tree(registry_key.clone());
+        if let Err(err) = &result {
+            self.report_error(&registry_key, err);
+        }
+        result
+    }
+
+    /// Retrieves a previously registered ontology from `root` instead of the configured registry path.
+    ///
+    /// Returns `None` if the ontology is not found under `root` or if the version could not be resolved.
+    pub fn get_in(&self, root: &Path, registry_key: RegistryKey) -> Option<File> {
+        let resolved_registry_key = self.resolve_registry_key(&registry_key).ok()?;
+
+        self.open_stored(&Self::stored_path(
+            root,
+            &self.file_name(&resolved_registry_key),
+        )?)
+        .ok()
+    }
+
+    /// Retrieves a previously registered ontology like [`OntologyRegistration::get`], as a
+    /// reader of its content.
+    ///
+    /// With [compressed storage](Self::with_compressed_storage) the file is decompressed while
+    /// it is read, instead of into a temporary file up front.
+    pub fn get_reader(&self, registry_key: RegistryKey) -> Option<Box<dyn Read>> {
+        let resolved_registry_key = self.resolve_registry_key(&registry_key).ok()?;
+
+        self.read_stored(&self.find_file(&self.file_name(&resolved_registry_key))?)
+            .ok()
+    }
+
+    /// Retrieves a previously registered ontology like [`OntologyRegistration::get`], but tells
+    /// a missing file apart from a failed lookup.
+    ///
+    /// Returns `Ok(None)` if the ontology is not registered. Fails if the version cannot be
+    /// resolved (e.g. because the metadata provider is unreachable) or the file cannot be opened.
+    pub fn try_get(
+        &self,
+        registry_key: RegistryKey,
+    ) -> Result<Option<File>, OntologyRegistryError> {
+        let resolved_registry_key = self.resolve_registry_key(&registry_key)?;
+
+        match self.find_file(&self.file_name(&resolved_registry_key)) {
+            Some(path) => Ok(Some(
+                self.open_stored(&path)
+                    .map_err(|err| OntologyRegistryError::io_at(&path, err))?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Registers several ontologies, collecting one result per request instead of stopping at
+    /// the first error.
+    ///
+    /// Requests are processed concurrently by a small pool of worker threads. Each result is
+    /// paired with the index of its request; the returned vector is ordered by that index.
+    pub fn register_many(
+        &self,
+        requests: &[(String, Version, FileType)],
+    ) -> Vec<(usize, Result<File, OntologyRegistryError>)>
+    where
+        MDP: Sync,
+        OP: Sync,
+    {
+        let next_request = AtomicUsize::new(0);
+        let results = Mutex::new(Vec::with_capacity(requests.len()));
+        let worker_count = requests.len().min(MAX_CONCURRENT_REGISTRATIONS);
+
+        thread::scope(|scope| {
+            for _ in 0..worker_count {
+                scope.spawn(|| {
+                    loop {
+                        let index = next_request.fetch_add(1, Ordering::Relaxed);
+                        let Some((ontology_id, version, file_type)) = requests.get(index) else {
+                            break;
+                        };
+
+                        let registry_key =
+                            RegistryKey::new(ontology_id, version.clone(), *file_type);
+                        let result = self.register_in(&self.registry_path, registry_key);
+                        results
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .push((index, result));
+                    }
+                });
+            }
+        });
+
+        let mut results = results.into_inner().unwrap_or_else(|e| e.into_inner());
+        results.sort_by_key(|(index, _)| *index);
+        results
+    }
+
+    /// Registers every ontology in `ids` in the same version and file type, e.g. to fill the
+    /// cache at service startup, and returns the path or error for each id, in the order given.
+    ///
+    /// Up to `concurrency` worker threads (at least one) register the ids concurrently. A failure
+    /// does not stop the others. Repeated ids, including ids that
+    /// [normalize](Self::with_lowercase_ids) to the same one, are registered only once and share
+    /// the result of their first occurrence; a shared failure is reported as
+    /// `OntologyRegistryError::UnableToRegister` with the original reason.
+    pub fn prewarm(
+        &self,
+        ids: &[&str],
+        version: &Version,
+        file_type: &FileType,
+        concurrency: usize,
+    ) -> Vec<(String, Result<PathBuf, OntologyRegistryError>)>
+    where
+        MDP: Sync,
+        OP: Sync,
+    {
+        let mut normalized_ids = Vec::new();
+        let mut unique_ids = Vec::new();
+        let first_occurrences: Vec<usize> = ids
+            .iter()
+            .map(|ontology_id| {
+                let normalized_id = self.normalize_id(ontology_id);
+                match normalized_ids.iter().position(|id| *id == normalized_id) {
+                    Some(position) => position,
+                    None => {
+                        normalized_ids.push(normalized_id);
+                        unique_ids.push(*ontology_id);
+                        unique_ids.len() - 1
+                    }
+                }
+            })
+            .collect();
+
+        let next_id = AtomicUsize::new(0);
+        let results = Mutex::new(Vec::with_capacity(unique_ids.len()));
+        let worker_count = unique_ids.len().min(concurrency.max(1));
+
+        thread::scope(|scope| {
+            for _ in 0..worker_count {
+                scope.spawn(|| {
+                    loop {
+                        let index = next_id.fetch_add(1, Ordering::Relaxed);
+                        let Some(ontology_id) = unique_ids.get(index) else {
+                            break;
+                        };
+
+                        let registry_key =
+                            RegistryKey::new(*ontology_id, version.clone(), *file_type);
+                        let result = self
+                            .register_entry(
+                                &self.registry_path,
+                                registry_key.clone(),
+                                &mut |_, _| {},
+                            )
+                            .map(|entry| entry.path);
+                        if let Err(err) = &result {
+                            self.report_error(&registry_key, err);
+                        }
+                        results
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .push((index, result));
+                    }
+                });
+            }
+        });
+
+        let mut results = results.into_inner().unwrap_or_else(|e| e.into_inner());
+        results.sort_by_key(|(index, _)| *index);
+        let shared: Vec<Result<PathBuf, String>> = results
+            .iter()
+            .map(|(_, result)| result.as_ref().cloned().map_err(ToString::to_string))
+            .collect();
+        let mut results: Vec<_> = results
+            .into_iter()
+            .map(|(_, result)| Some(result))
+            .collect();
+        ids.iter()
+            .zip(first_occurrences)
+            .map(|(ontology_id, position)| {
+                let result = results[position].take().unwrap_or_else(|| {
+                    shared[position]
+                        .clone()
+                        .map_err(|reason| OntologyRegistryError::UnableToRegister { reason })
+                });
+                (ontology_id.to_string(), result)
+            })
+            .collect()
+    }
+
+    /// Stores `content` as the given ontology without going through the ontology provider.
+    ///
+    /// Useful for ontologies generated locally or received by other means. `Version::Latest` is
+    /// resolved through the metadata provider first. The content is written atomically and
+    /// replaces any file already registered under the same key.
+    pub fn register_bytes(
+        &self,
+        ontology_id: impl TryInto<OntologyId, Error: Into<OntologyRegistryError>>,
+        version: &Version,
+        file_type: &FileType,
+        content: &[u8],
+    ) -> Result<File, OntologyRegistryError> {
+        let ontology_id = ontology_id.try_into().map_err(Into::into)?;
+        let registry_key = RegistryKey::new(ontology_id, version.clone(), *file_type);
+        let path = self.store_local(&registry_key, &mut &content[..])?;
+
+        self.open_stored(&path)
+            .map_err(|err| OntologyRegistryError::io_at(&path, err))
+    }
+
+    /// Copies an existing ontology file at `src` into the registry without going through the
+    /// ontology provider, e.g. to bootstrap a cache from earlier build artifacts. Returns the
+    /// path it is stored under.
+    ///
+    /// Behaves like [`Self::register_bytes`], but streams the file instead of holding it in
+    /// memory: `Version::Latest` is resolved through the metadata provider, the copy is atomic
+    /// and replaces any file already registered under the same key. `src` is left untouched.
+    pub fn import_file(
+        &self,
+        src: &Path,
+        ontology_id: impl TryInto<OntologyId, Error: Into<OntologyRegistryError>>,
+        version: &Version,
+        file_type: &FileType,
+    ) -> Result<PathBuf, OntologyRegistryError> {
+        let ontology_id = ontology_id.try_into().map_err(Into::into)?;
+        let mut file = File::open(src).map_err(|err| OntologyRegistryError::io_at(src, err))?;
+        self.store_local(
+            &RegistryKey::new(ontology_id, version.clone(), *file_type),
+            &mut file,
+        )
+    }
+
+    /// Downloads the ontology described by `metadata` straight from its `*_file_location` for
+    /// `file_type` and returns the path it is stored under, bypassing the ontology provider and
+    /// its URL conventions. The download uses the provider set with [`Self::with_url_provider`].
+    ///
+    /// The file is registered under the metadata's id and version, so `get` and `list` find it
+    /// like any other registration, and replaces a file already registered under that key.
+    ///
+    /// # Errors
+    /// Returns `OntologyRegistryError::ProvidingOntology` if the metadata has no download
+    /// location for `file_type`, and `Offline` in [offline mode](Self::with_offline).
+    #[cfg(feature = "http")]
+    pub fn register_from_metadata(
+        &self,
+        metadata: &OntologyMetadata,
+        file_type: &FileType,
+    ) -> Result<PathBuf, OntologyRegistryError> {
+        let registry_key = self.normalize_key(RegistryKey::new(
+            metadata.ontology_id.as_str(),
+            Version::Declared(metadata.version.clone()),
+            *file_type,
+        ));
+        let result = self
+            .checked_key(registry_key.clone())
+            .and_then(|registry_key| self.download_from_metadata(&registry_key, metadata));
+        if let Err(err) = &result {
+            self.report_error(&registry_key, err);
+        }
+        result
+    }
+
+    #[cfg(feature = "http")]
+    fn download_from_metadata(
+        &self,
+        registry_key: &RegistryKey,
+        metadata: &OntologyMetadata,
+    ) -> Result<PathBuf, OntologyRegistryError> {
+        let url = metadata
+            .file_location(registry_key.file_type())
+            .ok_or_else(|| OntologyRegistryError::ProvidingOntology {
+                reason: format!(
+                    "Metadata of {} has no download location for {}",
+                    metadata.ontology_id,
+                    registry_key.file_type()
+                ),
+            })?;
+        if self.offline {
+            return Err(Self::offline_error(registry_key));
+        }
+
+        let root = &self.registry_path;
+        if !root.exists() {
+            fs::create_dir_all(root).map_err(|_| OntologyRegistryError::NoRegistry)?;
+        }
+        let out_path = root.join(self.checked_file_name(registry_key)?);
+
+        info!(
+            target: DOWNLOAD_LOG_TARGET,
+            "Downloading {registry_key} from {url}"
+        );
+        let download_start = Instant::now();
+        let mut reader = self.url_provider.provide_ontology(
+            registry_key.ontology_id(),
+            url,
+            registry_key.version(),
+        )?;
+
+        metrics::record_cache_miss(registry_key);
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let _file_lock = self.lock_file(root)?;
+        let bytes_written =
+            self.write_ontology_file(root, registry_key, &mut reader, &CacheValidators::default())?;
+        self.record_download(registry_key, download_start, bytes_written);
+
+        Ok(out_path)
+    }
+
+    /// Resolves `registry_key` and atomically stores the content of `reader` under it in the
+    /// registry path. Returns the path of the stored file.
+    fn store_local(
+        &self,
+        registry_key: &RegistryKey,
+        reader: &mut impl Read,
+    ) -> Result<PathBuf, OntologyRegistryError> {
+        let registry_key = self.resolve_registry_key(registry_key)?;
+
+        if !self.registry_path.exists() {
+            fs::create_dir_all(&self.registry_path)
+                .map_err(|_| OntologyRegistryError::NoRegistry)?;
+        }
+
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let _file_lock = self.lock_file(&self.registry_path)?;
+        self.write_ontology_file(
+            &self.registry_path,
+            &registry_key,
+            reader,
+            &CacheValidators::default(),
+        )?;
+
+        Ok(self.registry_path.join(self.file_name(&registry_key)))
+    }
+
+    /// Returns the cached ontology, downloading and registering it first if it is not present.
+    ///
+    /// `Version::Latest` is resolved once and the resolved release is used for both the lookup
+    /// and the registration.
+    pub fn get_or_register(
+        &self,
+        ontology_id: impl TryInto<OntologyId, Error: Into<OntologyRegistryError>>,
+        version: &Version,
+        file_type: &FileType,
+    ) -> Result<File, OntologyRegistryError> {
+        let ontology_id = ontology_id.try_into().map_err(Into::into)?;
+        let registry_key =
+            self.resolve_registry_key(&RegistryKey::new(ontology_id, version.clone(), *file_type))?;
+
+        match self.get(registry_key.clone()) {
+            Some(file) => Ok(file),
+            None => self.register_in(&self.registry_path, registry_key),
+        }
+    }
+
+    /// Copies an ontology to `dest`, e.g. into the input directory of a pipeline, registering it
+    /// first if it is not present.
+    ///
+    /// Parent directories of `dest` are created as needed and an existing file at `dest` is
+    /// overwritten. The registered file stays in place.
+    pub fn export_to(
+        &self,
+        ontology_id: &str,
+        version: &Version,
+        file_type: &FileType,
+        dest: &Path,
+    ) -> Result<(), OntologyRegistryError> {
+        let registry_key = RegistryKey::new(ontology_id, version.clone(), *file_type);
+        let result = self
+            .register_entry(&self.registry_path, registry_key.clone(), &mut |_, _| {})
+            .and_then(|entry| {
+                if let Some(parent) = dest.parent() {
+                    fs::create_dir_all(parent)
+                        .map_err(|err| OntologyRegistryError::io_at(parent, err))?;
+                }
+                io::copy(
+                    &mut self
+                        .read_stored(&entry.path)
+                        .map_err(|err| OntologyRegistryError::io_at(&entry.path, err))?,
+                    &mut File::create(dest)
+                        .map_err(|err| OntologyRegistryError::io_at(dest, err))?,
+                )?;
+                Ok(())
+            });
+        if let Err(err) = &result {
+            self.report_error(&registry_key, err);
+        }
+        result
+    }
+
+    /// Lists the registered ontologies matching `ontology_id` and `file_type`.
+    ///
+    /// Ids are compared case-insensitively. `None` matches everything, so passing `None` for
+    /// both filters behaves like [`OntologyRegistration::list`].
+    pub fn list_filtered(
+        &self,
+        ontology_id: Option<&str>,
+        file_type: Option<&FileType>,
+    ) -> Result<Vec<RegistryKey>, OntologyRegistryError> {
+        let ontology_id = ontology_id.map(|id| self.normalize_id(id));
+        let mut registry_keys = self.list()?;
+        registry_keys.retain(|key| {
+            ontology_id
+                .as_deref()
+                .is_none_or(|id| key.ontology_id().eq_ignore_ascii_case(id))
+                && file_type.is_none_or(|file_type| key.file_type() == *file_type)
+        });
+        Ok(registry_keys)
+    }
+
+    /// Lists the registered ontologies like [`OntologyRegistration::list`], but leaves out the
+    /// `latest` aliases maintained by [`Self::with_latest_alias`] and `Version::Unversioned`
+    /// files, which are not tied to a release.
+    pub fn list_releases(&self) -> Result<Vec<RegistryKey>, OntologyRegistryError> {
+        let mut registry_keys = self.list()?;
+        registry_keys.retain(|key| matches!(key.version(), Version::Declared(_)));
+        Ok(registry_keys)
+    }
+
+    /// Returns the versions of `ontology_id` registered as `file_type`, oldest first.
+    ///
+    /// Versions are ordered like [`Version`], i.e. by their string, which puts date-based
+    /// releases in chronological order. `latest` aliases and unversioned files are left out.
+    pub fn local_versions(
+        &self,
+        ontology_id: &str,
+        file_type: &FileType,
+    ) -> Result<Vec<String>, OntologyRegistryError> {
+        let mut versions: Vec<Version> = self
+            .list_filtered(Some(ontology_id), Some(file_type))?
+            .into_iter()
+            .map(|key| key.into_parts().1)
+            .filter(|version| matches!(version, Version::Declared(_)))
+            .collect();
+        versions.sort();
+        versions.dedup();
+
+        Ok(versions.iter().map(Version::to_string).collect())
+    }
+
+    /// Returns the newest version of `ontology_id` registered as `file_type`, or `None` if no
+    /// release is registered. Ordered like [`Self::local_versions`].
+    pub fn latest_local(
+        &self,
+        ontology_id: &str,
+        file_type: &FileType,
+    ) -> Result<Option<String>, OntologyRegistryError> {
+        Ok(self.local_versions(ontology_id, file_type)?.pop())
+    }
+
+    /// Serializes the releases in the registry to a JSON list of [`ManifestEntry`]s, e.g. to pin
+    /// the ontologies of a reproducible build.
+    ///
+    /// `latest` aliases and unversioned files are left out. Paths are relative to the registry
+    /// root.
+    pub fn export_manifest(&self) -> Result<String, OntologyRegistryError> {
+        let mut registry_keys = self.list_releases()?;
+        registry_keys.sort();
+
+        let mut entries = Vec::with_capacity(registry_keys.len());
+        for registry_key in registry_keys {
+            let path = self.file_name(&registry_key);
+            let size = self.find_file(&path).map_or(Ok(0), |file| {
+                fs::metadata(file).map(|metadata| metadata.len())
+            })?;
+            let (ontology_id, version, file_type) = registry_key.into_parts();
+            entries.push(ManifestEntry {
+                ontology_id,
+                version: version.to_string(),
+                file_type,
+                path,
+                size,
+            });
+        }
+
+        serde_json::to_string_pretty(&entries).map_err(|err| {
+            OntologyRegistryError::UnableToRegister {
+                reason: format!("Unable to serialize manifest: {err}"),
+            }
+        })
+    }
+
+    /// Registers every release listed in a manifest produced by [`Self::export_manifest`].
+    ///
+    /// Releases already present are not downloaded again. Stops at the first release that
+    /// cannot be registered.
+    pub fn import_manifest(&self, manifest: &str) -> Result<(), OntologyRegistryError> {
+        let entries: Vec<ManifestEntry> = serde_json::from_str(manifest).map_err(|err| {
+            OntologyRegistryError::UnableToRegister {
+                reason: format!("Unable to parse manifest: {err}"),
+            }
+        })?;
+
+        for entry in entries {
+            self.register(RegistryKey::new(
+                entry.ontology_id,
+                Version::from(entry.version.as_str()),
+                entry.file_type,
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes a lock file to `lock` that pins every release in the registry to its exact version
+    /// and SHA-256 checksum, so [`Self::install_from_lock`] can reproduce the registry elsewhere.
+    ///
+    /// `latest` aliases and unversioned files are left out. Entries are sorted, so an unchanged
+    /// registry always yields the same file.
+    pub fn write_lock_file(&self, lock: &Path) -> Result<(), OntologyRegistryError> {
+        let mut registry_keys = self.list_releases()?;
+        registry_keys.sort();
+
+        let mut entries = Vec::with_capacity(registry_keys.len());
+        for registry_key in registry_keys {
+            let file_name = self.file_name(&registry_key);
+            let path = self
+                .find_file(&file_name)
+                .unwrap_or_else(|| self.registry_path.join(file_name));
+            let sha256 = self
+                .stored_sha256(&path)
+                .map_err(|err| OntologyRegistryError::io_at(&path, err))?;
+            let (ontology_id, version, file_type) = registry_key.into_parts();
+            entries.push(LockEntry {
+                ontology_id,
+                version: version.to_string(),
+                file_type,
+                sha256,
+            });
+        }
+
+        let raw = serde_json::to_string_pretty(&entries).map_err(|err| {
+            OntologyRegistryError::UnableToRegister {
+                reason: format!("Unable to serialize lock file: {err}"),
+            }
+        })?;
+        fs::write(lock, raw).map_err(|err| OntologyRegistryError::io_at(lock, err))?;
+        Ok(())
+    }
+
+    /// Registers exactly the releases pinned in a lock file written by [`Self::write_lock_file`].
+    ///
+    /// Versions are always taken from the lock file, never resolved as `Version::Latest`.
+    /// Releases already present are not downloaded again. Stops at the first release that
+    /// cannot be registered or whose checksum differs from the pinned one; a file with a
+    /// differing checksum is removed from the registry path, so it is not served later.
+    pub fn install_from_lock(&self, lock: &Path) -> Result<(), OntologyRegistryError> {
+        let raw =
+            fs::read_to_string(lock).map_err(|err| OntologyRegistryError::io_at(lock, err))?;
+        let entries: Vec<LockEntry> =
+            serde_json::from_str(&raw).map_err(|err| OntologyRegistryError::UnableToRegister {
+                reason: format!("Unable to parse lock file '{}': {err}", lock.display()),
+            })?;
+
+        for entry in entries {
+            let registry_key = RegistryKey::new(
+                entry.ontology_id,
+                Version::Declared(entry.version),
+                entry.file_type,
+            );
+            let path = self
+                .register_entry(&self.registry_path, registry_key.clone(), &mut |_, _| {})?
+                .path;
+
+            let actual = self
+                .stored_sha256(&path)
+                .map_err(|err| OntologyRegistryError::io_at(&path, err))?;
+            if actual != entry.sha256 {
+                if path.starts_with(&self.registry_path) {
+                    let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+                    let _file_lock = self.lock_file(&self.registry_path)?;
+                    self.remove_ontology_file(&path)?;
+                    Self::forget_file_names(&self.registry_path, &[path])?;
+                }
+                return Err(OntologyRegistryError::UnableToRegister {
+                    reason: format!(
+                        "Checksum of {registry_key} is {actual}, but the lock file pins {}",
+                        entry.sha256
+                    ),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the location of a registered ontology, for callers that need a path rather than
+    /// the reader returned by [`OntologyRegistration::get`].
+    ///
+    /// Returns `None` if the ontology is not registered or if the version could not be resolved.
+    pub fn path(&self, registry_key: RegistryKey) -> Option<PathBuf> {
+        let resolved_registry_key = self.resolve_registry_key(&registry_key).ok()?;
+
+        self.find_file(&self.file_name(&resolved_registry_key))
+    }
+
+    /// Returns the path under the registry path where the given ontology is stored once
+    /// registered, whether or not it exists yet, e.g. for logging or to prepare its directory.
+    ///
+    /// `Version::Latest` is resolved like [`Self::resolve_version`]. Read paths are not
+    /// considered, as `register` only ever writes to the registry path.
+    pub fn path_for(
+        &self,
+        ontology_id: &str,
+        version: &Version,
+        file_type: &FileType,
+    ) -> Result<PathBuf, OntologyRegistryError> {
+        let resolved_registry_key =
+            self.resolve_registry_key(&RegistryKey::new(ontology_id, version.clone(), *file_type))?;
+        let file_name = self.file_name(&resolved_registry_key);
+
+        Ok(Self::stored_path(&self.registry_path, &file_name)
+            .unwrap_or_else(|| self.registry_path.join(file_name)))
+    }
+
+    /// Returns when the registered file of an ontology was last written or confirmed unchanged
+    /// by [`refresh`](Self::refresh), e.g. to show how fresh the cache is or to decide whether
+    /// to refresh it.
+    ///
+    /// The time is recorded in the registry's manifest. For files the registry did not write
+    /// itself, the modification time of the file is used instead. Returns `None` if the
+    /// ontology is not registered, the version could not be resolved, or neither is known.
+    pub fn cached_at(
+        &self,
+        ontology_id: &str,
+        version: &Version,
+        file_type: &FileType,
+    ) -> Option<SystemTime> {
+        let resolved_registry_key = self
+            .resolve_registry_key(&RegistryKey::new(ontology_id, version.clone(), *file_type))
+            .ok()?;
+        let file_name = self.file_name(&resolved_registry_key);
+        let path = self.find_file(&file_name)?;
+        let checked_at = RegistryManifest::load(&self.registry_path)
+            .ok()
+            .and_then(|manifest| manifest.checked_at.get(&file_name).copied());
+        checked_at.or_else(|| {
+            fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        })
+    }
+
+    /// Retrieves a registered ontology like [`OntologyRegistration::get`], but first
+    /// [refreshes](Self::refresh) it if its [`Self::cached_at`] is longer ago than
+    /// [`Self::with_refresh_after`] allows. Without that option it behaves exactly like `get`.
+    ///
+    /// A refreshed file counts as fresh again, even if the provider reported it unchanged. If
+    /// the refresh fails, e.g. because the registry is offline or the provider is unreachable,
+    /// the stale file is served and the failure is logged. Like `get`, returns `None` if the
+    /// ontology is not registered; nothing is downloaded for it.
+    pub fn get_fresh(&self, registry_key: RegistryKey) -> Option<File> {
+        let Some(refresh_after) = self.refresh_after else {
+            return self.get(registry_key);
+        };
+
+        let (ontology_id, version, file_type) = registry_key.clone().into_parts();
+        let cached_at = self.cached_at(&ontology_id, &version, &file_type)?;
+        if cached_at.elapsed().is_ok_and(|age| age > refresh_after) {
+            debug!(
+                target: CACHE_LOG_TARGET,
+                "{registry_key} is older than {refresh_after:?}, refreshing"
+            );
+            match self.refresh(registry_key.clone()) {
+                Ok(file) => return Some(file),
+                Err(err) => warn!(
+                    target: CACHE_LOG_TARGET,
+                    "Serving stale {registry_key}, refreshing it failed: {err}"
+                ),
+            }
+        }
+
+        self.get(registry_key)
+    }
+
+    /// Downloads an ontology again unless the provider reports it unchanged.
+    ///
+    /// If the ontology is already registered, the cache validators recorded at its last download
+    /// are sent along, and a `304 Not Modified` keeps the existing file. Otherwise, or if the
+    /// content changed, the file is replaced atomically. Providers without conditional request
+    /// support always download.
+    pub fn refresh(&self, registry_key: RegistryKey) -> Result<File, OntologyRegistryError> {
+        if self.offline {
+            return Err(Self::offline_error(&registry_key));
+        }
+
+        let root = &self.registry_path;
+        if !root.exists() {
+            fs::create_dir_all(root).map_err(|_| OntologyRegistryError::NoRegistry)?;
+        }
+
+        let resolved_registry_key = self.resolve_registry_key(&registry_key)?;
+        let registry_file_name = self.file_name(&resolved_registry_key);
+        let out_path = root.join(&registry_file_name);
+
+        let validators = if out_path.is_file() {
+            RegistryManifest::load(root)?
+                .cache_validators
+                .remove(&registry_file_name)
+                .unwrap_or_default()
+        } else {
+            CacheValidators::default()
+        };
+
+        let metadata = self
+            .metadata_provider
+            .provide_metadata(resolved_registry_key.ontology_id())
+            .ok();
+        let provider_file_name = self.provider_file_name(&resolved_registry_key, metadata.as_ref());
+
+        info!(
+            target: DOWNLOAD_LOG_TARGET,
+            "Refreshing {resolved_registry_key} from {provider_file_name}"
+        );
+        let download_start = Instant::now();
+        match self.ontology_provider.provide_ontology_if_modified(
+            resolved_registry_key.ontology_id(),
+            &provider_file_name,
+            resolved_registry_key.version(),
+            &validators,
+        )? {
+            ConditionalFetch::NotModified => {
+                debug!(
+                    target: CACHE_LOG_TARGET,
+                    "{resolved_registry_key} is unchanged at {}",
+                    out_path.display()
+                );
+                self.record_cache_hit(&resolved_registry_key);
+                let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+                let _file_lock = self.lock_file(root)?;
+                let mut manifest = RegistryManifest::load(root)?;
+                manifest
+                    .checked_at
+                    .insert(registry_file_name.clone(), SystemTime::now());
+                manifest.save(root)?;
+            }
+            ConditionalFetch::Modified {
+                mut reader,
+                validators,
+                ..
+            } => {
+                metrics::record_cache_miss(&resolved_registry_key);
+                let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+                let _file_lock = self.lock_file(root)?;
+                let bytes_written = self.write_ontology_file(
+                    root,
+                    &resolved_registry_key,
+                    &mut reader,
+                    &validators,
+                )?;
+                self.record_download(&resolved_registry_key, download_start, bytes_written);
+            }
+        }
+
+        self.open_stored(&out_path)
+            .map_err(|err| OntologyRegistryError::io_at(&out_path, err))
+    }
+
+    /// Reports where an ontology would be stored and whether it is registered already, without
+    /// downloading anything.
+    ///
+    /// Only `Version::Latest` causes a request, to the metadata provider. The ontology provider
+    /// is never called.
+    pub fn plan_register(
+        &self,
+        ontology_id: &str,
+        version: &Version,
+        file_type: &FileType,
+    ) -> Result<RegisterPlan, OntologyRegistryError> {
+        let resolved_registry_key =
+            self.resolve_registry_key(&RegistryKey::new(ontology_id, version.clone(), *file_type))?;
+        let file_name = self.file_name(&resolved_registry_key);
+        let stored_path = Self::stored_path(&self.registry_path, &file_name);
+
+        Ok(RegisterPlan {
+            already_present: stored_path.is_some(),
+            resolved_version: resolved_registry_key.version().to_string(),
+            target_path: stored_path.unwrap_or_else(|| self.registry_path.join(file_name)),
+        })
+    }
+
+    /// Checks whether an ontology is present in the registry without opening it.
+    ///
+    /// `Version::Latest` is resolved through the metadata provider first. Returns `false` if
+    /// the version could not be resolved.
+    pub fn contains(&self, ontology_id: &str, version: &Version, file_type: &FileType) -> bool {
+        let registry_key = RegistryKey::new(ontology_id, version.clone(), *file_type);
+
+        self.resolve_registry_key(&registry_key)
+            .is_ok_and(|key| self.find_file(&self.file_name(&key)).is_some())
+    }
+
+    /// Removes every ontology file from the registry directory and returns how many were removed.
+    ///
+    /// Like `list`, only files are considered; subdirectories and hidden files are left in place,
+    /// except for the registry's own manifest, which is reset. An empty or missing registry
+    /// directory is not an error.
+    pub fn clear(&self) -> Result<usize, OntologyRegistryError> {
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let _file_lock = self.lock_file(&self.registry_path)?;
+
+        let files = self.ontology_files(&self.registry_path)?;
+        for (path, _) in &files {
+            self.remove_ontology_file(path)?;
+        }
+
+        let manifest_path = self.registry_path.join(MANIFEST_FILE_NAME);
+        if manifest_path.exists() {
+            fs::remove_file(&manifest_path)
+                .map_err(|err| OntologyRegistryError::io_at(&manifest_path, err))?;
+        }
+
+        Ok(files.len())
+    }
+
+    /// Removes every release of `ontology_id`, optionally only those of `file_type`, from the
+    /// registry directory and returns how many files were removed.
+    ///
+    /// Ids are compared case-insensitively, and `latest` aliases are removed as well. Hidden
+    /// files, such as the manifest, and leftovers of interrupted registrations are skipped, as
+    /// are files whose name cannot be parsed. Read paths are never modified.
+    pub fn unregister_all(
+        &self,
+        ontology_id: impl TryInto<OntologyId, Error: Into<OntologyRegistryError>>,
+        file_type: Option<&FileType>,
+    ) -> Result<usize, OntologyRegistryError> {
+        let ontology_id: OntologyId = ontology_id.try_into().map_err(Into::into)?;
+
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let _file_lock = self.lock_file(&self.registry_path)?;
+
+        let manifest = RegistryManifest::load(&self.registry_path)?;
+        let mut removed = Vec::new();
+        for (path, _) in self.ontology_files(&self.registry_path)? {
+            let Some(file_name) = Self::relative_file_name(&self.registry_path, &path) else {
+                continue;
+            };
+            let registry_key = match manifest.hashed_file_names.get(&file_name) {
+                Some(registry_key) => registry_key.clone(),
+                None => match self.parse_file_name(&file_name) {
+                    Ok(registry_key) => registry_key,
+                    Err(_) => continue,
+                },
+            };
+            if registry_key
+                .ontology_id()
+                .eq_ignore_ascii_case(ontology_id.as_str())
+                && file_type.is_none_or(|file_type| registry_key.file_type() == *file_type)
+            {
+                self.remove_ontology_file(&path)?;
+                removed.push(path);
+            }
+        }
+
+        Self::forget_file_names(&self.registry_path, &removed)?;
+
+        Ok(removed.len())
+    }
+
+    /// Deletes temporary files and directories left behind by interrupted registrations and
+    /// returns how many were removed.
+    ///
+    /// Without [`Self::with_file_lock`], only the current process's registrations are guarded,
+    /// so this must not run while another process is registering into the same directory.
+    pub fn cleanup_temp_files(&self) -> Result<usize, OntologyRegistryError> {
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let _file_lock = self.lock_file(&self.registry_path)?;
+
+        let temporaries = Self::stray_temporaries(&self.registry_path)?;
+        for path in &temporaries {
+            if path.is_dir() {
+                fs::remove_dir_all(path).map_err(|err| OntologyRegistryError::io_at(path, err))?;
+            } else {
+                fs::remove_file(path).map_err(|err| OntologyRegistryError::io_at(path, err))?;
+            }
+        }
+
+        Ok(temporaries.len())
+    }
+
+    /// Checks the integrity of the registry directory.
+    ///
+    /// Every file with a checksum recorded at registration is hashed again and reported as
+    /// [`VerifyReport::Ok`] or [`VerifyReport::Corrupt`]. Files the registry did not write
+    /// itself have no checksum and are skipped. Leftovers of interrupted registrations are
+    /// reported as [`VerifyReport::StrayTemporary`].
+    pub fn verify(&self) -> Result<Vec<VerifyReport>, OntologyRegistryError> {
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let _file_lock = self.lock_file(&self.registry_path)?;
+        let root = &self.registry_path;
+        let manifest = RegistryManifest::load(root)?;
+
+        let mut reports = Vec::new();
+        for (path, metadata) in self.ontology_files(root)? {
+            let Some(expected) = Self::relative_file_name(root, &path)
+                .and_then(|file_name| manifest.checksums.get(&file_name))
+            else {
+                continue;
+            };
+            if !metadata.is_file() {
+                continue;
+            }
+
+            let actual = self
+                .stored_sha256(&path)
+                .map_err(|err| OntologyRegistryError::io_at(&path, err))?;
+
+            if actual == *expected {
+                reports.push(VerifyReport::Ok { path });
+            } else {
+                reports.push(VerifyReport::Corrupt {
+                    path,
+                    expected: expected.clone(),
+                    actual,
+                });
+            }
+        }
+
+        reports.extend(
+            Self::stray_temporaries(root)?
+                .into_iter()
+                .map(|path| VerifyReport::StrayTemporary { path }),
+        );
+
+        Ok(reports)
+    }
+
+    /// Removes every ontology file whose modification time is older than `max_age` and returns
+    /// the paths of the removed files.
+    ///
+    /// Subdirectories and hidden files are left in place. A missing registry directory is not
+    /// an error.
+    pub fn prune_older_than(
+        &self,
+        max_age: Duration,
+    ) -> Result<Vec<PathBuf>, OntologyRegistryError> {
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let _file_lock = self.lock_file(&self.registry_path)?;
+
+        let Some(cutoff) = SystemTime::now().checked_sub(max_age) else {
+            return Ok(Vec::new());
+        };
+
+        let mut removed = Vec::new();
+        for (path, metadata) in self.ontology_files(&self.registry_path)? {
+            if metadata.modified()? < cutoff {
+                self.remove_ontology_file(&path)?;
+                removed.push(path);
+            }
+        }
+
+        Self::forget_file_names(&self.registry_path, &removed)?;
+
+        Ok(removed)
+    }
+
+    /// Reads the header block of a cached OBO file without parsing its term stanzas.
+    ///
+    /// `Version::Latest` is resolved through the metadata provider first. The ontology must
+    /// already be registered as `FileType::Obo`; nothing is downloaded.
+    ///
+    /// # Errors
+    ///
+    /// Returns `OntologyRegistryError::ReadingOboHeader` if the file is not in the registry or
+    /// its header is malformed.
+    pub fn read_obo_header(
+        &self,
+        ontology_id: &str,
+        version: &Version,
+    ) -> Result<OboHeader, OntologyRegistryError> {
+        let registry_key = self.resolve_registry_key(&RegistryKey::new(
+            ontology_id,
+            version.clone(),
+            FileType::Obo,
+        ))?;
+        let file_name = self.file_name(&registry_key);
+        let file_path = self
+            .find_file(&file_name)
+            .unwrap_or_else(|| self.registry_path.join(file_name));
+
+        let file = self.read_stored(&file_path).map_err(|err| {
+            OntologyRegistryError::ReadingOboHeader {
+                reason: format!("Unable to open '{}': {}", file_path.display(), err),
+            }
+        })?;
+
+        OboHeader::from_reader(file)
+    }
+
+    /// Returns the concrete release `version` refers to, e.g. to build paths or log what
+    /// `Version::Latest` currently means.
+    ///
+    /// `Version::Latest` is resolved through the metadata provider, a declared version is
+    /// returned unchanged without any lookup. In [offline mode](Self::with_offline),
+    /// `Version::Latest` resolves to the newest release registered as `file_type`, like
+    /// `register` and `get` do. `Version::Unversioned` has no release and is returned as
+    /// `"unversioned"`.
+    pub fn resolve_version(
+        &self,
+        ontology_id: &str,
+        version: &Version,
+        file_type: &FileType,
+    ) -> Result<String, OntologyRegistryError> {
+        let ontology_id = &self.checked_id(ontology_id)?;
+        match version {
+            Version::Latest if self.offline => self.resolve_latest_local(&RegistryKey::new(
+                ontology_id,
+                Version::Latest,
+                *file_type,
+            )),
+            Version::Latest => {
+                let meta_data = self.metadata_provider.provide_metadata(ontology_id)?;
+                debug!(
+                    target: RESOLVE_LOG_TARGET,
+                    "Resolved latest {ontology_id} to {}", meta_data.version
+                );
+                Ok(meta_data.version)
+            }
+            Version::Declared(v) => Ok(v.to_string()),
+            Version::Unversioned => Ok(version.to_string()),
+        }
+    }
+
+    /// Returns `registry_key` with its version resolved to a declared release.
+    ///
+    /// Fails with `UnsafeFileName` if the resolved key cannot be stored safely.
+    fn resolve_registry_key(
+        &self,
+        registry_key: &RegistryKey,
+    ) -> Result<RegistryKey, OntologyRegistryError> {
+        let registry_key = &self.checked_key(registry_key.clone())?;
+        let (ontology_id, resolved_version) = match registry_key.version() {
+            Version::Unversioned => {
+                self.checked_file_name(registry_key)?;
+                return Ok(registry_key.clone());
+            }
+            Version::Latest if self.offline => (
+                registry_key.ontology_id().to_string(),
+                self.resolve_latest_local(registry_key)?,
+            ),
+            Version::Latest if self.provider_latest => (
+                registry_key.ontology_id().to_string(),
+                self.resolve_latest_from_provider(registry_key)?,
+            ),
+            Version::Latest if self.follow_replaced_by => {
+                let (ontology_id, metadata) = self.latest_metadata(registry_key.ontology_id())?;
+                (ontology_id, metadata.version)
+            }
+            version => (
+                registry_key.ontology_id().to_string(),
+                self.resolve_version(
+                    registry_key.ontology_id(),
+                    version,
+                    &registry_key.file_type(),
+                )?,
+            ),
+        };
+
+        let resolved_registry_key = RegistryKey::new(
+            ontology_id,
+            Version::Declared(resolved_version),
+            registry_key.file_type(),
+        );
+        self.checked_file_name(&resolved_registry_key)?;
+        Ok(resolved_registry_key)
+    }
+
+    /// Resolves `Version::Latest` of `registry_key` through
+    /// [`OntologyProviding::latest_version`], see [`Self::with_provider_latest`].
+    fn resolve_latest_from_provider(
+        &self,
+        registry_key: &RegistryKey,
+    ) -> Result<String, OntologyRegistryError> {
+        let provider_file_name = self.provider_file_name(registry_key, None);
+        let version = self
+            .ontology_provider
+            .latest_version(registry_key.ontology_id(), &provider_file_name)?;
+        debug!(
+            target: RESOLVE_LOG_TARGET,
+            "Resolved latest {} to {version} through the ontology provider",
+            registry_key.ontology_id()
+        );
+        Ok(version)
+    }
+
+    /// Resolves `Version::Latest` of `registry_key` to the newest local release of its file type.
+    fn resolve_latest_local(
+        &self,
+        registry_key: &RegistryKey,
+    ) -> Result<String, OntologyRegistryError> {
+        let newest = self
+            .latest_local(registry_key.ontology_id(), &registry_key.file_type())?
+            .ok_or_else(|| Self::offline_error(registry_key))?;
+        debug!(
+            target: RESOLVE_LOG_TARGET,
+            "Resolved latest {} to local release {newest}",
+            registry_key.ontology_id()
+        );
+        Ok(newest)
+    }
+
+    /// Records a failed registration of `registry_key` in the log, the metrics and the observer.
+    fn report_error(&self, registry_key: &RegistryKey, err: &OntologyRegistryError) {
+        warn!(target: ERROR_LOG_TARGET, "Registering {registry_key} failed: {err}");
+        metrics::record_error(err);
+        if let Some(observer) = &self.observer {
+            observer.on_error(registry_key, err);
+        }
+    }
+
+    /// Records a finished download of `registry_key` that started at `download_start` in the
+    /// log, the metrics and the observer.
+    fn record_download(
+        &self,
+        registry_key: &RegistryKey,
+        download_start: Instant,
+        bytes_written: u64,
+    ) {
+        let duration = download_start.elapsed();
+        info!(
+            target: DOWNLOAD_LOG_TARGET,
+            "Downloaded {registry_key}: {bytes_written} bytes in {duration:.2?}"
+        );
+        metrics::record_download(registry_key, duration, bytes_written);
+        if let Some(observer) = &self.observer {
+            observer.on_download(registry_key, bytes_written, duration);
+        }
+    }
+
+    /// Records that `registry_key` was served from the registry in the metrics and the observer.
+    fn record_cache_hit(&self, registry_key: &RegistryKey) {
+        metrics::record_cache_hit(registry_key);
+        if let Some(observer) = &self.observer {
+            observer.on_cache_hit(registry_key);
+        }
+    }
+
+    /// Returns `ontology_id` as seen by providers and the file system, see
+    /// [`Self::with_id_normalizer`].
+    fn normalize_id(&self, ontology_id: &str) -> String {
+        match self.id_normalizer {
+            Some(normalizer) => normalizer(ontology_id),
+            None => ontology_id.to_string(),
+        }
+    }
+
+    /// Normalizes `ontology_id` and checks that it is a valid [`OntologyId`]. Every id passed
+    /// to the registry goes through here before it reaches a provider or the file system.
+    fn checked_id(&self, ontology_id: &str) -> Result<String, OntologyRegistryError> {
+        let ontology_id = self.normalize_id(ontology_id);
+        OntologyId::new(&ontology_id)?;
+        Ok(ontology_id)
+    }
+
+    /// Returns `registry_key` with its ontology id normalized and validated by
+    /// [`Self::checked_id`].
+    fn checked_key(&self, registry_key: RegistryKey) -> Result<RegistryKey, OntologyRegistryError> {
+        let (ontology_id, version, file_type) = registry_key.into_parts();
+        Ok(RegistryKey::new(
+            self.checked_id(&ontology_id)?,
+            version,
+            file_type,
+        ))
+    }
+
+    /// Returns `registry_key` with its ontology id normalized.
+    fn normalize_key(&self, registry_key: RegistryKey) -> RegistryKey {
+        if self.id_normalizer.is_none() {
+            return registry_key;
+        }
+        let (ontology_id, version, file_type) = registry_key.into_parts();
+        RegistryKey::new(self.normalize_id(&ontology_id), version, file_type)
+    }
+
+    /// Fetches the metadata `Version::Latest` of `ontology_id` resolves to and returns it along
+    /// with the id it belongs to, which is the replacement of an obsolete ontology if
+    /// [`Self::with_follow_replaced_by`] is enabled.
+    fn latest_metadata(
+        &self,
+        ontology_id: &str,
+    ) -> Result<(String, OntologyMetadata), OntologyRegistryError> {
+        let metadata = self.metadata_provider.provide_metadata(ontology_id)?;
+        let (ontology_id, metadata) = match &metadata.replaced_by {
+            Some(replacement) if metadata.is_obsolete && self.follow_replaced_by => {
+                let replacement = self.normalize_id(replacement);
+                info!(
+                    target: RESOLVE_LOG_TARGET,
+                    "{ontology_id} is obsolete, resolving its replacement {replacement} instead"
+                );
+                let metadata = self.metadata_provider.provide_metadata(&replacement)?;
+                (replacement, metadata)
+            }
+            _ => (ontology_id.to_string(), metadata),
+        };
+        Self::warn_if_obsolete(&ontology_id, &metadata);
+        debug!(
+            target: RESOLVE_LOG_TARGET,
+            "Resolved latest {ontology_id} to {}", metadata.version
+        );
+        Ok((ontology_id, metadata))
+    }
+
+    /// Registers `registry_key` and its dependencies breadth-first, see
+    /// [`Self::register_with_dependencies`].
+    fn register_dependency_tree(
+        &self,
+        registry_key: RegistryKey,
+    ) -> Result<Vec<PathBuf>, OntologyRegistryError> {
+        let file_type = registry_key.file_type();
+        let root = self.register_entry(&self.registry_path, registry_key, &mut |_, _| {})?;
+        let metadata = match root.metadata {
+            Some(metadata) => metadata,
+            None if self.offline => return Err(Self::offline_error(&root.registry_key)),
+            None => self
+                .metadata_provider
+                .provide_metadata(root.registry_key.ontology_id())?,
+        };
+
+        // File names are case-insensitive, so are the ids a cycle is detected by.
+        let mut seen = HashSet::from([root.registry_key.ontology_id().to_lowercase()]);
+        let mut pending = VecDeque::from(metadata.dependencies);
+        let mut paths = vec![root.path];
+        while let Some(dependency) = pending.pop_front() {
+            let dependency = self.normalize_id(&dependency);
+            if !seen.insert(dependency.to_lowercase()) {
+                continue;
+            }
+            let metadata = match self.metadata_provider.provide_metadata(&dependency) {
+                Ok(metadata) => metadata,
+                Err(err) => {
+                    warn!(
+                        target: RESOLVE_LOG_TARGET,
+                        "Skipping dependency {dependency} without metadata: {err}"
+                    );
+                    continue;
+                }
+            };
+            let dependency_key =
+                RegistryKey::new(dependency, Version::Declared(metadata.version), file_type);
+            let entry = self.register_entry(&self.registry_path, dependency_key, &mut |_, _| {})?;
+            paths.push(entry.path);
+            pending.extend(metadata.dependencies);
+        }
+        Ok(paths)
+    }
+
+    /// Logs a warning if `metadata` marks `ontology_id` as obsolete.
+    fn warn_if_obsolete(ontology_id: &str, metadata: &OntologyMetadata) {
+        if !metadata.is_obsolete {
+            return;
+        }
+        match &metadata.replaced_by {
+            Some(replacement) => warn!(
+                target: RESOLVE_LOG_TARGET,
+                "{ontology_id} is obsolete and replaced by {replacement}"
+            ),
+            None => warn!(target: RESOLVE_LOG_TARGET, "{ontology_id} is obsolete"),
+        }
+    }
+
+    /// Builds the error returned when offline mode prevents serving `registry_key`.
+    fn offline_error(registry_key: &RegistryKey) -> OntologyRegistryError {
+        OntologyRegistryError::Offline {
+            ontology_id: registry_key.ontology_id().to_string(),
+            version: registry_key.version().to_string(),
+        }
+    }
+
+    /// Determines the upstream file name to request from the ontology provider, see
+    /// [`OntologyProviding::file_name_for`].
+    fn provider_file_name(
+        &self,
+        registry_key: &RegistryKey,
+        metadata: Option<&OntologyMetadata>,
+    ) -> String {
+        self.ontology_provider.file_name_for(
+            registry_key.ontology_id(),
+            &registry_key.file_type(),
+            metadata,
+        )
+    }
+
+    fn register_file(
+        &self,
+        root: &Path,
+        registry_key: RegistryKey,
+        on_progress: &mut dyn FnMut(u64, Option<u64>),
+    ) -> Result<File, OntologyRegistryError> {
+        let entry = self.register_entry(root, registry_key, on_progress)?;
+        self.open_stored(&entry.path)
+            .map_err(|err| OntologyRegistryError::io_at(&entry.path, err))
+    }
+
+    /// Registers an ontology and reports where it was stored, under which version, the metadata
+    /// fetched while doing so (if any) and whether the file was registered already.
+    fn register_entry(
+        &self,
+        root: &Path,
+        registry_key: RegistryKey,
+        on_progress: &mut dyn FnMut(u64, Option<u64>),
+    ) -> Result<RegistryEntry, OntologyRegistryError> {
+        let registry_key = self.checked_key(registry_key)?;
+        if !root.exists() {
+            fs::create_dir_all(root).map_err(|_| OntologyRegistryError::NoRegistry)?;
+        }
+
+        let mut out_path = root.to_path_buf();
+
+        let (ontology_id, resolved_version, metadata) = match registry_key.version() {
+            Version::Latest if self.offline => (
+                registry_key.ontology_id().to_string(),
+                Version::Declared(self.resolve_latest_local(&registry_key)?),
+                None,
+            ),
+            Version::Latest if self.provider_latest => (
+                registry_key.ontology_id().to_string(),
+                Version::Declared(self.resolve_latest_from_provider(&registry_key)?),
+                None,
+            ),
+            Version::Latest => {
+                let (ontology_id, metadata) = self.latest_metadata(registry_key.ontology_id())?;
+                let version = Version::Declared(metadata.version.clone());
+                (ontology_id, version, Some(metadata))
+            }
+            version => (
+                registry_key.ontology_id().to_string(),
+                version.clone(),
+                None,
+            ),
+        };
+
+        let resolved_registry_key =
+            RegistryKey::new(ontology_id, resolved_version, registry_key.file_type());
+
+        let registry_file_name = self.checked_file_name(&resolved_registry_key)?;
+        out_path.push(registry_file_name.clone());
+
+        if let Some(out_path) = Self::stored_path(root, &registry_file_name) {
+            let registry_file_name =
+                Self::relative_file_name(root, &out_path).unwrap_or(registry_file_name);
+            debug!(
+                target: CACHE_LOG_TARGET,
+                "Cache hit for {resolved_registry_key} at {}",
+                out_path.display()
+            );
+            self.record_cache_hit(&resolved_registry_key);
+            if self.latest_alias {
+                let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+                let _file_lock = self.lock_file(root)?;
+                self.update_latest_alias(
+                    root,
+                    &resolved_registry_key,
+                    &registry_file_name,
+                    registry_key.version() == &Version::Latest,
+                )?;
+            }
+            return Ok(RegistryEntry {
+                registry_key: resolved_registry_key,
+                path: out_path,
+                metadata,
+                was_cached: true,
+            });
+        }
+
+        if self.offline {
+            return Err(Self::offline_error(&resolved_registry_key));
+        }
+        if self
+            .cancel_flag
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::Relaxed))
+        {
+            return Err(OntologyRegistryError::Cancelled);
+        }
+
+        let metadata = metadata.or_else(|| {
+            match self
+                .metadata_provider
+                .provide_metadata(resolved_registry_key.ontology_id())
+            {
+                Ok(metadata) => {
+                    Self::warn_if_obsolete(resolved_registry_key.ontology_id(), &metadata);
+                    Some(metadata)
+                }
+                Err(err) => {
+                    warn!(
+                        target: DOWNLOAD_LOG_TARGET,
+                        "Requesting the default file name for {resolved_registry_key}, its metadata is unavailable: {err}"
+                    );
+                    None
+                }
+            }
+        });
+        // The download location in the metadata belongs to the release it describes.
+        let release_metadata = metadata.as_ref().filter(|metadata| {
+            resolved_registry_key.version() == &Version::Declared(metadata.version.clone())
+        });
+        let provider_file_name = self.provider_file_name(&resolved_registry_key, release_metadata);
+
+        metrics::record_cache_miss(&resolved_registry_key);
+        info!(
+            target: DOWNLOAD_LOG_TARGET,
+            "Downloading {resolved_registry_key} from {provider_file_name}"
+        );
+        let download_start = Instant::now();
+
+        let no_validators = CacheValidators::default();
+        let (ontology_reader, validators, content_length) = match self
+            .ontology_provider
+            .provide_ontology_if_modified(
+                resolved_registry_key.ontology_id(),
+                &provider_file_name,
+                resolved_registry_key.version(),
+                &no_validators,
+            )? {
+            ConditionalFetch::Modified {
+                reader,
+                validators,
+                content_length,
+            } => (reader, validators, content_length),
+            ConditionalFetch::NotModified => {
+                return Err(OntologyRegistryError::ProvidingOntology {
+                    reason: format!(
+                        "Unexpected 304 Not Modified for unconditional request of {provider_file_name}"
+                    ),
+                });
+            }
+        };
+
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let _file_lock = self.lock_file(root)?;
+
+        if out_path.exists() {
+            drop(ontology_reader);
+            return Ok(RegistryEntry {
+                registry_key: resolved_registry_key,
+                path: out_path,
+                metadata,
+                was_cached: true,
+            });
+        }
+
+        let mut ontology_reader = ProgressReader::new(ontology_reader, content_length, on_progress);
+        let bytes_written = self.write_ontology_file(
+            root,
+            &resolved_registry_key,
+            &mut ontology_reader,
+            &validators,
+        )?;
+        drop(ontology_reader);
+
+        self.record_download(&resolved_registry_key, download_start, bytes_written);
+
+        Ok(RegistryEntry {
+            registry_key: resolved_registry_key,
+            path: out_path,
+            metadata,
+            was_cached: false,
+        })
+    }
+}
+
+impl<MDP: OntologyMetadataProviding, OP: OntologyProviding> OntologyRegistration
+    for FileSystemOntologyRegistry<MDP, OP>
+{
+    /// Registers an ontology by downloading it and saving it to the local filesystem.
+    ///
+    /// # Behavior
+    ///
+    /// 1.  Resolves the version (fetching metadata if `Latest` is requested). `Latest` is always
+    ///     resolved fresh against the metadata provider; a previously cached file of an older
+    ///     release is never served in its place.
+    /// 2.  Checks if the file already exists locally. If so, returns the path immediately.
+    /// 3.  Fetches the content from the `OntologyProvider`. The upstream file name is taken from the
+    ///     metadata's download location if available, otherwise `{ontology_id}{file_ending}` is assumed.
+    /// 4.  **Atomic Write:** Streams content chunk-by-chunk into a `.tmp` file, then renames it to the final destination.
+    ///     This prevents other threads/processes from reading incomplete files.
+    ///
+    /// # Errors
+    ///
+    /// Returns `OntologyRegistryError` if:
+    /// * The metadata cannot be resolved.
+    /// * The registry directory cannot be created.
+    /// * The ontology provider fails to return data. A missing upstream file is reported as
+    ///   `OntologyRegistryError::NotFound`.
+    /// * File I/O operations (creation, writing, renaming) fail. These surface as
+    ///   `OntologyRegistryError::Io` with the underlying error as source.
+    fn register(&self, registry_key: RegistryKey) -> Result<File, OntologyRegistryError> {
+        self.register_in(&self.registry_path, registry_key)
+    }
+
+    /// Removes an ontology from the local filesystem registry.
+    ///
+    /// Logs a warning if the version cannot be resolved or if deletion fails.
+    /// This operation is thread-safe regarding the `write_lock`.
+    fn unregister(&self, registry_key: RegistryKey) -> Result<(), OntologyRegistryError> {
+        let resolved_registry_key = self.resolve_registry_key(&registry_key)?;
+
+        let file_name = self.file_name(&resolved_registry_key);
+        let file_path = Self::stored_path(&self.registry_path, &file_name)
+            .unwrap_or_else(|| self.registry_path.join(file_name));
+
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let _file_lock = self.lock_file(&self.registry_path)?;
+
+        if file_path.exists() {
+            self.remove_ontology_file(&file_path)?;
+        }
+
+        Self::forget_file_names(&self.registry_path, &[file_path])
+    }
+
+    /// Retrieves the local filesystem path for a specific ontology.
+    ///
+    /// Returns `None` if the ontology is not currently found in the local registry
+    /// or if the version could not be resolved.
+    fn get(&self, registry_key: RegistryKey) -> Option<File> {
+        let resolved_registry_key = self.resolve_registry_key(&registry_key).ok()?;
+
+        self.open_stored(&self.find_file(&self.file_name(&resolved_registry_key))?)
+            .ok()
+    }
+
+    /// Lists all ontologies currently stored in the registry directory and its read paths.
+    ///
+    /// Files whose names were hashed are reported under the key recorded in the manifest. A key
+    /// stored in several directories is reported once.
+    fn list(&self) -> Result<Vec<RegistryKey>, OntologyRegistryError> {
+        let mut registry_keys = Vec::new();
+        for root in self.search_roots() {
+            for registry_key in self.list_root(root)? {
+                let registry_key = self.normalize_key(registry_key);
+                if !registry_keys.contains(&registry_key) {
+                    registry_keys.push(registry_key);
+                }
+            }
+        }
+        Ok(registry_keys)
+    }
+}
+
+/// Outcome of checking a single entry of the registry directory in
+/// [`FileSystemOntologyRegistry::verify`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyReport {
+    /// The file still matches the checksum recorded when it was written.
+    Ok { path: PathBuf },
+    /// The file no longer matches its recorded checksum.
+    Corrupt {
+        path: PathBuf,
+        expected: String,
+        actual: String,
+    },
+    /// A temporary file or directory left behind by an interrupted registration.
+    StrayTemporary { path: PathBuf },
+}
+
+/// Outcome of [`FileSystemOntologyRegistry::register_entry`].
+struct RegistryEntry {
+    /// The registered key, with its version resolved.
+    registry_key: RegistryKey,
+    path: PathBuf,
+    metadata: Option<OntologyMetadata>,
+    was_cached: bool,
+}
+
+/// Result of [`FileSystemOntologyRegistry::register_detailed`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisteredOntology {
+    /// Location of the registered file.
+    pub path: PathBuf,
+    /// Metadata of the ontology as reported by the metadata provider.
+    pub metadata: OntologyMetadata,
+    /// `true` if the file was already registered and nothing was downloaded.
+    pub was_cached: bool,
+}
+
+/// Result of [`FileSystemOntologyRegistry::plan_register`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisterPlan {
+    /// Location the file is or would be stored at.
+    pub target_path: PathBuf,
+    /// The release the requested version resolved to.
+    pub resolved_version: String,
+    /// `true` if the file is registered already, so registering would not download anything.
+    pub already_present: bool,
+}
+
+/// A release recorded by [`FileSystemOntologyRegistry::export_manifest`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub ontology_id: String,
+    pub version: String,
+    pub file_type: FileType,
+    /// Location of the file relative to the registry root, with `/` as separator.
+    pub path: String,
+    /// Size of the file in bytes.
+    pub size: u64,
+}
+
+/// A release pinned by [`FileSystemOntologyRegistry::write_lock_file`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockEntry {
+    pub ontology_id: String,
+    pub version: String,
+    pub file_type: FileType,
+    /// Hex-encoded SHA-256 digest of the file.
+    pub sha256: String,
+}
+
+/// Forwards writes to `inner` while computing their SHA-256 digest.
+struct HashingWriter<W> {
+    inner: W,
+    hasher: Sha256,
+}
+
+impl<W> HashingWriter<W> {
+    fn new(inner: W) -> Self {
+        HashingWriter {
+            inner,
+            hasher: Sha256::new(),
+        }
+    }
+
+    /// Closes `inner` and returns the hex-encoded digest of everything written.
+    fn finish(self) -> String {
+        self.into_parts().1
+    }
+
+    /// Returns `inner` and the hex-encoded digest of everything written.
+    fn into_parts(self) -> (W, String) {
+        (self.inner, hex(&self.hasher.finalize()))
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Rejects empty content and HTML pages, which servers tend to return with status 200 in place
+/// of a missing file. Intended for [`FileSystemOntologyRegistry::with_validator`].
+///
+/// HTML is recognized by a leading `<!DOCTYPE html` or `<html` tag, ignoring case, whitespace
+/// and a byte order mark. Only the first 4 KiB of the content are read.
+pub fn validate_not_empty_or_html(
+    _file_type: &FileType,
+    content: &mut dyn Read,
+) -> Result<(), String> {
+    let mut prefix = Vec::new();
+    content
+        .take(4096)
+        .read_to_end(&mut prefix)
+        .map_err(|err| format!("content cannot be read: {err}"))?;
+    if prefix.is_empty() {
+        return Err("content is empty".to_string());
+    }
+    let start = prefix.strip_prefix(b"\xef\xbb\xbf").unwrap_or(&prefix);
+    let start = start.trim_ascii_start();
+    let is_html = [&b"<!doctype html"[..], b"<html"].iter().any(|tag| {
+        start
+            .get(..tag.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(tag))
+    });
+    if is_html {
+        return Err("content is an HTML page".to_string());
+    }
+    Ok(())
+}
+
+/// Identifies the content behind a file name, so hard-linked names can be told apart from
+/// copies.
+#[cfg(unix)]
+fn content_key(_path: &Path, metadata: &fs::Metadata) -> (u64, u64) {
+    use std::os::unix::fs::MetadataExt;
+    (metadata.dev(), metadata.ino())
+}
+
+/// Identifies the content behind a file name. Without inode numbers, every name counts as a
+/// copy of its own.
+#[cfg(not(unix))]
+fn content_key(path: &Path, _metadata: &fs::Metadata) -> PathBuf {
+    path.to_path_buf()
+}
+
+/// Returns the hex-encoded SHA-256 digest of everything `reader` yields.
+fn sha256_reader(mut reader: impl Read) -> io::Result<String> {
+    let mut hashing_writer = HashingWriter::new(io::sink());
+    io::copy(&mut reader, &mut hashing_writer)?;
+    Ok(hashing_writer.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::io::{Cursor, Read};
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    #[derive(Clone, Debug)]
+    struct MockMetadataProvider {
+        data: HashMap<String, OntologyMetadata>,
+    }
+
+    impl MockMetadataProvider {
+        fn new() -> Self {
+            Self {
+                data: HashMap::new(),
+            }
+        }
+        fn with_version(self, id: &str, version: &str) -> Self {
+            self.with_metadata(OntologyMetadata {
+                ontology_id: id.to_string(),
+                version: version.to_string(),
+                ..Default::default()
+            })
+        }
+        fn with_metadata(mut self, metadata: OntologyMetadata) -> Self {
+            self.data.insert(metadata.ontology_id.clone(), metadata);
+            self
+        }
+    }
+
+    impl OntologyMetadataProviding for MockMetadataProvider {
+        fn provide_metadata(
+            &self,
+            ontology_id: &str,
+        ) -> Result<OntologyMetadata, OntologyRegistryError> {
+            self.data
+                .get(ontology_id)
+                .cloned()
+                .ok_or(OntologyRegistryError::UnableToRegister {
+                    reason: "Metadata not found".into(),
+                })
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    struct MockOntologyProvider {
+        content: HashMap<String, String>,
+        requested_file_names: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl MockOntologyProvider {
+        fn new() -> Self {
+            Self {
+                content: HashMap::new(),
+                requested_file_names: Arc::new(Mutex::new(Vec::new())),
+            }
+        }
+        fn with_content(mut self, id: &str, content: &str) -> Self {
+            self.content.insert(id.to_string(), content.to_string());
+            self
+        }
+    }
+
+    impl OntologyProviding for MockOntologyProvider {
+        fn provide_ontology(
+            &self,
+            ontology_id: &str,
+            file_name: &str,
+            _version: &Version,
+        ) -> Result<impl Read, OntologyRegistryError> {
+            self.requested_file_names
+                .lock()
+                .unwrap()
+                .push(file_name.to_string());
+            Ok(Cursor::new(
+                self.content
+                    .get(ontology_id)
+                    .cloned()
+                    .ok_or(OntologyRegistryError::UnableToRegister {
+                        reason: "Content not found".into(),
+                    })?
+                    .into_bytes(),
+            ))
+        }
+    }
+
+    #[test]
+    fn test_register_declared_version_success() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+
+        let content = "<rdf>content</rdf>";
         let metadata_mock = MockMetadataProvider::new();
         let ontology_mock = MockOntologyProvider::new().with_content("my_ontology", content);
 
-        let registry =
-            FileSystemOntologyRegistry::new(registry_path.clone(), metadata_mock, ontology_mock);
-        let reg_key = RegistryKey::new(
-            "my_ontology",
-            Version::Declared("1.0".to_string()),
-            FileType::Json,
+        let registry =
+            FileSystemOntologyRegistry::new(registry_path.clone(), metadata_mock, ontology_mock);
+        let reg_key = RegistryKey::new(
+            "my_ontology",
+            Version::Declared("1.0".to_string()),
+            FileType::Json,
+        );
+        let result = registry.register(reg_key);
+
+        assert!(result.is_ok());
+        let mut file = result.unwrap();
+        let mut content = String::new();
+        file.read_to_string(&mut content).unwrap();
+
+        assert_eq!(content, "<rdf>content</rdf>");
+    }
+
+    #[test]
+    fn test_register_latest_version_resolution() {
+        let temp_dir = tempdir().unwrap();
+        let content = "latest_context";
+        let metadata_mock = MockMetadataProvider::new().with_version("my_ontology", "2024-05-05");
+        let ontology_mock = MockOntologyProvider::new().with_content("my_ontology", content);
+
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            metadata_mock,
+            ontology_mock,
+        );
+
+        let reg_key = RegistryKey::new("my_ontology", Version::Latest, FileType::Json);
+        let result = registry.register(reg_key);
+
+        assert!(result.is_ok());
+        let mut file = result.unwrap();
+        let mut loaded_content = String::new();
+        file.read_to_string(&mut loaded_content).unwrap();
+
+        assert_eq!(loaded_content, content);
+    }
+
+    #[test]
+    fn test_register_resolved_returns_resolved_version() {
+        let temp_dir = tempdir().unwrap();
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new().with_version("mondo", "2024-01-04"),
+            MockOntologyProvider::new().with_content("mondo", "mondo content"),
+        );
+
+        let (mut file, version) = registry
+            .register_resolved(RegistryKey::new("mondo", Version::Latest, FileType::Obo))
+            .unwrap();
+        let mut content = String::new();
+        file.read_to_string(&mut content).unwrap();
+
+        assert_eq!(version, "2024-01-04");
+        assert_eq!(content, "mondo content");
+        assert!(temp_dir.path().join("mondo@2024-01-04.obo").exists());
+
+        let (_, version) = registry
+            .register_resolved(RegistryKey::new(
+                "mondo",
+                Version::from("2023-06-01"),
+                FileType::Obo,
+            ))
+            .unwrap();
+        assert_eq!(version, "2023-06-01");
+    }
+
+    #[test]
+    fn test_register_skips_existing_file() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+
+        let existing_file_path = registry_path.join("test_ont_1.0.rdf");
+        fs::write(&existing_file_path, "old_content").unwrap();
+
+        let metadata_mock = MockMetadataProvider::new();
+        let ontology_mock = MockOntologyProvider::new().with_content("test_ont", "new_content");
+
+        let registry = FileSystemOntologyRegistry::new(registry_path, metadata_mock, ontology_mock);
+
+        let reg_key = RegistryKey::new(
+            "test_ont",
+            Version::Declared("1.0".to_string()),
+            FileType::Json,
+        );
+        let result = registry.register(reg_key);
+
+        assert!(result.is_ok());
+
+        let content = fs::read_to_string(existing_file_path).unwrap();
+        assert_eq!(content, "old_content");
+    }
+
+    #[test]
+    fn test_register_latest_never_serves_stale_release() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+        fs::write(registry_path.join("my_ontology@2024-01-01.json"), "stale").unwrap();
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new().with_version("my_ontology", "2024-06-01"),
+            MockOntologyProvider::new().with_content("my_ontology", "fresh"),
+        );
+
+        let reg_key = RegistryKey::new("my_ontology", Version::Latest, FileType::Json);
+        let mut file = registry.register(reg_key).unwrap();
+        let mut loaded_content = String::new();
+        file.read_to_string(&mut loaded_content).unwrap();
+
+        assert_eq!(loaded_content, "fresh");
+        assert!(registry_path.join("my_ontology@2024-06-01.json").exists());
+    }
+
+    #[test]
+    fn test_register_in_overrides_registry_path() {
+        let default_dir = tempdir().unwrap();
+        let tenant_dir = tempdir().unwrap();
+
+        let registry = FileSystemOntologyRegistry::new(
+            default_dir.path().to_path_buf(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new().with_content("my_ontology", "tenant content"),
+        );
+
+        let reg_key = RegistryKey::new("my_ontology", Version::from("1.0"), FileType::Json);
+        registry
+            .register_in(tenant_dir.path(), reg_key.clone())
+            .unwrap();
+
+        assert!(tenant_dir.path().join("my_ontology@1.0.json").exists());
+        assert!(!default_dir.path().join("my_ontology@1.0.json").exists());
+        assert!(
+            registry
+                .get_in(tenant_dir.path(), reg_key.clone())
+                .is_some()
+        );
+        assert!(registry.get(reg_key).is_none());
+    }
+
+    #[test]
+    fn test_register_ids_differing_in_case_share_one_file() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new()
+                .with_content("GO", "upper")
+                .with_content("go", "lower"),
+        );
+
+        registry
+            .register(RegistryKey::new("GO", Version::from("1.0"), FileType::Json))
+            .unwrap();
+        registry
+            .register(RegistryKey::new("go", Version::from("1.0"), FileType::Json))
+            .unwrap();
+
+        let files = registry.list().unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].ontology_id(), "go");
+        assert_eq!(
+            fs::read_to_string(registry_path.join("go@1.0.json")).unwrap(),
+            "upper"
+        );
+    }
+
+    #[test]
+    fn test_mixed_case_file_names_are_found() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(temp_dir.path().join("HP@1.0.obo"), "hp content").unwrap();
+        let requested_file_names = Arc::new(Mutex::new(Vec::new()));
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider {
+                requested_file_names: requested_file_names.clone(),
+                ..MockOntologyProvider::new()
+            },
+        );
+
+        let listed = registry.list().unwrap();
+
+        assert_eq!(
+            listed,
+            vec![RegistryKey::new("HP", Version::from("1.0"), FileType::Obo)]
+        );
+        let mut content = String::new();
+        registry
+            .get(RegistryKey::new("hp", Version::from("1.0"), FileType::Obo))
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "hp content");
+        assert!(registry.register(listed[0].clone()).is_ok());
+        assert!(requested_file_names.lock().unwrap().is_empty());
+        registry.unregister(listed[0].clone()).unwrap();
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_register_logs_cache_hit_without_download() {
+        struct CapturingLogger {
+            records: Mutex<Vec<(String, String)>>,
+        }
+
+        impl log::Log for CapturingLogger {
+            fn enabled(&self, _metadata: &log::Metadata) -> bool {
+                true
+            }
+
+            fn log(&self, record: &log::Record) {
+                self.records
+                    .lock()
+                    .unwrap()
+                    .push((record.target().to_string(), record.args().to_string()));
+            }
+
+            fn flush(&self) {}
+        }
+
+        static LOGGER: CapturingLogger = CapturingLogger {
+            records: Mutex::new(Vec::new()),
+        };
+        log::set_logger(&LOGGER).unwrap();
+        log::set_max_level(log::LevelFilter::Debug);
+
+        let temp_dir = tempdir().unwrap();
+        fs::write(temp_dir.path().join("logged@2024-04-26.obo"), "cached").unwrap();
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new().with_content("logged", "downloaded"),
+        );
+
+        registry
+            .register(RegistryKey::new(
+                "logged",
+                Version::from("2024-04-26"),
+                FileType::Obo,
+            ))
+            .unwrap();
+
+        // Other tests run concurrently, so only records about this ontology count.
+        let records: Vec<(String, String)> = LOGGER
+            .records
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, message)| message.contains("logged"))
+            .cloned()
+            .collect();
+        assert!(records.iter().any(
+            |(target, message)| target == CACHE_LOG_TARGET && message.starts_with("Cache hit")
+        ));
+        assert!(
+            !records
+                .iter()
+                .any(|(target, _)| target == DOWNLOAD_LOG_TARGET)
+        );
+    }
+
+    #[test]
+    fn test_register_uses_file_name_from_metadata_location() {
+        let temp_dir = tempdir().unwrap();
+        let ontology_mock = MockOntologyProvider::new().with_content("my_ontology", "full");
+        let requested_file_names = ontology_mock.requested_file_names.clone();
+
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new().with_metadata(OntologyMetadata {
+                ontology_id: "my_ontology".to_string(),
+                version: "2024-05-05".to_string(),
+                owl_file_location: Some(
+                    "http://purl.obolibrary.org/obo/my_ontology/my_ontology-full.owl".to_string(),
+                ),
+                ..Default::default()
+            }),
+            ontology_mock,
+        );
+
+        registry
+            .register(RegistryKey::new(
+                "my_ontology",
+                Version::Latest,
+                FileType::Owl,
+            ))
+            .unwrap();
+        registry
+            .register(RegistryKey::new(
+                "my_ontology",
+                Version::from("1.0"),
+                FileType::Json,
+            ))
+            .unwrap();
+        registry
+            .register(RegistryKey::new(
+                "my_ontology",
+                Version::from("1.0"),
+                FileType::Owl,
+            ))
+            .unwrap();
+
+        assert_eq!(
+            *requested_file_names.lock().unwrap(),
+            vec![
+                "my_ontology-full.owl",
+                "my_ontology.json",
+                "my_ontology.owl"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_register_uses_file_name_chosen_by_provider() {
+        /// Serves every release as `ontology<ext>`, ignoring the id and metadata.
+        #[derive(Debug)]
+        struct GenericNameProvider(MockOntologyProvider);
+
+        impl OntologyProviding for GenericNameProvider {
+            fn provide_ontology(
+                &self,
+                ontology_id: &str,
+                file_name: &str,
+                version: &Version,
+            ) -> Result<impl Read, OntologyRegistryError> {
+                self.0.provide_ontology(ontology_id, file_name, version)
+            }
+
+            fn file_name_for(
+                &self,
+                _ontology_id: &str,
+                file_type: &FileType,
+                _metadata: Option<&OntologyMetadata>,
+            ) -> String {
+                format!("ontology{}", file_type.as_file_ending())
+            }
+        }
+
+        let temp_dir = tempdir().unwrap();
+        let ontology_mock = MockOntologyProvider::new().with_content("hp", "hp");
+        let requested_file_names = ontology_mock.requested_file_names.clone();
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new().with_metadata(OntologyMetadata {
+                ontology_id: "hp".to_string(),
+                version: "2024-04-26".to_string(),
+                obo_file_location: Some("http://purl.obolibrary.org/obo/hp.obo".to_string()),
+                ..Default::default()
+            }),
+            GenericNameProvider(ontology_mock),
+        );
+
+        registry
+            .register(RegistryKey::new("hp", Version::Latest, FileType::Obo))
+            .unwrap();
+
+        assert_eq!(*requested_file_names.lock().unwrap(), vec!["ontology.obo"]);
+        assert!(temp_dir.path().join("hp@2024-04-26.obo").exists());
+    }
+
+    #[test]
+    fn test_provider_latest_stores_under_version_reported_by_provider() {
+        /// Serves the newest release of every ontology as version `2025-01-01`.
+        #[derive(Debug)]
+        struct LatestProvider(MockOntologyProvider);
+
+        impl OntologyProviding for LatestProvider {
+            fn provide_ontology(
+                &self,
+                ontology_id: &str,
+                file_name: &str,
+                version: &Version,
+            ) -> Result<impl Read, OntologyRegistryError> {
+                self.0.provide_ontology(ontology_id, file_name, version)
+            }
+
+            fn provide_latest(
+                &self,
+                ontology_id: &str,
+                file_name: &str,
+            ) -> Result<(String, impl Read), OntologyRegistryError> {
+                let reader = self
+                    .0
+                    .provide_ontology(ontology_id, file_name, &Version::Latest)?;
+                Ok(("2025-01-01".to_string(), reader))
+            }
+        }
+
+        let temp_dir = tempdir().unwrap();
+        let latest_mock = MockOntologyProvider::new().with_content("hp", "hp");
+        let requested_file_names = latest_mock.requested_file_names.clone();
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new(),
+            LatestProvider(latest_mock),
+        )
+        .with_provider_latest(true);
+
+        let (mut file, version) = registry
+            .register_resolved(RegistryKey::new("hp", Version::Latest, FileType::Obo))
+            .unwrap();
+        let mut content = String::new();
+        file.read_to_string(&mut content).unwrap();
+
+        assert_eq!(version, "2025-01-01");
+        assert_eq!(content, "hp");
+        assert!(temp_dir.path().join("hp@2025-01-01.obo").exists());
+        // One request to learn the version, one to download it.
+        assert_eq!(requested_file_names.lock().unwrap().len(), 2);
+
+        let (_, version) = registry
+            .register_resolved(RegistryKey::new("hp", Version::Latest, FileType::Obo))
+            .unwrap();
+        assert_eq!(version, "2025-01-01");
+        assert_eq!(registry.list().unwrap().len(), 1);
+        // The cached release is not downloaded again.
+        assert_eq!(requested_file_names.lock().unwrap().len(), 3);
+
+        let latest = registry
+            .get(RegistryKey::new("hp", Version::Latest, FileType::Obo))
+            .unwrap();
+        assert_eq!(read_to_string(latest), "hp");
+
+        let default_dir = tempdir().unwrap();
+        let default_registry = FileSystemOntologyRegistry::new(
+            default_dir.path().to_path_buf(),
+            MockMetadataProvider::new().with_version("hp", "2024-04-26"),
+            LatestProvider(MockOntologyProvider::new().with_content("hp", "hp")),
+        );
+        default_registry
+            .register(RegistryKey::new("hp", Version::Latest, FileType::Obo))
+            .unwrap();
+        assert!(default_dir.path().join("hp@2024-04-26.obo").exists());
+    }
+
+    #[test]
+    fn test_provider_latest_fails_for_provider_without_support() {
+        let temp_dir = tempdir().unwrap();
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new().with_version("hp", "2024-04-26"),
+            MockOntologyProvider::new().with_content("hp", "hp"),
+        )
+        .with_provider_latest(true);
+
+        let result = registry.register(RegistryKey::new("hp", Version::Latest, FileType::Obo));
+
+        assert!(matches!(
+            result,
+            Err(OntologyRegistryError::ProvidingOntology { .. })
+        ));
+        assert!(!temp_dir.path().join("hp@2024-04-26.obo").exists());
+    }
+
+    #[test]
+    fn test_register_recovers_poisoned_write_lock() {
+        let temp_dir = tempdir().unwrap();
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new().with_version("hp", "2024-04-26"),
+            MockOntologyProvider::new().with_content("hp", "hp"),
+        );
+
+        std::thread::scope(|scope| {
+            let poisoner = scope.spawn(|| {
+                let _guard = registry.write_lock.lock().unwrap();
+                panic!("poisoning the write lock");
+            });
+            assert!(poisoner.join().is_err());
+        });
+        assert!(registry.write_lock.is_poisoned());
+
+        registry
+            .register(RegistryKey::new("hp", Version::Latest, FileType::Obo))
+            .unwrap();
+
+        assert!(temp_dir.path().join("hp@2024-04-26.obo").exists());
+    }
+
+    #[test]
+    fn test_register_hashes_overlong_file_names() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+        let long_version = "http://purl.obolibrary.org/obo/my_ontology/releases/2024-05-05";
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new().with_content("my_ontology", "content"),
+        )
+        .with_max_file_name_length(40);
+
+        let reg_key = RegistryKey::new("my_ontology", Version::from(long_version), FileType::Owl);
+        registry.register(reg_key.clone()).unwrap();
+
+        let stored_names: Vec<String> = fs::read_dir(&registry_path)
+            .unwrap()
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .filter(|name| !name.starts_with('.'))
+            .collect();
+        assert_eq!(stored_names.len(), 1);
+        assert!(stored_names[0].len() <= 40);
+        assert!(stored_names[0].ends_with(".owl"));
+
+        assert_eq!(registry.list().unwrap(), vec![reg_key.clone()]);
+        assert!(registry.get(reg_key.clone()).is_some());
+
+        registry.unregister(reg_key).unwrap();
+        assert!(registry.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_register_evicts_least_recently_used_files() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+
+        let old = registry_path.join("old@1.0.obo");
+        let recent = registry_path.join("recent@1.0.obo");
+        fs::write(&old, "a".repeat(10)).unwrap();
+        fs::write(&recent, "b".repeat(10)).unwrap();
+        let now = SystemTime::now();
+        for (path, age) in [(&old, 7200), (&recent, 3600)] {
+            let time = now - Duration::from_secs(age);
+            File::options()
+                .write(true)
+                .open(path)
+                .unwrap()
+                .set_times(fs::FileTimes::new().set_accessed(time).set_modified(time))
+                .unwrap();
+        }
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new().with_content("new", &"c".repeat(10)),
+        )
+        .with_max_bytes(25);
+
+        registry
+            .register(RegistryKey::new("new", Version::from("1.0"), FileType::Obo))
+            .unwrap();
+
+        assert!(!old.exists());
+        assert!(recent.exists());
+        assert!(registry_path.join("new@1.0.obo").exists());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_eviction_counts_linked_files_once() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new()
+                .with_content("hp", &"a".repeat(10))
+                .with_content("go", &"b".repeat(10)),
+        )
+        .with_deduplication(true)
+        .with_max_bytes(25);
+
+        for (ontology_id, version) in [("hp", "2024-01-01"), ("hp", "2024-04-26"), ("go", "1.0")] {
+            registry
+                .register(RegistryKey::new(
+                    ontology_id,
+                    Version::from(version),
+                    FileType::Obo,
+                ))
+                .unwrap();
+        }
+
+        assert!(registry_path.join("hp@2024-01-01.obo").exists());
+        assert!(registry_path.join("hp@2024-04-26.obo").exists());
+        assert!(registry_path.join("go@1.0.obo").exists());
+    }
+
+    #[test]
+    fn test_register_never_evicts_new_file() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+        fs::write(registry_path.join("old@1.0.obo"), "a").unwrap();
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new().with_content("big", &"b".repeat(100)),
+        )
+        .with_max_bytes(10);
+
+        registry
+            .register(RegistryKey::new("big", Version::from("1.0"), FileType::Obo))
+            .unwrap();
+
+        assert!(!registry_path.join("old@1.0.obo").exists());
+        assert!(registry_path.join("big@1.0.obo").exists());
+    }
+
+    #[test]
+    fn test_register_updates_latest_alias() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new().with_content("mondo", "content"),
+        )
+        .with_latest_alias(true);
+
+        for version in ["2024-01-01", "2024-02-01"] {
+            registry
+                .register(RegistryKey::new(
+                    "mondo",
+                    Version::from(version),
+                    FileType::Json,
+                ))
+                .unwrap();
+        }
+
+        let alias_path = registry_path.join("mondo@latest.json");
+        #[cfg(unix)]
+        assert_eq!(
+            fs::read_link(&alias_path).unwrap(),
+            Path::new("mondo@2024-02-01.json")
+        );
+        assert!(alias_path.exists());
+
+        assert_eq!(registry.list().unwrap().len(), 3);
+        let mut releases = registry.list_releases().unwrap();
+        releases.sort();
+        assert_eq!(
+            releases,
+            vec![
+                RegistryKey::new("mondo", Version::from("2024-01-01"), FileType::Json),
+                RegistryKey::new("mondo", Version::from("2024-02-01"), FileType::Json),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_latest_alias_does_not_move_back_to_older_release() {
+        let temp_dir = tempdir().unwrap();
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new().with_version("mondo", "2024-01-01"),
+            MockOntologyProvider::new().with_content("mondo", "content"),
+        )
+        .with_latest_alias(true);
+        #[cfg(unix)]
+        let alias_target = || {
+            fs::canonicalize(temp_dir.path().join("mondo@latest.json"))
+                .unwrap()
+                .file_name()
+                .unwrap()
+                .to_owned()
+        };
+
+        // Registered newest first, then an older cached and an older new release.
+        for version in ["2024-02-01", "2024-01-01", "2024-01-01", "2023-12-01"] {
+            registry
+                .register(RegistryKey::new(
+                    "mondo",
+                    Version::from(version),
+                    FileType::Json,
+                ))
+                .unwrap();
+        }
+        #[cfg(unix)]
+        assert_eq!(alias_target(), "mondo@2024-02-01.json");
+
+        registry
+            .register(RegistryKey::new("mondo", Version::Latest, FileType::Json))
+            .unwrap();
+        #[cfg(unix)]
+        assert_eq!(alias_target(), "mondo@2024-01-01.json");
+    }
+
+    #[test]
+    fn test_register_propagates_not_found() {
+        struct NotFoundProvider;
+
+        impl OntologyProviding for NotFoundProvider {
+            fn provide_ontology(
+                &self,
+                ontology_id: &str,
+                _file_name: &str,
+                version: &Version,
+            ) -> Result<impl Read, OntologyRegistryError> {
+                Err::<Cursor<Vec<u8>>, _>(OntologyRegistryError::NotFound {
+                    ontology_id: ontology_id.to_string(),
+                    version: version.to_string(),
+                })
+            }
+        }
+
+        let temp_dir = tempdir().unwrap();
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new(),
+            NotFoundProvider,
+        );
+
+        let result = registry.register(RegistryKey::new(
+            "missing",
+            Version::from("1.0"),
+            FileType::Obo,
+        ));
+
+        assert!(matches!(
+            result,
+            Err(OntologyRegistryError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_get_existing_ontology() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+        let target_path = registry_path.join("findme@2024-05-05.json");
+
+        let content = "data";
+        fs::write(&target_path, content).unwrap();
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path,
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new(),
+        );
+
+        let reg_key = RegistryKey::new(
+            "findme",
+            Version::Declared("2024-05-05".to_string()),
+            FileType::Json,
+        );
+
+        let result = registry.get(reg_key);
+
+        let mut file = result.unwrap();
+        let mut loaded_content = String::new();
+        file.read_to_string(&mut loaded_content).unwrap();
+
+        assert_eq!(loaded_content, content);
+    }
+
+    #[test]
+    fn test_path_of_registered_ontology() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+        fs::write(registry_path.join("hp@2024-04-26.obo"), "data").unwrap();
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new().with_version("hp", "2024-04-26"),
+            MockOntologyProvider::new(),
+        );
+
+        assert_eq!(
+            registry.path(RegistryKey::new("hp", Version::Latest, FileType::Obo)),
+            Some(registry_path.join("hp@2024-04-26.obo"))
+        );
+        assert_eq!(
+            registry.path(RegistryKey::new("hp", Version::Latest, FileType::Json)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_cached_at_reports_when_file_was_written() {
+        let temp_dir = tempdir().unwrap();
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new().with_version("hp", "2024-04-26"),
+            MockOntologyProvider::new().with_content("hp", "hp"),
+        );
+        assert!(
+            registry
+                .cached_at("hp", &Version::Latest, &FileType::Obo)
+                .is_none()
+        );
+
+        let before = SystemTime::now() - Duration::from_secs(5);
+        registry
+            .register(RegistryKey::new("hp", Version::Latest, FileType::Obo))
+            .unwrap();
+
+        let cached_at = registry
+            .cached_at("hp", &Version::from("2024-04-26"), &FileType::Obo)
+            .unwrap();
+        assert!(cached_at >= before);
+        assert!(cached_at <= SystemTime::now());
+        assert!(
+            registry
+                .cached_at("hp", &Version::Latest, &FileType::Owl)
+                .is_none()
+        );
+        assert!(
+            registry
+                .cached_at("unknown", &Version::Latest, &FileType::Obo)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_path_for_unregistered_ontology() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new().with_version("hp", "2024-04-26"),
+            MockOntologyProvider::new(),
+        )
+        .with_layout(RegistryLayout::Nested);
+
+        assert_eq!(
+            registry
+                .path_for("hp", &Version::Latest, &FileType::Obo)
+                .unwrap(),
+            registry_path.join("hp").join("2024-04-26.obo")
+        );
+        assert_eq!(
+            registry
+                .path_for("mondo", &Version::from("2024-01-04"), &FileType::Json)
+                .unwrap(),
+            registry_path.join("mondo").join("2024-01-04.json")
+        );
+        assert!(
+            registry
+                .path_for("mondo", &Version::Latest, &FileType::Json)
+                .is_err()
+        );
+        assert!(fs::read_dir(&registry_path).unwrap().next().is_none());
+    }
+
+    #[test]
+    fn test_read_paths_are_searched_but_never_written() {
+        let user_dir = tempdir().unwrap();
+        let shared_dir = tempdir().unwrap();
+        fs::write(shared_dir.path().join("hp@2024-04-26.obo"), "shared hp").unwrap();
+        let ontology_mock = MockOntologyProvider::new()
+            .with_content("hp", "downloaded hp")
+            .with_content("mondo", "downloaded mondo");
+        let requested_file_names = ontology_mock.requested_file_names.clone();
+        let registry = FileSystemOntologyRegistry::new(
+            user_dir.path().to_path_buf(),
+            MockMetadataProvider::new().with_version("hp", "2024-04-26"),
+            ontology_mock,
+        )
+        .with_read_paths(vec![shared_dir.path().to_path_buf()]);
+
+        let mut content = String::new();
+        registry
+            .get(RegistryKey::new("hp", Version::Latest, FileType::Obo))
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "shared hp");
+        assert!(registry.contains("hp", &Version::from("2024-04-26"), &FileType::Obo));
+        assert_eq!(
+            registry.path(RegistryKey::new("hp", Version::Latest, FileType::Obo)),
+            Some(shared_dir.path().join("hp@2024-04-26.obo"))
+        );
+
+        registry
+            .get_or_register("hp", &Version::Latest, &FileType::Obo)
+            .unwrap();
+        assert!(requested_file_names.lock().unwrap().is_empty());
+
+        let mondo = RegistryKey::new("mondo", Version::from("2024-01-01"), FileType::Obo);
+        registry.register(mondo.clone()).unwrap();
+        assert!(user_dir.path().join("mondo@2024-01-01.obo").exists());
+        assert!(!shared_dir.path().join("mondo@2024-01-01.obo").exists());
+
+        let mut listed = registry.list().unwrap();
+        listed.sort();
+        assert_eq!(
+            listed,
+            vec![
+                RegistryKey::new("hp", Version::from("2024-04-26"), FileType::Obo),
+                mondo.clone(),
+            ]
+        );
+
+        registry
+            .unregister(RegistryKey::new(
+                "hp",
+                Version::from("2024-04-26"),
+                FileType::Obo,
+            ))
+            .unwrap();
+        assert!(shared_dir.path().join("hp@2024-04-26.obo").exists());
+    }
+
+    #[test]
+    fn test_get_non_existent_ontology() {
+        let temp_dir = tempdir().unwrap();
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new(),
+        );
+        let reg_key = RegistryKey::new(
+            "missing",
+            Version::Declared("9.9".to_string()),
+            FileType::Obo,
+        );
+        let result = registry.get(reg_key);
+
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_register_many_reports_each_result() {
+        let temp_dir = tempdir().unwrap();
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new().with_content("hp", "hp content"),
+        );
+
+        let results = registry.register_many(&[
+            ("hp".to_string(), Version::from("1.0"), FileType::Obo),
+            ("unknown".to_string(), Version::from("1.0"), FileType::Obo),
+        ]);
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, 0);
+        assert!(results[0].1.is_ok());
+        assert_eq!(results[1].0, 1);
+        assert!(matches!(
+            results[1].1,
+            Err(OntologyRegistryError::UnableToRegister { .. })
+        ));
+    }
+
+    #[test]
+    fn test_register_bytes() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().join("registry");
+        let ontology_mock = MockOntologyProvider::new();
+        let requested_file_names = ontology_mock.requested_file_names.clone();
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new().with_version("local", "2024-06-01"),
+            ontology_mock,
+        );
+
+        let mut content = String::new();
+        registry
+            .register_bytes(
+                "local",
+                &Version::Latest,
+                &FileType::Obo,
+                b"format-version: 1.2",
+            )
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+
+        assert_eq!(content, "format-version: 1.2");
+        assert_eq!(
+            fs::read_to_string(registry_path.join("local@2024-06-01.obo")).unwrap(),
+            "format-version: 1.2"
+        );
+        assert!(requested_file_names.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_import_file_then_get() {
+        let temp_dir = tempdir().unwrap();
+        let artifacts_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().join("registry");
+        let src = artifacts_dir.path().join("hp.obo");
+        fs::write(&src, "format-version: 1.2").unwrap();
+        let ontology_mock = MockOntologyProvider::new();
+        let requested_file_names = ontology_mock.requested_file_names.clone();
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new(),
+            ontology_mock,
+        );
+
+        let path = registry
+            .import_file(&src, "hp", &Version::from("2024-04-26"), &FileType::Obo)
+            .unwrap();
+
+        assert_eq!(path, registry_path.join("hp@2024-04-26.obo"));
+        let mut content = String::new();
+        registry
+            .get(RegistryKey::new(
+                "hp",
+                Version::from("2024-04-26"),
+                FileType::Obo,
+            ))
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "format-version: 1.2");
+        assert!(src.exists());
+        assert!(requested_file_names.lock().unwrap().is_empty());
+        assert!(
+            registry
+                .import_file(
+                    &artifacts_dir.path().join("missing.obo"),
+                    "hp",
+                    &Version::from("2024-04-26"),
+                    &FileType::Obo
+                )
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_get_or_register_downloads_once() {
+        let temp_dir = tempdir().unwrap();
+        let ontology_mock = MockOntologyProvider::new().with_content("hp", "hp content");
+        let requested_file_names = ontology_mock.requested_file_names.clone();
+
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new().with_version("hp", "2024-04-26"),
+            ontology_mock,
+        );
+
+        for _ in 0..2 {
+            let mut content = String::new();
+            registry
+                .get_or_register("hp", &Version::Latest, &FileType::Obo)
+                .unwrap()
+                .read_to_string(&mut content)
+                .unwrap();
+            assert_eq!(content, "hp content");
+        }
+
+        assert_eq!(requested_file_names.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "gzip")]
+    fn test_compressed_storage_round_trip() {
+        let registry_dir = tempdir().unwrap();
+        let export_dir = tempdir().unwrap();
+        let registry_path = registry_dir.path().to_path_buf();
+        let content = "format-version: 1.2\ndata-version: hp/releases/2024-04-26\n";
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new().with_version("hp", "2024-04-26"),
+            MockOntologyProvider::new().with_content("hp", content),
+        )
+        .with_compressed_storage(true);
+        let registry_key = RegistryKey::new("hp", Version::Latest, FileType::Obo);
+
+        let mut registered = String::new();
+        registry
+            .register(registry_key.clone())
+            .unwrap()
+            .read_to_string(&mut registered)
+            .unwrap();
+        assert_eq!(registered, content);
+
+        let stored = fs::read(registry_path.join("hp@2024-04-26.obo.gz")).unwrap();
+        assert!(stored.starts_with(&[0x1f, 0x8b]));
+        assert_eq!(
+            registry.list().unwrap(),
+            vec![RegistryKey::new(
+                "hp",
+                Version::from("2024-04-26"),
+                FileType::Obo
+            )]
+        );
+
+        let mut retrieved = String::new();
+        registry
+            .get(registry_key)
+            .unwrap()
+            .read_to_string(&mut retrieved)
+            .unwrap();
+        assert_eq!(retrieved, content);
+
+        let dest = export_dir.path().join("hp.obo");
+        registry
+            .export_to("hp", &Version::from("2024-04-26"), &FileType::Obo, &dest)
+            .unwrap();
+        assert_eq!(fs::read_to_string(dest).unwrap(), content);
+        assert_eq!(
+            registry
+                .read_obo_header("hp", &Version::Latest)
+                .unwrap()
+                .format_version
+                .as_deref(),
+            Some("1.2")
+        );
+        assert!(matches!(
+            registry.verify().unwrap()[..],
+            [VerifyReport::Ok { .. }]
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "gzip")]
+    fn test_compressed_storage_streams_and_checksums_original_content() {
+        let compressed_dir = tempdir().unwrap();
+        let plain_dir = tempdir().unwrap();
+        let lock_dir = tempdir().unwrap();
+        let lock = lock_dir.path().join("registry.lock");
+        let content = "format-version: 1.2\n";
+        let compressed = FileSystemOntologyRegistry::new(
+            compressed_dir.path().to_path_buf(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new().with_content("hp", content),
+        )
+        .with_compressed_storage(true);
+        let registry_key = RegistryKey::new("hp", Version::from("2024-04-26"), FileType::Obo);
+        compressed.register(registry_key.clone()).unwrap();
+
+        let mut streamed = String::new();
+        compressed
+            .get_reader(registry_key.clone())
+            .unwrap()
+            .read_to_string(&mut streamed)
+            .unwrap();
+        assert_eq!(streamed, content);
+
+        compressed.write_lock_file(&lock).unwrap();
+        let entries: Vec<LockEntry> =
+            serde_json::from_str(&fs::read_to_string(&lock).unwrap()).unwrap();
+        assert_eq!(
+            entries[0].sha256,
+            sha256_reader(content.as_bytes()).unwrap()
+        );
+
+        let plain = FileSystemOntologyRegistry::new(
+            plain_dir.path().to_path_buf(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new().with_content("hp", content),
+        );
+        plain.install_from_lock(&lock).unwrap();
+        assert!(plain.get(registry_key).is_some());
+    }
+
+    #[test]
+    fn test_list_skips_compressed_files_without_compressed_storage() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+        fs::write(registry_path.join("hp@2024-04-26.obo"), "hp").unwrap();
+        fs::write(registry_path.join("go@2024-01-01.owl.gz"), "go").unwrap();
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path,
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new(),
+        );
+
+        assert_eq!(
+            registry.list().unwrap(),
+            vec![RegistryKey::new(
+                "hp",
+                Version::from("2024-04-26"),
+                FileType::Obo
+            )]
+        );
+    }
+
+    #[test]
+    fn test_export_to_copies_registered_file() {
+        let registry_dir = tempdir().unwrap();
+        let export_dir = tempdir().unwrap();
+        let registry = FileSystemOntologyRegistry::new(
+            registry_dir.path().to_path_buf(),
+            MockMetadataProvider::new().with_version("hp", "2024-04-26"),
+            MockOntologyProvider::new().with_content("hp", "hp content"),
+        );
+
+        let dest = export_dir.path().join("inputs").join("hp.obo");
+        registry
+            .export_to("hp", &Version::Latest, &FileType::Obo, &dest)
+            .unwrap();
+
+        let cached = registry_dir.path().join("hp@2024-04-26.obo");
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "hp content");
+        assert_eq!(
+            fs::read_to_string(&dest).unwrap(),
+            fs::read_to_string(&cached).unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "obolibrary")]
+    fn test_refresh_records_unchanged_file_as_fresh_without_touching_it() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+        let mut server = mockito::Server::new();
+        let _download = server
+            .mock("GET", "/go/releases/2024-01-01/go.obo")
+            .with_status(200)
+            .with_header("etag", "\"v1\"")
+            .with_body("first release")
+            .create();
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new(),
+            crate::OboLibraryProvider::new(server.url()),
+        );
+        let reg_key = RegistryKey::new("go", Version::from("2024-01-01"), FileType::Obo);
+        registry.register(reg_key.clone()).unwrap();
+
+        let an_hour_ago = SystemTime::now() - Duration::from_secs(3600);
+        let path = registry_path.join("go@2024-01-01.obo");
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(an_hour_ago)
+            .unwrap();
+        let mut manifest = RegistryManifest::load(&registry_path).unwrap();
+        manifest
+            .checked_at
+            .insert("go@2024-01-01.obo".to_string(), an_hour_ago);
+        manifest.save(&registry_path).unwrap();
+        let _revalidation = server
+            .mock("GET", "/go/releases/2024-01-01/go.obo")
+            .match_header("if-none-match", "\"v1\"")
+            .with_status(304)
+            .create();
+
+        registry.refresh(reg_key).unwrap();
+
+        let cached_at = registry
+            .cached_at("go", &Version::from("2024-01-01"), &FileType::Obo)
+            .unwrap();
+        assert!(cached_at > an_hour_ago + Duration::from_secs(60));
+        assert_eq!(
+            fs::metadata(&path).unwrap().modified().unwrap(),
+            an_hour_ago
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "obolibrary")]
+    fn test_refresh_keeps_unchanged_file() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+        let mut server = mockito::Server::new();
+
+        let download = server
+            .mock("GET", "/go/releases/2024-01-01/go.obo")
+            .with_status(200)
+            .with_header("etag", "\"v1\"")
+            .with_body("first release")
+            .expect(1)
+            .create();
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new(),
+            crate::OboLibraryProvider::new(server.url()),
+        );
+        let reg_key = RegistryKey::new("go", Version::from("2024-01-01"), FileType::Obo);
+
+        registry.register(reg_key.clone()).unwrap();
+        download.assert();
+
+        let revalidation = server
+            .mock("GET", "/go/releases/2024-01-01/go.obo")
+            .match_header("if-none-match", "\"v1\"")
+            .with_status(304)
+            .expect(1)
+            .create();
+
+        let mut content = String::new();
+        registry
+            .refresh(reg_key.clone())
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+
+        revalidation.assert();
+        assert_eq!(content, "first release");
+
+        let changed = server
+            .mock("GET", "/go/releases/2024-01-01/go.obo")
+            .match_header("if-none-match", "\"v1\"")
+            .with_status(200)
+            .with_header("etag", "\"v2\"")
+            .with_body("patched release")
+            .expect(1)
+            .create();
+
+        registry.refresh(reg_key).unwrap();
+
+        changed.assert();
+        assert_eq!(
+            fs::read_to_string(registry_path.join("go@2024-01-01.obo")).unwrap(),
+            "patched release"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "obolibrary")]
+    fn test_register_with_progress_reports_increasing_counts() {
+        let temp_dir = tempdir().unwrap();
+        let mut server = mockito::Server::new();
+        let body = "a".repeat(256 * 1024);
+
+        let _m = server
+            .mock("GET", "/go/releases/2024-01-01/go.obo")
+            .with_status(200)
+            .with_body(&body)
+            .create();
+
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new(),
+            crate::OboLibraryProvider::new(server.url()),
+        );
+
+        let mut reports = Vec::new();
+        registry
+            .register_with_progress(
+                RegistryKey::new("go", Version::from("2024-01-01"), FileType::Obo),
+                |bytes, total| reports.push((bytes, total)),
+            )
+            .unwrap();
+
+        assert!(!reports.is_empty());
+        assert!(reports.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert_eq!(
+            reports.last(),
+            Some(&(body.len() as u64, Some(body.len() as u64)))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "obolibrary")]
+    fn test_register_aborts_download_over_size_limit() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+        let mut server = mockito::Server::new();
+
+        let _m = server
+            .mock("GET", "/go/releases/2024-01-01/go.obo")
+            .with_status(200)
+            .with_chunked_body(|w| w.write_all(&[b'a'; 1024]))
+            .create();
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new(),
+            crate::OboLibraryProvider::new(server.url()).with_max_download_bytes(100),
+        );
+
+        let result = registry.register(RegistryKey::new(
+            "go",
+            Version::from("2024-01-01"),
+            FileType::Obo,
+        ));
+
+        assert!(matches!(
+            result,
+            Err(OntologyRegistryError::DownloadTooLarge { limit: 100, .. })
+        ));
+        assert_eq!(fs::read_dir(&registry_path).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_contains_registered_ontology() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+        fs::write(registry_path.join("hp@2024-04-26.json"), "data").unwrap();
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path,
+            MockMetadataProvider::new().with_version("hp", "2024-04-26"),
+            MockOntologyProvider::new(),
+        );
+
+        assert!(registry.contains("hp", &Version::Latest, &FileType::Json));
+        assert!(registry.contains("hp", &Version::from("2024-04-26"), &FileType::Json));
+    }
+
+    #[test]
+    fn test_contains_missing_ontology() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+        fs::write(registry_path.join("hp@2024-04-26.json"), "data").unwrap();
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path,
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new(),
+        );
+
+        assert!(!registry.contains("hp", &Version::from("2024-04-26"), &FileType::Obo));
+        assert!(!registry.contains("hp", &Version::from("2023-01-01"), &FileType::Json));
+    }
+
+    #[test]
+    fn test_contains_unresolvable_version() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+        fs::write(registry_path.join("hp@2024-04-26.json"), "data").unwrap();
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path,
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new(),
+        );
+
+        assert!(!registry.contains("hp", &Version::Latest, &FileType::Json));
+    }
+
+    #[test]
+    fn test_read_obo_header() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+        fs::write(
+            registry_path.join("hp@2024-04-26.obo"),
+            "format-version: 1.2\ndata-version: hp/releases/2024-04-26/hp.obo\nontology: hp\n\n[Term]\nid: HP:0000001\n",
+        )
+        .unwrap();
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path,
+            MockMetadataProvider::new().with_version("hp", "2024-04-26"),
+            MockOntologyProvider::new(),
+        );
+
+        let header = registry.read_obo_header("hp", &Version::Latest).unwrap();
+
+        assert_eq!(
+            header.data_version.as_deref(),
+            Some("hp/releases/2024-04-26/hp.obo")
+        );
+        assert_eq!(header.ontology.as_deref(), Some("hp"));
+    }
+
+    #[test]
+    fn test_read_obo_header_missing_file() {
+        let temp_dir = tempdir().unwrap();
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new(),
+        );
+
+        let result = registry.read_obo_header("hp", &Version::from("2024-04-26"));
+
+        assert!(matches!(
+            result,
+            Err(OntologyRegistryError::ReadingOboHeader { .. })
+        ));
+    }
+
+    #[test]
+    fn test_unregister_removes_file() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+        let target_path = registry_path.join("todelete@2024-05-05.json");
+        fs::write(&target_path, "delete_me").unwrap();
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path,
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new(),
+        );
+
+        assert!(target_path.exists());
+
+        let reg_key = RegistryKey::new(
+            "todelete",
+            Version::Declared("2024-05-05".to_string()),
+            FileType::Json,
+        );
+
+        registry.unregister(reg_key).unwrap();
+
+        assert!(!target_path.exists());
+    }
+
+    #[test]
+    fn test_unregister_chains_io_error() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+        fs::create_dir(registry_path.join("blocked@1.0.json")).unwrap();
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path,
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new(),
+        );
+
+        let result = registry.unregister(RegistryKey::new(
+            "blocked",
+            Version::from("1.0"),
+            FileType::Json,
+        ));
+
+        match result {
+            Err(OntologyRegistryError::IoAt { path, .. }) => {
+                assert!(path.ends_with("blocked@1.0.json"), "{path}");
+            }
+            _ => panic!("Expected IoAt"),
+        }
+    }
+
+    #[test]
+    fn test_list_files() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+
+        fs::write(registry_path.join("A@1.0.json"), "").unwrap();
+        fs::write(registry_path.join("B@2.0.obo"), "").unwrap();
+        fs::create_dir(registry_path.join("subdir")).unwrap();
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path,
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new(),
+        );
+
+        let files = registry.list().unwrap();
+
+        assert_eq!(files.len(), 2);
+        assert!(
+            files.iter().any(|f| *f
+                == RegistryKey::new("A", Version::Declared("1.0".to_string()), FileType::Json))
+        );
+        assert!(
+            files.iter().any(|f| *f
+                == RegistryKey::new("B", Version::Declared("2.0".to_string()), FileType::Obo))
+        );
+    }
+
+    #[test]
+    fn test_list_filtered() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+
+        fs::write(registry_path.join("hp@1.0.obo"), "").unwrap();
+        fs::write(registry_path.join("hp@1.0.json"), "").unwrap();
+        fs::write(registry_path.join("mondo@2.0.obo"), "").unwrap();
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path,
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new(),
+        );
+        let key = |id: &str, version: &str, file_type| {
+            RegistryKey::new(id, Version::from(version), file_type)
+        };
+
+        let mut by_id = registry.list_filtered(Some("HP"), None).unwrap();
+        by_id.sort_by_key(|k| k.file_type().as_file_ending());
+        assert_eq!(
+            by_id,
+            vec![
+                key("hp", "1.0", FileType::Json),
+                key("hp", "1.0", FileType::Obo)
+            ]
+        );
+
+        let by_type = registry.list_filtered(None, Some(&FileType::Json)).unwrap();
+        assert_eq!(by_type, vec![key("hp", "1.0", FileType::Json)]);
+
+        let by_both = registry
+            .list_filtered(Some("mondo"), Some(&FileType::Obo))
+            .unwrap();
+        assert_eq!(by_both, vec![key("mondo", "2.0", FileType::Obo)]);
+
+        assert_eq!(registry.list_filtered(None, None).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_list_files_ignore_hidden_files() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+
+        fs::write(registry_path.join("A@1.0.json"), "").unwrap();
+        fs::write(registry_path.join(".DS_Store"), "").unwrap();
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path,
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new(),
+        );
+
+        let files = registry.list().unwrap();
+
+        assert_eq!(files.len(), 1);
+        assert!(
+            files.iter().any(|f| *f
+                == RegistryKey::new("A", Version::Declared("1.0".to_string()), FileType::Json))
+        );
+    }
+
+    #[test]
+    fn test_clear_removes_all_files() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+
+        fs::write(registry_path.join("A@1.0.json"), "").unwrap();
+        fs::write(registry_path.join("B@2.0.obo"), "").unwrap();
+        fs::write(registry_path.join("C@3.0.owl"), "").unwrap();
+        fs::create_dir(registry_path.join("subdir")).unwrap();
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new(),
+        );
+
+        assert_eq!(registry.clear().unwrap(), 3);
+        assert!(registry.list().unwrap().is_empty());
+        assert!(registry_path.join("subdir").is_dir());
+    }
+
+    #[test]
+    fn test_unversioned_round_trip() {
+        let temp_dir = tempdir().unwrap();
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new().with_version("mondo", "2024-01-04"),
+            MockOntologyProvider::new().with_content("mondo", "mondo"),
+        )
+        .with_latest_alias(true);
+        let unversioned_key = RegistryKey::new("mondo", Version::Unversioned, FileType::Owl);
+
+        registry.register(unversioned_key.clone()).unwrap();
+        registry
+            .register(RegistryKey::new("mondo", Version::Latest, FileType::Owl))
+            .unwrap();
+
+        assert!(temp_dir.path().join("mondo.owl").exists());
+        assert!(temp_dir.path().join("mondo@2024-01-04.owl").exists());
+        let mut content = String::new();
+        registry
+            .get(unversioned_key.clone())
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "mondo");
+        assert!(registry.list().unwrap().contains(&unversioned_key));
+        assert_eq!(
+            registry.local_versions("mondo", &FileType::Owl).unwrap(),
+            vec!["2024-01-04"]
+        );
+        assert!(!registry.list_releases().unwrap().contains(&unversioned_key));
+
+        registry.unregister(unversioned_key.clone()).unwrap();
+
+        assert!(!temp_dir.path().join("mondo.owl").exists());
+        assert!(temp_dir.path().join("mondo@2024-01-04.owl").exists());
+        assert!(registry.get(unversioned_key).is_none());
+    }
+
+    #[test]
+    fn test_unversioned_file_name_in_nested_layout() {
+        let temp_dir = tempdir().unwrap();
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new().with_content("mondo", "mondo"),
+        )
+        .with_layout(RegistryLayout::Nested);
+        let unversioned_key = RegistryKey::new("mondo", Version::Unversioned, FileType::Owl);
+
+        registry.register(unversioned_key.clone()).unwrap();
+
+        assert!(
+            temp_dir
+                .path()
+                .join("mondo")
+                .join("unversioned.owl")
+                .exists()
+        );
+        assert_eq!(registry.list().unwrap(), vec![unversioned_key]);
+    }
+
+    #[test]
+    fn test_prewarm_registers_each_id_and_fails_soft() {
+        let temp_dir = tempdir().unwrap();
+        let ontology_mock = MockOntologyProvider::new()
+            .with_content("hp", "hp")
+            .with_content("HP", "hp")
+            .with_content("mondo", "mondo");
+        let requested_file_names = ontology_mock.requested_file_names.clone();
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new()
+                .with_version("hp", "2024-04-26")
+                .with_version("HP", "2024-04-26")
+                .with_version("mondo", "2024-01-04"),
+            ontology_mock,
+        );
+
+        let results = registry.prewarm(
+            &["hp", "mondo", "unknown", "HP", "hp"],
+            &Version::Latest,
+            &FileType::Obo,
+            3,
+        );
+
+        let ids: Vec<&str> = results.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["hp", "mondo", "unknown", "HP", "hp"]);
+        let hp_path = temp_dir.path().join("hp@2024-04-26.obo");
+        assert_eq!(results[0].1.as_ref().unwrap(), &hp_path);
+        assert_eq!(
+            results[1].1.as_ref().unwrap(),
+            &temp_dir.path().join("mondo@2024-01-04.obo")
+        );
+        assert!(results[2].1.is_err());
+        assert_eq!(results[3].1.as_ref().unwrap(), &hp_path);
+        assert_eq!(results[4].1.as_ref().unwrap(), &hp_path);
+        assert_eq!(fs::read_to_string(&hp_path).unwrap(), "hp");
+        assert_eq!(registry.list().unwrap().len(), 2);
+        assert_eq!(requested_file_names.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_observer_receives_registry_events() {
+        #[derive(Debug, Default)]
+        struct RecordingObserver {
+            events: Arc<Mutex<Vec<String>>>,
+        }
+
+        impl RegistryObserver for RecordingObserver {
+            fn on_download(&self, registry_key: &RegistryKey, bytes: u64, _duration: Duration) {
+                let event = format!("download {} {bytes}", registry_key.as_file_name());
+                self.events.lock().unwrap().push(event);
+            }
+
+            fn on_cache_hit(&self, registry_key: &RegistryKey) {
+                let event = format!("cache_hit {}", registry_key.as_file_name());
+                self.events.lock().unwrap().push(event);
+            }
+
+            fn on_evict(&self, path: &Path, bytes: u64) {
+                let file_name = path.file_name().unwrap().to_string_lossy();
+                let event = format!("evict {file_name} {bytes}");
+                self.events.lock().unwrap().push(event);
+            }
+
+            fn on_error(&self, registry_key: &RegistryKey, _error: &OntologyRegistryError) {
+                let event = format!("error {}", registry_key.as_file_name());
+                self.events.lock().unwrap().push(event);
+            }
+        }
+
+        let temp_dir = tempdir().unwrap();
+        let observer = RecordingObserver::default();
+        let events = observer.events.clone();
+        let registry = FileSystemOntologyRegistry::builder(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new()
+                .with_version("hp", "2024-04-26")
+                .with_version("mondo", "2024-01-04"),
+            MockOntologyProvider::new()
+                .with_content("hp", "hp")
+                .with_content("mondo", "mondo"),
+        )
+        .with_max_bytes(5)
+        .with_observer(observer)
+        .build()
+        .unwrap();
+
+        for ontology_id in ["hp", "hp", "mondo", "unknown"] {
+            let _ = registry.register(RegistryKey::new(
+                ontology_id,
+                Version::Latest,
+                FileType::Obo,
+            ));
+        }
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                "download hp@2024-04-26.obo 2",
+                "cache_hit hp@2024-04-26.obo",
+                "evict hp@2024-04-26.obo 2",
+                "download mondo@2024-01-04.obo 5",
+                "error unknown@latest.obo",
+            ]
+        );
+    }
+
+    fn register_stale_hp(registry_path: &Path) -> PathBuf {
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.to_path_buf(),
+            MockMetadataProvider::new().with_version("hp", "2024-04-26"),
+            MockOntologyProvider::new().with_content("hp", "old"),
+        );
+        let key = RegistryKey::new("hp", Version::Latest, FileType::Obo);
+        registry.register(key).unwrap();
+        let mut manifest = RegistryManifest::load(registry_path).unwrap();
+        manifest.checked_at.insert(
+            "hp@2024-04-26.obo".to_string(),
+            SystemTime::now() - Duration::from_secs(3600),
+        );
+        manifest.save(registry_path).unwrap();
+        registry
+            .path_for("hp", &Version::from("2024-04-26"), &FileType::Obo)
+            .unwrap()
+    }
+
+    fn read_to_string(mut file: File) -> String {
+        let mut content = String::new();
+        file.read_to_string(&mut content).unwrap();
+        content
+    }
+
+    #[test]
+    fn test_get_fresh_serves_recent_file_without_download() {
+        let temp_dir = tempdir().unwrap();
+        register_stale_hp(temp_dir.path());
+        let ontology_mock = MockOntologyProvider::new().with_content("hp", "new");
+        let requested_file_names = ontology_mock.requested_file_names.clone();
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new().with_version("hp", "2024-04-26"),
+            ontology_mock,
+        )
+        .with_refresh_after(Duration::from_secs(2 * 3600));
+
+        let file = registry
+            .get_fresh(RegistryKey::new("hp", Version::Latest, FileType::Obo))
+            .unwrap();
+
+        assert_eq!(read_to_string(file), "old");
+        assert!(requested_file_names.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_get_fresh_refreshes_stale_file() {
+        let temp_dir = tempdir().unwrap();
+        let path = register_stale_hp(temp_dir.path());
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new().with_version("hp", "2024-04-26"),
+            MockOntologyProvider::new().with_content("hp", "new"),
+        )
+        .with_refresh_after(Duration::from_secs(60));
+
+        let file = registry
+            .get_fresh(RegistryKey::new("hp", Version::Latest, FileType::Obo))
+            .unwrap();
+
+        assert_eq!(read_to_string(file), "new");
+        let age = registry
+            .cached_at("hp", &Version::from("2024-04-26"), &FileType::Obo)
+            .unwrap()
+            .elapsed();
+        assert!(age.unwrap_or_default() < Duration::from_secs(60));
+        assert_eq!(fs::read_to_string(path).unwrap(), "new");
+    }
+
+    #[test]
+    fn test_get_fresh_serves_stale_file_when_offline() {
+        let temp_dir = tempdir().unwrap();
+        register_stale_hp(temp_dir.path());
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new().with_version("hp", "2024-04-26"),
+            MockOntologyProvider::new().with_content("hp", "new"),
+        )
+        .with_refresh_after(Duration::from_secs(60))
+        .with_offline(true);
+
+        let file = registry
+            .get_fresh(RegistryKey::new("hp", Version::Latest, FileType::Obo))
+            .unwrap();
+
+        assert_eq!(read_to_string(file), "old");
+        assert!(
+            registry
+                .get_fresh(RegistryKey::new("mondo", Version::Latest, FileType::Obo))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_failed_deduplication_lookup_removes_temp_dir() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(temp_dir.path().join(MANIFEST_FILE_NAME), "not json").unwrap();
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new().with_content("hp", "hp content"),
+        )
+        .with_deduplication(true);
+
+        let result = registry.register(RegistryKey::new(
+            "hp",
+            Version::from("2024-04-26"),
+            FileType::Obo,
+        ));
+
+        assert!(result.is_err());
+        let names: Vec<_> = fs::read_dir(temp_dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names, vec![MANIFEST_FILE_NAME]);
+    }
+
+    #[test]
+    fn test_deduplication_links_identical_versions() {
+        let temp_dir = tempdir().unwrap();
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new().with_content("hp", "hp content"),
+        )
+        .with_deduplication(true);
+        let old_key = RegistryKey::new("hp", Version::from("2024-01-01"), FileType::Obo);
+        let new_key = RegistryKey::new("hp", Version::from("2024-04-26"), FileType::Obo);
+
+        registry.register(old_key.clone()).unwrap();
+        registry.register(new_key.clone()).unwrap();
+
+        let old_path = registry.path(old_key.clone()).unwrap();
+        let new_path = registry.path(new_key.clone()).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            let old_metadata = fs::metadata(&old_path).unwrap();
+            let new_metadata = fs::metadata(&new_path).unwrap();
+            assert_eq!(old_metadata.ino(), new_metadata.ino());
+            assert_eq!(new_metadata.nlink(), 2);
+        }
+        assert_eq!(fs::read_to_string(&new_path).unwrap(), "hp content");
+        assert!(
+            registry
+                .verify()
+                .unwrap()
+                .iter()
+                .all(|report| matches!(report, VerifyReport::Ok { .. }))
+        );
+
+        registry.unregister(old_key.clone()).unwrap();
+
+        assert!(registry.get(old_key).is_none());
+        let mut content = String::new();
+        registry
+            .get(new_key)
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "hp content");
+    }
+
+    #[test]
+    fn test_register_rejects_invalid_ontology_id() {
+        let temp_dir = tempdir().unwrap();
+        let ontology_mock = MockOntologyProvider::new().with_content("hp", "hp content");
+        let requested_file_names = ontology_mock.requested_file_names.clone();
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new(),
+            ontology_mock,
+        );
+
+        for ontology_id in ["", "hp/../hp", "hp\\go"] {
+            let result = registry.register(RegistryKey::new(
+                ontology_id,
+                Version::from("1.0"),
+                FileType::Obo,
+            ));
+            assert!(matches!(
+                result,
+                Err(OntologyRegistryError::InvalidOntologyId { .. })
+            ));
+        }
+        let reg_key = RegistryKey::new("hp/../hp", Version::from("1.0"), FileType::Obo);
+        assert!(registry.get(reg_key.clone()).is_none());
+        assert!(matches!(
+            registry.unregister(reg_key),
+            Err(OntologyRegistryError::InvalidOntologyId { .. })
+        ));
+        assert!(matches!(
+            registry.register_bytes("hp@1.0", &Version::from("1.0"), &FileType::Obo, b"hp"),
+            Err(OntologyRegistryError::InvalidOntologyId { .. })
+        ));
+        assert!(matches!(
+            registry.resolve_version("", &Version::Latest, &FileType::Obo),
+            Err(OntologyRegistryError::InvalidOntologyId { .. })
+        ));
+        assert!(requested_file_names.lock().unwrap().is_empty());
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_entry_points_accept_ontology_id() {
+        let temp_dir = tempdir().unwrap();
+        let ontology_mock = MockOntologyProvider::new();
+        let requested_file_names = ontology_mock.requested_file_names.clone();
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new(),
+            ontology_mock,
+        );
+        let version = Version::from("1.0");
+
+        registry
+            .register_bytes(
+                OntologyId::new("CL modifier").unwrap(),
+                &version,
+                &FileType::Obo,
+                b"cl content",
+            )
+            .unwrap();
+        let mut content = String::new();
+        registry
+            .get_or_register("cl modifier", &version, &FileType::Obo)
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+
+        assert_eq!(content, "cl content");
+        assert!(temp_dir.path().join("cl modifier@1.0.obo").is_file());
+        assert!(requested_file_names.lock().unwrap().is_empty());
+        assert_eq!(registry.unregister_all("CL modifier", None).unwrap(), 1);
+        assert!(matches!(
+            registry.get_or_register("cl/modifier", &version, &FileType::Obo),
+            Err(OntologyRegistryError::InvalidOntologyId { .. })
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "http")]
+    fn test_register_from_metadata_downloads_file_location() {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("GET", "/custom/hp-full.obo")
+            .match_header("user-agent", "registry-test")
+            .with_status(200)
+            .with_body("hp content")
+            .create();
+        let temp_dir = tempdir().unwrap();
+        let ontology_mock = MockOntologyProvider::new();
+        let requested_file_names = ontology_mock.requested_file_names.clone();
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new(),
+            ontology_mock,
+        )
+        .with_url_provider(UrlOntologyProvider::default().with_user_agent("registry-test"));
+        let metadata = OntologyMetadata {
+            ontology_id: "hp".to_string(),
+            version: "2024-04-26".to_string(),
+            obo_file_location: Some(format!("{}/custom/hp-full.obo", server.url())),
+            ..Default::default()
+        };
+
+        let path = registry
+            .register_from_metadata(&metadata, &FileType::Obo)
+            .unwrap();
+
+        mock.assert();
+        assert_eq!(path, temp_dir.path().join("hp@2024-04-26.obo"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "hp content");
+        assert!(
+            registry
+                .get(RegistryKey::new(
+                    "hp",
+                    Version::from("2024-04-26"),
+                    FileType::Obo
+                ))
+                .is_some()
+        );
+        assert!(requested_file_names.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "http")]
+    fn test_register_from_metadata_without_location() {
+        let temp_dir = tempdir().unwrap();
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new(),
+        );
+        let metadata = OntologyMetadata {
+            ontology_id: "hp".to_string(),
+            version: "2024-04-26".to_string(),
+            obo_file_location: Some("http://localhost/hp.obo".to_string()),
+            ..Default::default()
+        };
+
+        let result = registry.register_from_metadata(&metadata, &FileType::Owl);
+
+        assert!(matches!(
+            result,
+            Err(OntologyRegistryError::ProvidingOntology { .. })
+        ));
+        assert!(registry.list().unwrap().is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "http")]
+    fn test_register_from_metadata_offline() {
+        let mut server = mockito::Server::new();
+        let mock = server.mock("GET", "/hp.obo").expect(0).create();
+        let temp_dir = tempdir().unwrap();
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new(),
+        )
+        .with_offline(true);
+        let metadata = OntologyMetadata {
+            ontology_id: "hp".to_string(),
+            version: "2024-04-26".to_string(),
+            obo_file_location: Some(format!("{}/hp.obo", server.url())),
+            ..Default::default()
+        };
+
+        let result = registry.register_from_metadata(&metadata, &FileType::Obo);
+
+        assert!(matches!(result, Err(OntologyRegistryError::Offline { .. })));
+        mock.assert();
+        assert!(registry.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unregister_all_removes_every_version() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new()
+                .with_content("hp", "hp")
+                .with_content("mondo", "mondo"),
+        );
+        for version in ["2024-01-01", "2024-04-26", "2025-01-01"] {
+            registry
+                .register(RegistryKey::new(
+                    "hp",
+                    Version::from(version),
+                    FileType::Obo,
+                ))
+                .unwrap();
+        }
+        registry
+            .register(RegistryKey::new(
+                "hp",
+                Version::from("2024-04-26"),
+                FileType::Json,
+            ))
+            .unwrap();
+        registry
+            .register(RegistryKey::new(
+                "mondo",
+                Version::from("2024-01-04"),
+                FileType::Obo,
+            ))
+            .unwrap();
+        fs::create_dir(registry_path.join(".tmp_0_0_0")).unwrap();
+
+        assert_eq!(
+            registry.unregister_all("HP", Some(&FileType::Obo)).unwrap(),
+            3
+        );
+        assert_eq!(registry.list_filtered(Some("hp"), None).unwrap().len(), 1);
+        assert_eq!(registry.unregister_all("hp", None).unwrap(), 1);
+        assert_eq!(
+            registry.list().unwrap(),
+            vec![RegistryKey::new(
+                "mondo",
+                Version::from("2024-01-04"),
+                FileType::Obo
+            )]
+        );
+        assert!(registry_path.join(".tmp_0_0_0").is_dir());
+    }
+
+    #[test]
+    fn test_clear_empty_and_missing_directory() {
+        let temp_dir = tempdir().unwrap();
+
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new(),
+        );
+        assert_eq!(registry.clear().unwrap(), 0);
+
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().join("missing"),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new(),
+        );
+        assert_eq!(registry.clear().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_verify_reports_good_corrupt_and_stray_files() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new()
+                .with_content("good", "good content")
+                .with_content("bad", "bad content"),
+        );
+        registry
+            .register(RegistryKey::new(
+                "good",
+                Version::from("1.0"),
+                FileType::Obo,
+            ))
+            .unwrap();
+        registry
+            .register(RegistryKey::new("bad", Version::from("1.0"), FileType::Obo))
+            .unwrap();
+
+        let good = registry_path.join("good@1.0.obo");
+        let bad = registry_path.join("bad@1.0.obo");
+        let stray = registry_path.join("leftover@1.0.obo.tmp");
+        fs::write(&bad, "tampered").unwrap();
+        fs::write(&stray, "partial").unwrap();
+
+        let reports = registry.verify().unwrap();
+
+        assert_eq!(reports.len(), 3);
+        assert!(reports.contains(&VerifyReport::Ok { path: good }));
+        assert!(
+            reports
+                .iter()
+                .any(|report| matches!(report, VerifyReport::Corrupt { path, .. } if *path == bad))
+        );
+        assert!(reports.contains(&VerifyReport::StrayTemporary { path: stray }));
+    }
+
+    #[test]
+    fn test_cleanup_temp_files() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+
+        fs::write(registry_path.join("A@1.0.json"), "").unwrap();
+        fs::write(registry_path.join("B@2.0.obo.tmp"), "partial").unwrap();
+        fs::create_dir(registry_path.join(".tmp_1_2")).unwrap();
+        fs::write(registry_path.join(".tmp_1_2").join("C@3.0.owl.tmp"), "").unwrap();
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new(),
+        );
+
+        assert_eq!(registry.list().unwrap().len(), 1);
+        assert_eq!(registry.cleanup_temp_files().unwrap(), 2);
+        assert!(!registry_path.join("B@2.0.obo.tmp").exists());
+        assert!(!registry_path.join(".tmp_1_2").exists());
+        assert!(registry_path.join("A@1.0.json").exists());
+    }
+
+    #[test]
+    fn test_open_cleans_up_temp_files() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+        fs::write(registry_path.join("B@2.0.obo.tmp"), "partial").unwrap();
+
+        FileSystemOntologyRegistry::open(
+            registry_path.clone(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new(),
+        )
+        .unwrap();
+
+        assert!(!registry_path.join("B@2.0.obo.tmp").exists());
+    }
+
+    #[test]
+    fn test_try_new_rejects_file_as_registry_path() {
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("ontologies");
+        fs::write(&file_path, "not a directory").unwrap();
+
+        let result = FileSystemOntologyRegistry::try_new(
+            file_path.clone(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new(),
+        );
+
+        match result {
+            Err(err @ OntologyRegistryError::NotADirectory { .. }) => {
+                assert_eq!(
+                    err.to_string(),
+                    format!(
+                        "Registry path {} exists but is not a directory",
+                        file_path.display()
+                    )
+                );
+            }
+            _ => panic!("Expected NotADirectory"),
+        }
+        for registry_path in [
+            temp_dir.path().to_path_buf(),
+            temp_dir.path().join("missing"),
+        ] {
+            assert!(
+                FileSystemOntologyRegistry::try_new(
+                    registry_path,
+                    MockMetadataProvider::new(),
+                    MockOntologyProvider::new(),
+                )
+                .is_ok()
+            );
+        }
+    }
+
+    #[test]
+    fn test_prune_older_than_removes_only_stale_files() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+
+        let stale = registry_path.join("stale@1.0.obo");
+        let fresh = registry_path.join("fresh@1.0.obo");
+        fs::write(&stale, "").unwrap();
+        fs::write(&fresh, "").unwrap();
+        fs::create_dir(registry_path.join("subdir")).unwrap();
+        File::options()
+            .write(true)
+            .open(&stale)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(30 * 24 * 60 * 60))
+            .unwrap();
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new(),
+        );
+
+        let removed = registry
+            .prune_older_than(Duration::from_secs(24 * 60 * 60))
+            .unwrap();
+
+        assert_eq!(removed, vec![stale.clone()]);
+        assert!(!stale.exists());
+        assert!(fresh.exists());
+        assert!(registry_path.join("subdir").is_dir());
+    }
+
+    #[test]
+    fn test_nested_layout_round_trip() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new().with_version("MONDO", "2024-02-01"),
+            MockOntologyProvider::new().with_content("MONDO", "mondo content"),
+        )
+        .with_layout(RegistryLayout::Nested);
+
+        let reg_key = RegistryKey::new("MONDO", Version::Latest, FileType::Json);
+        registry.register(reg_key.clone()).unwrap();
+
+        let stored_path = registry_path.join("mondo").join("2024-02-01.json");
+        assert_eq!(fs::read_to_string(&stored_path).unwrap(), "mondo content");
+
+        let mut content = String::new();
+        registry
+            .get(reg_key.clone())
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "mondo content");
+
+        registry.unregister(reg_key).unwrap();
+        assert!(!stored_path.exists());
+        assert!(!registry_path.join("mondo").exists());
+    }
+
+    #[test]
+    fn test_nested_layout_list() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+
+        fs::create_dir(registry_path.join("hp")).unwrap();
+        fs::create_dir(registry_path.join("mondo")).unwrap();
+        fs::write(registry_path.join("hp").join("1.0.obo"), "").unwrap();
+        fs::write(registry_path.join("hp").join("2.0.obo"), "").unwrap();
+        fs::write(registry_path.join("mondo").join("3.0.json"), "").unwrap();
+        fs::write(registry_path.join("mondo").join(".DS_Store"), "").unwrap();
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path,
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new(),
+        )
+        .with_layout(RegistryLayout::Nested);
+
+        let mut files = registry.list().unwrap();
+        files.sort();
+
+        assert_eq!(
+            files,
+            vec![
+                RegistryKey::new("hp", Version::from("1.0"), FileType::Obo),
+                RegistryKey::new("hp", Version::from("2.0"), FileType::Obo),
+                RegistryKey::new("mondo", Version::from("3.0"), FileType::Json),
+            ]
+        );
+    }
+
+    /// Set by `test_file_lock_across_processes` for the child processes it spawns.
+    const FILE_LOCK_CHILD_ENV: &str = "ONTOLOGY_REGISTRY_FILE_LOCK_CHILD_DIR";
+
+    fn file_lock_registry(
+        registry_path: PathBuf,
+    ) -> FileSystemOntologyRegistry<MockMetadataProvider, MockOntologyProvider> {
+        FileSystemOntologyRegistry::new(
+            registry_path,
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new().with_content("large", &"a".repeat(4 * 1024 * 1024)),
+        )
+        .with_file_lock(true)
+    }
+
+    /// Registers in a child process of `test_file_lock_across_processes`, which passes
+    /// `--ignored` to run it. Ignored otherwise, as it checks nothing on its own.
+    #[test]
+    #[ignore = "run by test_file_lock_across_processes in a child process"]
+    fn file_lock_child_registers() {
+        let Ok(registry_path) = std::env::var(FILE_LOCK_CHILD_ENV) else {
+            return;
+        };
+
+        let registry = file_lock_registry(PathBuf::from(registry_path));
+        for _ in 0..5 {
+            registry
+                .refresh(RegistryKey::new(
+                    "large",
+                    Version::from("1.0"),
+                    FileType::Owl,
+                ))
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_file_lock_across_processes() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+
+        let children: Vec<_> = (0..2)
+            .map(|_| {
+                process::Command::new(std::env::current_exe().unwrap())
+                    .args([
+                        "--exact",
+                        "blocking::file_system_ontology_registry::tests::file_lock_child_registers",
+                        "--ignored",
+                        "--test-threads=1",
+                    ])
+                    .env(FILE_LOCK_CHILD_ENV, &registry_path)
+                    .stdout(process::Stdio::null())
+                    .spawn()
+                    .unwrap()
+            })
+            .collect();
+        for mut child in children {
+            assert!(child.wait().unwrap().success());
+        }
+
+        let registry = file_lock_registry(registry_path.clone());
+        let stored = fs::read_to_string(registry_path.join("large@1.0.owl")).unwrap();
+        assert_eq!(stored.len(), 4 * 1024 * 1024);
+        assert!(stored.bytes().all(|b| b == b'a'));
+        assert!(matches!(
+            registry.verify().unwrap().as_slice(),
+            [VerifyReport::Ok { .. }]
+        ));
+        assert!(registry_path.join(LOCK_FILE_NAME).exists());
+        assert_eq!(
+            registry.list().unwrap(),
+            vec![RegistryKey::new(
+                "large",
+                Version::from("1.0"),
+                FileType::Owl
+            )]
+        );
+    }
+
+    #[test]
+    fn test_create_temp_dir_is_unique() {
+        let temp_dir = tempdir().unwrap();
+        let registry = FileSystemOntologyRegistry::new(
+            temp_dir.path().to_path_buf(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new(),
+        );
+
+        let temp_dirs: Vec<PathBuf> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..8)
+                .map(|_| scope.spawn(|| registry.create_temp_dir(temp_dir.path()).unwrap()))
+                .collect();
+            workers.into_iter().map(|w| w.join().unwrap()).collect()
+        });
+
+        let unique: std::collections::HashSet<_> = temp_dirs.iter().collect();
+        assert_eq!(unique.len(), temp_dirs.len());
+    }
+
+    #[test]
+    fn test_concurrent_writes_of_same_release_stay_intact() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new(),
+        );
+
+        let contents: Vec<String> = (b'a'..=b'h')
+            .map(|byte| (byte as char).to_string().repeat(256 * 1024))
+            .collect();
+        thread::scope(|scope| {
+            for content in &contents {
+                let registry = &registry;
+                scope.spawn(move || {
+                    registry
+                        .register_bytes(
+                            "hp",
+                            &Version::from("1.0"),
+                            &FileType::Obo,
+                            content.as_bytes(),
+                        )
+                        .unwrap();
+                });
+            }
+        });
+
+        let stored = fs::read_to_string(registry_path.join("hp@1.0.obo")).unwrap();
+        assert!(contents.contains(&stored));
+        assert!(matches!(
+            registry.verify().unwrap().as_slice(),
+            [VerifyReport::Ok { .. }]
+        ));
+        assert_eq!(registry.cleanup_temp_files().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_unsafe_versions_are_rejected() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().join("registry");
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new().with_version("hp", "../../evil"),
+            MockOntologyProvider::new().with_content("hp", "hp content"),
+        );
+
+        let result = registry.register(RegistryKey::new("hp", Version::Latest, FileType::Obo));
+        assert!(matches!(
+            result,
+            Err(OntologyRegistryError::UnsafeFileName { .. })
+        ));
+
+        for version in ["2024/01", "/etc/passwd", "1.0\\..\\..\\evil"] {
+            let reg_key = RegistryKey::new("hp", Version::from(version), FileType::Obo);
+            assert!(matches!(
+                registry.register(reg_key.clone()),
+                Err(OntologyRegistryError::UnsafeFileName { .. })
+            ));
+            assert!(registry.get(reg_key.clone()).is_none());
+            assert!(matches!(
+                registry.unregister(reg_key),
+                Err(OntologyRegistryError::UnsafeFileName { .. })
+            ));
+        }
+
+        let escaped: Vec<_> = fs::read_dir(temp_dir.path())
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name())
+            .collect();
+        assert_eq!(escaped, vec![std::ffi::OsString::from("registry")]);
+        assert!(registry.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_local_versions() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+        for file_name in [
+            "hp@2024-04-26.obo",
+            "hp@2023-10-09.obo",
+            "HP@2024-02-08.obo",
+            "hp@2025-01-01.json",
+            "mondo@2024-01-01.obo",
+        ] {
+            fs::write(registry_path.join(file_name), "").unwrap();
+        }
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path,
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new(),
+        );
+
+        assert_eq!(
+            registry.local_versions("hp", &FileType::Obo).unwrap(),
+            vec!["2023-10-09", "2024-02-08", "2024-04-26"]
+        );
+        assert!(
+            registry
+                .local_versions("uberon", &FileType::Obo)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_plan_register_does_not_download() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+        let ontology_mock = MockOntologyProvider::new().with_content("hp", "hp content");
+        let requested_file_names = ontology_mock.requested_file_names.clone();
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new().with_version("hp", "2024-04-26"),
+            ontology_mock,
         );
-        let result = registry.register(reg_key);
 
-        assert!(result.is_ok());
-        let mut file = result.unwrap();
-        let mut content = String::new();
-        file.read_to_string(&mut content).unwrap();
+        let expected_path = registry_path.join("hp@2024-04-26.obo");
+        let plan = registry
+            .plan_register("hp", &Version::Latest, &FileType::Obo)
+            .unwrap();
+        assert_eq!(
+            plan,
+            RegisterPlan {
+                target_path: expected_path.clone(),
+                resolved_version: "2024-04-26".to_string(),
+                already_present: false,
+            }
+        );
+        assert!(requested_file_names.lock().unwrap().is_empty());
+        assert!(!expected_path.exists());
 
-        assert_eq!(content, "<rdf>content</rdf>");
+        registry
+            .register(RegistryKey::new("hp", Version::Latest, FileType::Obo))
+            .unwrap();
+        requested_file_names.lock().unwrap().clear();
+
+        let plan = registry
+            .plan_register("hp", &Version::from("2024-04-26"), &FileType::Obo)
+            .unwrap();
+        assert!(plan.already_present);
+        assert_eq!(plan.target_path, expected_path);
+        assert!(requested_file_names.lock().unwrap().is_empty());
     }
 
     #[test]
-    fn test_register_latest_version_resolution() {
+    fn test_try_get_surfaces_metadata_errors() {
         let temp_dir = tempdir().unwrap();
-        let content = "latest_context";
-        let metadata_mock = MockMetadataProvider::new().with_version("my_ontology", "2024-05-05");
-        let ontology_mock = MockOntologyProvider::new().with_content("my_ontology", content);
-
         let registry = FileSystemOntologyRegistry::new(
             temp_dir.path().to_path_buf(),
-            metadata_mock,
-            ontology_mock,
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new().with_content("hp", "hp content"),
         );
 
-        let reg_key = RegistryKey::new("my_ontology", Version::Latest, FileType::Json);
-        let result = registry.register(reg_key);
+        let latest = RegistryKey::new("hp", Version::Latest, FileType::Obo);
+        assert!(registry.try_get(latest.clone()).is_err());
+        assert!(registry.get(latest).is_none());
 
-        assert!(result.is_ok());
-        let mut file = result.unwrap();
-        let mut loaded_content = String::new();
-        file.read_to_string(&mut loaded_content).unwrap();
+        let declared = RegistryKey::new("hp", Version::from("2024-04-26"), FileType::Obo);
+        assert!(registry.try_get(declared.clone()).unwrap().is_none());
 
-        assert_eq!(loaded_content, content);
+        registry.register(declared.clone()).unwrap();
+        assert!(registry.try_get(declared).unwrap().is_some());
     }
 
     #[test]
-    fn test_register_skips_existing_file() {
+    fn test_lowercase_ids_share_one_file_and_reach_providers_lowercased() {
         let temp_dir = tempdir().unwrap();
         let registry_path = temp_dir.path().to_path_buf();
+        let metadata_mock = MockMetadataProvider::new().with_version("mondo", "2024-05-05");
+        let ontology_mock =
+            MockOntologyProvider::new().with_content("mondo", "format-version: 1.2");
 
-        let existing_file_path = registry_path.join("test_ont_1.0.rdf");
-        fs::write(&existing_file_path, "old_content").unwrap();
+        let default_registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            metadata_mock.clone(),
+            ontology_mock.clone(),
+        );
+        assert!(
+            default_registry
+                .register(RegistryKey::new("MONDO", Version::Latest, FileType::Obo))
+                .is_err()
+        );
 
-        let metadata_mock = MockMetadataProvider::new();
-        let ontology_mock = MockOntologyProvider::new().with_content("test_ont", "new_content");
+        let registry = FileSystemOntologyRegistry::new(registry_path, metadata_mock, ontology_mock)
+            .with_lowercase_ids(true);
+        registry
+            .register(RegistryKey::new("MONDO", Version::Latest, FileType::Obo))
+            .unwrap();
+        registry
+            .register(RegistryKey::new("Mondo", Version::Latest, FileType::Obo))
+            .unwrap();
 
-        let registry = FileSystemOntologyRegistry::new(registry_path, metadata_mock, ontology_mock);
+        assert_eq!(
+            registry.list().unwrap(),
+            vec![RegistryKey::new(
+                "mondo",
+                Version::from("2024-05-05"),
+                FileType::Obo
+            )]
+        );
+        assert!(
+            registry
+                .get(RegistryKey::new("mOnDo", Version::Latest, FileType::Obo))
+                .is_some()
+        );
+        registry
+            .unregister(RegistryKey::new(
+                "MONDO",
+                Version::from("2024-05-05"),
+                FileType::Obo,
+            ))
+            .unwrap();
+        assert!(registry.list().unwrap().is_empty());
+    }
 
-        let reg_key = RegistryKey::new(
-            "test_ont",
-            Version::Declared("1.0".to_string()),
-            FileType::Json,
+    #[test]
+    fn test_obsolete_ontology_is_reported_and_optionally_replaced() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+        let metadata_mock = MockMetadataProvider::new()
+            .with_metadata(OntologyMetadata {
+                ontology_id: "mp".to_string(),
+                version: "2024-02-07".to_string(),
+                is_obsolete: true,
+                replaced_by: Some("upheno".to_string()),
+                ..Default::default()
+            })
+            .with_version("upheno", "2024-05-01");
+        let ontology_mock = MockOntologyProvider::new()
+            .with_content("mp", "format-version: 1.2")
+            .with_content("upheno", "format-version: 1.4");
+
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            metadata_mock.clone(),
+            ontology_mock.clone(),
         );
-        let result = registry.register(reg_key);
+        let registered = registry
+            .register_detailed(RegistryKey::new("mp", Version::Latest, FileType::Obo))
+            .unwrap();
+        assert!(registered.metadata.is_obsolete);
+        assert_eq!(registered.metadata.replaced_by.as_deref(), Some("upheno"));
+        assert!(registry_path.join("mp@2024-02-07.obo").exists());
 
-        assert!(result.is_ok());
+        let registry = FileSystemOntologyRegistry::new(registry_path, metadata_mock, ontology_mock)
+            .with_follow_replaced_by(true);
+        let registered = registry
+            .register_detailed(RegistryKey::new("mp", Version::Latest, FileType::Obo))
+            .unwrap();
+        assert_eq!(registered.metadata.ontology_id, "upheno");
+        assert!(!registered.metadata.is_obsolete);
+        assert_eq!(
+            fs::read_to_string(&registered.path).unwrap(),
+            "format-version: 1.4"
+        );
+        assert!(
+            registry
+                .get(RegistryKey::new("mp", Version::Latest, FileType::Obo))
+                .is_some()
+        );
+    }
 
-        let content = fs::read_to_string(existing_file_path).unwrap();
-        assert_eq!(content, "old_content");
+    #[test]
+    fn test_register_with_dependencies_follows_two_levels() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+        let metadata = |id: &str, dependencies: &[&str]| OntologyMetadata {
+            ontology_id: id.to_string(),
+            version: "2024-01-01".to_string(),
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+            ..Default::default()
+        };
+        let metadata_mock = MockMetadataProvider::new()
+            .with_metadata(metadata("uberon", &["ro", "unknown"]))
+            .with_metadata(metadata("ro", &["bfo", "uberon"]))
+            .with_metadata(metadata("bfo", &[]));
+        let ontology_mock = MockOntologyProvider::new()
+            .with_content("uberon", "uberon")
+            .with_content("ro", "ro")
+            .with_content("bfo", "bfo");
+        let registry =
+            FileSystemOntologyRegistry::new(registry_path.clone(), metadata_mock, ontology_mock);
+
+        let paths = registry
+            .register_with_dependencies("uberon", &Version::from("2023-12-01"), &FileType::Obo)
+            .unwrap();
+
+        assert_eq!(
+            paths,
+            vec![
+                registry_path.join("uberon@2023-12-01.obo"),
+                registry_path.join("ro@2024-01-01.obo"),
+                registry_path.join("bfo@2024-01-01.obo"),
+            ]
+        );
+        assert_eq!(fs::read_to_string(&paths[2]).unwrap(), "bfo");
     }
 
     #[test]
-    fn test_get_existing_ontology() {
+    fn test_cancel_flag_aborts_download_without_committing() {
+        /// Yields one chunk, then raises the cancel flag as if a shutdown began mid-download.
+        struct CancellingReader {
+            cancel_flag: Arc<AtomicBool>,
+            chunks: usize,
+        }
+
+        impl Read for CancellingReader {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                if self.chunks == 1 {
+                    self.cancel_flag.store(true, Ordering::Relaxed);
+                }
+                self.chunks += 1;
+                buf[0] = b'x';
+                Ok(1)
+            }
+        }
+
+        #[derive(Debug)]
+        struct CancellingProvider {
+            cancel_flag: Arc<AtomicBool>,
+        }
+
+        impl OntologyProviding for CancellingProvider {
+            fn provide_ontology(
+                &self,
+                _ontology_id: &str,
+                _file_name: &str,
+                _version: &Version,
+            ) -> Result<impl Read, OntologyRegistryError> {
+                Ok(CancellingReader {
+                    cancel_flag: self.cancel_flag.clone(),
+                    chunks: 0,
+                })
+            }
+        }
+
         let temp_dir = tempdir().unwrap();
         let registry_path = temp_dir.path().to_path_buf();
-        let target_path = registry_path.join("findme@2024-05-05.json");
+        let cancel_flag = Arc::new(AtomicBool::new(false));
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new(),
+            CancellingProvider {
+                cancel_flag: cancel_flag.clone(),
+            },
+        )
+        .with_cancel_flag(cancel_flag.clone());
+        let registry_key = RegistryKey::new("hp", Version::from("2024-04-26"), FileType::Obo);
 
-        let content = "data";
-        fs::write(&target_path, content).unwrap();
+        let result = registry.register(registry_key.clone());
+
+        assert!(matches!(result, Err(OntologyRegistryError::Cancelled)));
+        assert!(fs::read_dir(&registry_path).unwrap().next().is_none());
+        assert!(matches!(
+            registry.register(registry_key),
+            Err(OntologyRegistryError::Cancelled)
+        ));
+    }
 
+    #[test]
+    fn test_validator_rejects_html_and_accepts_ontology() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+        let ontology_mock = MockOntologyProvider::new()
+            .with_content("hp", "\n<!DOCTYPE HTML><html><body>Not Found</body></html>")
+            .with_content("mondo", "format-version: 1.2");
         let registry = FileSystemOntologyRegistry::new(
-            registry_path,
+            registry_path.clone(),
             MockMetadataProvider::new(),
-            MockOntologyProvider::new(),
-        );
+            ontology_mock,
+        )
+        .with_validator(validate_not_empty_or_html);
 
-        let reg_key = RegistryKey::new(
-            "findme",
-            Version::Declared("2024-05-05".to_string()),
-            FileType::Json,
+        let result = registry.register(RegistryKey::new(
+            "hp",
+            Version::from("2024-04-26"),
+            FileType::Obo,
+        ));
+        match result {
+            Err(OntologyRegistryError::UnableToRegister { reason }) => {
+                assert!(reason.contains("HTML"), "{reason}");
+            }
+            other => panic!("Expected a validation error, got {other:?}"),
+        }
+        assert!(!registry_path.join("hp@2024-04-26.obo").exists());
+
+        registry
+            .register(RegistryKey::new(
+                "mondo",
+                Version::from("2024-01-04"),
+                FileType::Obo,
+            ))
+            .unwrap();
+        assert!(registry_path.join("mondo@2024-01-04.obo").exists());
+        assert!(
+            registry
+                .register_bytes("go", &Version::from("2024-01-17"), &FileType::Json, b"")
+                .is_err()
         );
+    }
 
-        let result = registry.get(reg_key);
+    #[test]
+    fn test_offline_mode_never_calls_providers() {
+        #[derive(Default)]
+        struct CountingProvider {
+            calls: AtomicUsize,
+        }
 
-        let mut file = result.unwrap();
-        let mut loaded_content = String::new();
-        file.read_to_string(&mut loaded_content).unwrap();
+        impl OntologyMetadataProviding for CountingProvider {
+            fn provide_metadata(
+                &self,
+                _ontology_id: &str,
+            ) -> Result<OntologyMetadata, OntologyRegistryError> {
+                self.calls.fetch_add(1, Ordering::Relaxed);
+                Ok(OntologyMetadata::default())
+            }
+        }
 
-        assert_eq!(loaded_content, content);
+        impl OntologyProviding for CountingProvider {
+            fn provide_ontology(
+                &self,
+                _ontology_id: &str,
+                _file_name: &str,
+                _version: &Version,
+            ) -> Result<impl Read, OntologyRegistryError> {
+                self.calls.fetch_add(1, Ordering::Relaxed);
+                Ok(Cursor::new(Vec::new()))
+            }
+        }
+
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+        for file_name in [
+            "hp@2024-01-01.obo",
+            "hp@2024-04-26.obo",
+            "hp@2025-01-01.json",
+        ] {
+            fs::write(registry_path.join(file_name), file_name).unwrap();
+        }
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path,
+            CountingProvider::default(),
+            CountingProvider::default(),
+        )
+        .with_offline(true);
+
+        let mut content = String::new();
+        registry
+            .register(RegistryKey::new("hp", Version::Latest, FileType::Obo))
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "hp@2024-04-26.obo");
+        assert!(
+            registry
+                .get(RegistryKey::new(
+                    "hp",
+                    Version::from("2024-01-01"),
+                    FileType::Obo
+                ))
+                .is_some()
+        );
+        assert_eq!(
+            registry
+                .resolve_version("hp", &Version::Latest, &FileType::Obo)
+                .unwrap(),
+            "2024-04-26"
+        );
+        assert_eq!(
+            registry
+                .resolve_version("hp", &Version::Latest, &FileType::Json)
+                .unwrap(),
+            "2025-01-01"
+        );
+        assert!(matches!(
+            registry.resolve_version("hp", &Version::Latest, &FileType::Owl),
+            Err(OntologyRegistryError::Offline { .. })
+        ));
+
+        let missing_release = RegistryKey::new("hp", Version::from("2023-01-01"), FileType::Obo);
+        assert!(matches!(
+            registry.register(missing_release.clone()),
+            Err(OntologyRegistryError::Offline { .. })
+        ));
+        assert!(registry.get(missing_release).is_none());
+        assert!(matches!(
+            registry.register(RegistryKey::new("mondo", Version::Latest, FileType::Obo)),
+            Err(OntologyRegistryError::Offline { .. })
+        ));
+        assert!(matches!(
+            registry.refresh(RegistryKey::new("hp", Version::Latest, FileType::Obo)),
+            Err(OntologyRegistryError::Offline { .. })
+        ));
+
+        assert_eq!(registry.metadata_provider.calls.load(Ordering::Relaxed), 0);
+        assert_eq!(registry.ontology_provider.calls.load(Ordering::Relaxed), 0);
     }
 
     #[test]
-    fn test_get_non_existent_ontology() {
+    fn test_resolve_version() {
         let temp_dir = tempdir().unwrap();
         let registry = FileSystemOntologyRegistry::new(
             temp_dir.path().to_path_buf(),
-            MockMetadataProvider::new(),
+            MockMetadataProvider::new().with_version("hp", "2024-04-26"),
             MockOntologyProvider::new(),
         );
-        let reg_key = RegistryKey::new(
-            "missing",
-            Version::Declared("9.9".to_string()),
-            FileType::Obo,
+
+        assert_eq!(
+            registry
+                .resolve_version("hp", &Version::Latest, &FileType::Obo)
+                .unwrap(),
+            "2024-04-26"
         );
-        let result = registry.get(reg_key);
+        assert_eq!(
+            registry
+                .resolve_version("unknown", &Version::from("1.0"), &FileType::Obo)
+                .unwrap(),
+            "1.0"
+        );
+        assert!(
+            registry
+                .resolve_version("unknown", &Version::Latest, &FileType::Obo)
+                .is_err()
+        );
+    }
 
-        assert!(result.is_none());
+    #[test]
+    fn test_register_turtle_round_trip() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
+        let ontology_mock = MockOntologyProvider::new().with_content("envo", "@prefix : <x> .");
+        let requested_file_names = ontology_mock.requested_file_names.clone();
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new(),
+            ontology_mock,
+        );
+
+        let reg_key = RegistryKey::new("envo", Version::from("2024-07-01"), FileType::Ttl);
+        registry.register(reg_key.clone()).unwrap();
+
+        assert_eq!(*requested_file_names.lock().unwrap(), vec!["envo.ttl"]);
+        assert_eq!(
+            fs::read_to_string(registry_path.join("envo@2024-07-01.ttl")).unwrap(),
+            "@prefix : <x> ."
+        );
+        assert!(registry.get(reg_key.clone()).is_some());
+        assert_eq!(registry.list().unwrap(), vec![reg_key]);
     }
 
     #[test]
-    fn test_unregister_removes_file() {
+    fn test_register_detailed_reports_cache_hits() {
         let temp_dir = tempdir().unwrap();
         let registry_path = temp_dir.path().to_path_buf();
-        let target_path = registry_path.join("todelete@2024-05-05.json");
-        fs::write(&target_path, "delete_me").unwrap();
+        let metadata = OntologyMetadata {
+            ontology_id: "hp".to_string(),
+            version: "2024-04-26".to_string(),
+            obo_file_location: Some("http://purl.obolibrary.org/obo/hp.obo".to_string()),
+            title: Some("Human Phenotype Ontology".to_string()),
+            ..Default::default()
+        };
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new().with_metadata(metadata.clone()),
+            MockOntologyProvider::new().with_content("hp", "hp content"),
+        );
+
+        let first = registry
+            .register_detailed(RegistryKey::new("hp", Version::Latest, FileType::Obo))
+            .unwrap();
+        assert!(!first.was_cached);
+        assert_eq!(first.path, registry_path.join("hp@2024-04-26.obo"));
+        assert_eq!(first.metadata, metadata);
+
+        let second = registry
+            .register_detailed(RegistryKey::new(
+                "hp",
+                Version::from("2024-04-26"),
+                FileType::Obo,
+            ))
+            .unwrap();
+        assert!(second.was_cached);
+        assert_eq!(second.path, first.path);
+        assert_eq!(second.metadata, metadata);
+    }
+
+    #[test]
+    fn test_export_and_import_manifest() {
+        let source_dir = tempdir().unwrap();
+        let source = FileSystemOntologyRegistry::new(
+            source_dir.path().to_path_buf(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new()
+                .with_content("hp", "hp content")
+                .with_content("mondo", "mondo"),
+        )
+        .with_latest_alias(true);
+        source
+            .register(RegistryKey::new(
+                "hp",
+                Version::from("2024-04-26"),
+                FileType::Obo,
+            ))
+            .unwrap();
+        source
+            .register(RegistryKey::new(
+                "mondo",
+                Version::from("2024-02-01"),
+                FileType::Json,
+            ))
+            .unwrap();
+
+        let manifest = source.export_manifest().unwrap();
+        let entries: Vec<ManifestEntry> = serde_json::from_str(&manifest).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                ManifestEntry {
+                    ontology_id: "hp".to_string(),
+                    version: "2024-04-26".to_string(),
+                    file_type: FileType::Obo,
+                    path: "hp@2024-04-26.obo".to_string(),
+                    size: 10,
+                },
+                ManifestEntry {
+                    ontology_id: "mondo".to_string(),
+                    version: "2024-02-01".to_string(),
+                    file_type: FileType::Json,
+                    path: "mondo@2024-02-01.json".to_string(),
+                    size: 5,
+                },
+            ]
+        );
+
+        let target_dir = tempdir().unwrap();
+        let target = FileSystemOntologyRegistry::new(
+            target_dir.path().to_path_buf(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new()
+                .with_content("hp", "hp content")
+                .with_content("mondo", "mondo"),
+        );
+        target.import_manifest(&manifest).unwrap();
+
+        let mut imported = target.list().unwrap();
+        imported.sort();
+        assert_eq!(
+            imported,
+            vec![
+                RegistryKey::new("hp", Version::from("2024-04-26"), FileType::Obo),
+                RegistryKey::new("mondo", Version::from("2024-02-01"), FileType::Json),
+            ]
+        );
+        assert_eq!(target.export_manifest().unwrap(), manifest);
+    }
+
+    #[test]
+    fn test_lock_file_round_trip() {
+        let source_dir = tempdir().unwrap();
+        let source = FileSystemOntologyRegistry::new(
+            source_dir.path().to_path_buf(),
+            MockMetadataProvider::new().with_version("hp", "2024-04-26"),
+            MockOntologyProvider::new()
+                .with_content("hp", "hp content")
+                .with_content("mondo", "mondo"),
+        )
+        .with_latest_alias(true);
+        source
+            .register(RegistryKey::new("hp", Version::Latest, FileType::Obo))
+            .unwrap();
+        source
+            .register(RegistryKey::new(
+                "mondo",
+                Version::from("2024-02-01"),
+                FileType::Json,
+            ))
+            .unwrap();
+
+        let lock_dir = tempdir().unwrap();
+        let lock = lock_dir.path().join("registry.lock");
+        source.write_lock_file(&lock).unwrap();
+
+        let entries: Vec<LockEntry> =
+            serde_json::from_str(&fs::read_to_string(&lock).unwrap()).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].version, "2024-04-26");
+        assert_eq!(
+            entries[0].sha256,
+            sha256_reader(File::open(source_dir.path().join("hp@2024-04-26.obo")).unwrap())
+                .unwrap()
+        );
+
+        // Without metadata, `Latest` could not be resolved, so only the pinned versions work.
+        let target_dir = tempdir().unwrap();
+        let target = FileSystemOntologyRegistry::new(
+            target_dir.path().to_path_buf(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new()
+                .with_content("hp", "hp content")
+                .with_content("mondo", "mondo"),
+        );
+        target.install_from_lock(&lock).unwrap();
+
+        let target_lock = lock_dir.path().join("target.lock");
+        target.write_lock_file(&target_lock).unwrap();
+        assert_eq!(
+            fs::read_to_string(&target_lock).unwrap(),
+            fs::read_to_string(&lock).unwrap()
+        );
+
+        let tampered_dir = tempdir().unwrap();
+        let tampered = FileSystemOntologyRegistry::new(
+            tampered_dir.path().to_path_buf(),
+            MockMetadataProvider::new(),
+            MockOntologyProvider::new()
+                .with_content("hp", "changed upstream")
+                .with_content("mondo", "mondo"),
+        );
+        assert!(matches!(
+            tampered.install_from_lock(&lock),
+            Err(OntologyRegistryError::UnableToRegister { .. })
+        ));
+        assert!(
+            tampered
+                .get(RegistryKey::new(
+                    "hp",
+                    Version::from("2024-04-26"),
+                    FileType::Obo
+                ))
+                .is_none()
+        );
+        assert!(tampered.list().unwrap().is_empty());
+    }
 
+    #[test]
+    fn test_import_manifest_rejects_invalid_json() {
+        let temp_dir = tempdir().unwrap();
         let registry = FileSystemOntologyRegistry::new(
-            registry_path,
+            temp_dir.path().to_path_buf(),
             MockMetadataProvider::new(),
             MockOntologyProvider::new(),
         );
 
-        assert!(target_path.exists());
+        assert!(matches!(
+            registry.import_manifest("not json"),
+            Err(OntologyRegistryError::UnableToRegister { .. })
+        ));
+    }
 
-        let reg_key = RegistryKey::new(
-            "todelete",
-            Version::Declared("2024-05-05".to_string()),
-            FileType::Json,
-        );
+    /// Stores files as `<id>/<version>/<id><ext>`.
+    #[derive(Debug)]
+    struct ReleaseDirectoryNaming;
 
-        registry.unregister(reg_key).unwrap();
+    impl FileNamingStrategy for ReleaseDirectoryNaming {
+        fn file_name(&self, ontology_id: &str, version: &str, file_type: &FileType) -> String {
+            format!(
+                "{ontology_id}/{version}/{ontology_id}{}",
+                file_type.as_file_ending()
+            )
+        }
 
-        assert!(!target_path.exists());
+        fn parse_file_name(&self, file_name: &str) -> Result<RegistryKey, OntologyRegistryError> {
+            let parse_err = || OntologyRegistryError::CantParseRegistryKey {
+                raw_key: file_name.to_string(),
+            };
+            let mut segments = file_name.split('/');
+            let (Some(ontology_id), Some(version), Some(name), None) = (
+                segments.next(),
+                segments.next(),
+                segments.next(),
+                segments.next(),
+            ) else {
+                return Err(parse_err());
+            };
+            let file_ending = name.strip_prefix(ontology_id).ok_or_else(parse_err)?;
+            let file_type = FileType::from_file_ending(file_ending)?;
+            let version = match version {
+                "latest" => Version::Latest,
+                version => Version::from(version),
+            };
+            Ok(RegistryKey::new(ontology_id, version, file_type))
+        }
     }
 
     #[test]
-    fn test_list_files() {
+    fn test_custom_naming_strategy_round_trip() {
         let temp_dir = tempdir().unwrap();
         let registry_path = temp_dir.path().to_path_buf();
 
-        fs::write(registry_path.join("A@1.0.json"), "").unwrap();
-        fs::write(registry_path.join("B@2.0.obo"), "").unwrap();
-        fs::create_dir(registry_path.join("subdir")).unwrap();
+        let registry = FileSystemOntologyRegistry::new(
+            registry_path.clone(),
+            MockMetadataProvider::new().with_version("hp", "2024-04-26"),
+            MockOntologyProvider::new().with_content("hp", "hp content"),
+        )
+        .with_naming_strategy(ReleaseDirectoryNaming);
+
+        let reg_key = RegistryKey::new("hp", Version::Latest, FileType::Obo);
+        registry.register(reg_key.clone()).unwrap();
+
+        let stored_path = registry_path.join("hp").join("2024-04-26").join("hp.obo");
+        assert_eq!(fs::read_to_string(&stored_path).unwrap(), "hp content");
+
+        let mut content = String::new();
+        registry
+            .get(reg_key.clone())
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "hp content");
+
+        assert_eq!(
+            registry.list().unwrap(),
+            vec![RegistryKey::new(
+                "hp",
+                Version::from("2024-04-26"),
+                FileType::Obo
+            )]
+        );
+
+        registry.unregister(reg_key).unwrap();
+        assert!(!stored_path.exists());
+        assert!(!registry_path.join("hp").exists());
+        assert!(registry.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_custom_naming_strategy_latest_alias() {
+        let temp_dir = tempdir().unwrap();
+        let registry_path = temp_dir.path().to_path_buf();
 
         let registry = FileSystemOntologyRegistry::new(
-            registry_path,
+            registry_path.clone(),
             MockMetadataProvider::new(),
-            MockOntologyProvider::new(),
-        );
+            MockOntologyProvider::new().with_content("hp", "hp content"),
+        )
+        .with_naming_strategy(ReleaseDirectoryNaming)
+        .with_latest_alias(true);
 
-        let files = registry.list().unwrap();
+        registry
+            .register(RegistryKey::new(
+                "hp",
+                Version::from("2024-04-26"),
+                FileType::Obo,
+            ))
+            .unwrap();
 
-        assert_eq!(files.len(), 2);
-        assert!(
-            files.iter().any(|f| *f
-                == RegistryKey::new("A", Version::Declared("1.0".to_string()), FileType::Json))
-        );
-        assert!(
-            files.iter().any(|f| *f
-                == RegistryKey::new("B", Version::Declared("2.0".to_string()), FileType::Obo))
+        let alias_path = registry_path.join("hp").join("latest").join("hp.obo");
+        #[cfg(unix)]
+        assert_eq!(
+            fs::read_link(&alias_path).unwrap(),
+            Path::new("../2024-04-26/hp.obo")
         );
+        assert_eq!(fs::read_to_string(&alias_path).unwrap(), "hp content");
+        assert_eq!(registry.list_releases().unwrap().len(), 1);
     }
 
     #[test]
-    fn test_list_files_ignore_hidden_files() {
+    fn test_register_streams_large_body() {
         let temp_dir = tempdir().unwrap();
         let registry_path = temp_dir.path().to_path_buf();
 
-        fs::write(registry_path.join("A@1.0.json"), "").unwrap();
-        fs::write(registry_path.join(".DS_Store"), "").unwrap();
+        let large_body = "a".repeat(8 * 1024 * 1024);
+        let ontology_mock = MockOntologyProvider::new().with_content("large", &large_body);
 
         let registry = FileSystemOntologyRegistry::new(
-            registry_path,
+            registry_path.clone(),
             MockMetadataProvider::new(),
-            MockOntologyProvider::new(),
+            ontology_mock,
         );
 
-        let files = registry.list().unwrap();
+        let reg_key = RegistryKey::new("large", Version::from("1.0"), FileType::Owl);
+        registry.register(reg_key).unwrap();
 
-        assert_eq!(files.len(), 1);
-        assert!(
-            files.iter().any(|f| *f
-                == RegistryKey::new("A", Version::Declared("1.0".to_string()), FileType::Json))
-        );
+        let stored = fs::metadata(registry_path.join("large@1.0.owl")).unwrap();
+        assert_eq!(stored.len(), large_body.len() as u64);
+
+        let leftover_dirs = fs::read_dir(&registry_path)
+            .unwrap()
+            .flatten()
+            .filter(|e| e.path().is_dir())
+            .count();
+        assert_eq!(leftover_dirs, 0);
     }
 
     #[test]
@@ -0,0 +1,268 @@
+use crate::blocking::file_system_ontology_registry::FileSystemOntologyRegistry;
+#[cfg(feature = "http")]
+use crate::blocking::url_ontology_provider::UrlOntologyProvider;
+use crate::enums::{FileType, RegistryLayout};
+use crate::error::OntologyRegistryError;
+use crate::traits::{
+    FileNamingStrategy, OntologyMetadataProviding, OntologyProviding, RegistryObserver,
+};
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+
+/// Collects the options of a [`FileSystemOntologyRegistry`] before creating it.
+///
+/// Every setter forwards to the `with_*` method of the same name on the registry, which
+/// documents the option in full. [`Self::build`] checks the registry path like
+/// [`FileSystemOntologyRegistry::try_new`].
+///
+/// ```rust,no_run
+/// use ontology_registry::blocking::file_system_ontology_registry::FileSystemOntologyRegistry;
+/// use ontology_registry::{LocalDirectoryProvider, RegistryLayout, StaticMetadataProvider};
+/// use std::path::PathBuf;
+///
+/// let registry = FileSystemOntologyRegistry::builder(
+///     PathBuf::from("/tmp/ontologies"),
+///     StaticMetadataProvider::default(),
+///     LocalDirectoryProvider::new("/mnt/mirror"),
+/// )
+/// .with_layout(RegistryLayout::Nested)
+/// .with_max_bytes(10 * 1024 * 1024 * 1024)
+/// .with_offline(true)
+/// .build()
+/// .unwrap();
+/// ```
+#[derive(Debug)]
+pub struct FileSystemOntologyRegistryBuilder<MDP, OP> {
+    registry: FileSystemOntologyRegistry<MDP, OP>,
+}
+
+impl<MDP: OntologyMetadataProviding, OP: OntologyProviding>
+    FileSystemOntologyRegistryBuilder<MDP, OP>
+{
+    pub fn new(registry_path: PathBuf, metadata_provider: MDP, ontology_provider: OP) -> Self {
+        FileSystemOntologyRegistryBuilder {
+            registry: FileSystemOntologyRegistry::new(
+                registry_path,
+                metadata_provider,
+                ontology_provider,
+            ),
+        }
+    }
+
+    /// See [`FileSystemOntologyRegistry::with_max_file_name_length`].
+    pub fn with_max_file_name_length(self, max_file_name_length: usize) -> Self {
+        self.map(|registry| registry.with_max_file_name_length(max_file_name_length))
+    }
+
+    /// See [`FileSystemOntologyRegistry::with_max_bytes`].
+    pub fn with_max_bytes(self, max_bytes: u64) -> Self {
+        self.map(|registry| registry.with_max_bytes(max_bytes))
+    }
+
+    /// See [`FileSystemOntologyRegistry::with_refresh_after`].
+    pub fn with_refresh_after(self, refresh_after: Duration) -> Self {
+        self.map(|registry| registry.with_refresh_after(refresh_after))
+    }
+
+    /// See [`FileSystemOntologyRegistry::with_latest_alias`].
+    pub fn with_latest_alias(self, latest_alias: bool) -> Self {
+        self.map(|registry| registry.with_latest_alias(latest_alias))
+    }
+
+    /// See [`FileSystemOntologyRegistry::with_file_lock`].
+    pub fn with_file_lock(self, file_lock: bool) -> Self {
+        self.map(|registry| registry.with_file_lock(file_lock))
+    }
+
+    /// See [`FileSystemOntologyRegistry::with_offline`].
+    pub fn with_offline(self, offline: bool) -> Self {
+        self.map(|registry| registry.with_offline(offline))
+    }
+
+    /// See [`FileSystemOntologyRegistry::with_read_paths`].
+    pub fn with_read_paths(self, read_paths: Vec<PathBuf>) -> Self {
+        self.map(|registry| registry.with_read_paths(read_paths))
+    }
+
+    /// See [`FileSystemOntologyRegistry::with_lowercase_ids`].
+    pub fn with_lowercase_ids(self, lowercase_ids: bool) -> Self {
+        self.map(|registry| registry.with_lowercase_ids(lowercase_ids))
+    }
+
+    /// See [`FileSystemOntologyRegistry::with_id_normalizer`].
+    pub fn with_id_normalizer(self, normalizer: fn(&str) -> String) -> Self {
+        self.map(|registry| registry.with_id_normalizer(normalizer))
+    }
+
+    /// See [`FileSystemOntologyRegistry::with_follow_replaced_by`].
+    pub fn with_follow_replaced_by(self, follow_replaced_by: bool) -> Self {
+        self.map(|registry| registry.with_follow_replaced_by(follow_replaced_by))
+    }
+
+    /// See [`FileSystemOntologyRegistry::with_provider_latest`].
+    pub fn with_provider_latest(self, provider_latest: bool) -> Self {
+        self.map(|registry| registry.with_provider_latest(provider_latest))
+    }
+
+    /// See [`FileSystemOntologyRegistry::with_url_provider`].
+    #[cfg(feature = "http")]
+    pub fn with_url_provider(self, url_provider: UrlOntologyProvider) -> Self {
+        self.map(|registry| registry.with_url_provider(url_provider))
+    }
+
+    /// See [`FileSystemOntologyRegistry::with_cancel_flag`].
+    pub fn with_cancel_flag(self, cancel_flag: Arc<AtomicBool>) -> Self {
+        self.map(|registry| registry.with_cancel_flag(cancel_flag))
+    }
+
+    /// See [`FileSystemOntologyRegistry::with_validator`].
+    pub fn with_validator(
+        self,
+        validator: impl Fn(&FileType, &mut dyn Read) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        self.map(|registry| registry.with_validator(validator))
+    }
+
+    /// See [`FileSystemOntologyRegistry::with_compressed_storage`].
+    #[cfg(feature = "gzip")]
+    pub fn with_compressed_storage(self, compressed_storage: bool) -> Self {
+        self.map(|registry| registry.with_compressed_storage(compressed_storage))
+    }
+
+    /// See [`FileSystemOntologyRegistry::with_deduplication`].
+    pub fn with_deduplication(self, deduplicate: bool) -> Self {
+        self.map(|registry| registry.with_deduplication(deduplicate))
+    }
+
+    /// See [`FileSystemOntologyRegistry::with_observer`].
+    pub fn with_observer(self, observer: impl RegistryObserver + 'static) -> Self {
+        self.map(|registry| registry.with_observer(observer))
+    }
+
+    /// See [`FileSystemOntologyRegistry::with_layout`].
+    pub fn with_layout(self, layout: RegistryLayout) -> Self {
+        self.map(|registry| registry.with_layout(layout))
+    }
+
+    /// See [`FileSystemOntologyRegistry::with_naming_strategy`].
+    pub fn with_naming_strategy(self, naming_strategy: impl FileNamingStrategy + 'static) -> Self {
+        self.map(|registry| registry.with_naming_strategy(naming_strategy))
+    }
+
+    /// Creates the registry.
+    ///
+    /// # Errors
+    /// Returns `OntologyRegistryError::NotADirectory` if the registry path is an existing file,
+    /// and `Io` if it cannot be inspected.
+    pub fn build(self) -> Result<FileSystemOntologyRegistry<MDP, OP>, OntologyRegistryError> {
+        FileSystemOntologyRegistry::<MDP, OP>::check_registry_path(self.registry.registry_path())?;
+        Ok(self.registry)
+    }
+
+    fn map(
+        self,
+        configure: impl FnOnce(
+            FileSystemOntologyRegistry<MDP, OP>,
+        ) -> FileSystemOntologyRegistry<MDP, OP>,
+    ) -> Self {
+        FileSystemOntologyRegistryBuilder {
+            registry: configure(self.registry),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocking::local_directory_provider::LocalDirectoryProvider;
+    use crate::blocking::static_metadata_provider::StaticMetadataProvider;
+    use crate::enums::Version;
+    use crate::ontology_metadata::OntologyMetadata;
+    use crate::registry_key::RegistryKey;
+    use crate::traits::OntologyRegistration;
+    use std::collections::HashMap;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn metadata_provider() -> StaticMetadataProvider {
+        StaticMetadataProvider::new(HashMap::from([(
+            "hp".to_string(),
+            OntologyMetadata {
+                ontology_id: "hp".to_string(),
+                version: "2024-04-26".to_string(),
+                ..Default::default()
+            },
+        )]))
+    }
+
+    #[test]
+    fn test_build_applies_options() {
+        let mirror_dir = tempdir().unwrap();
+        fs::create_dir(mirror_dir.path().join("hp")).unwrap();
+        fs::write(mirror_dir.path().join("hp").join("hp.obo"), "hp").unwrap();
+        let registry_dir = tempdir().unwrap();
+
+        let registry = FileSystemOntologyRegistry::builder(
+            registry_dir.path().to_path_buf(),
+            metadata_provider(),
+            LocalDirectoryProvider::new(mirror_dir.path()),
+        )
+        .with_layout(RegistryLayout::Nested)
+        .with_lowercase_ids(true)
+        .build()
+        .unwrap();
+
+        registry
+            .register(RegistryKey::new("HP", Version::Latest, FileType::Obo))
+            .unwrap();
+
+        assert!(
+            registry_dir
+                .path()
+                .join("hp")
+                .join("2024-04-26.obo")
+                .exists()
+        );
+    }
+
+    #[test]
+    fn test_build_offline_registry_does_not_download() {
+        let registry_dir = tempdir().unwrap();
+
+        let registry = FileSystemOntologyRegistryBuilder::new(
+            registry_dir.path().to_path_buf(),
+            metadata_provider(),
+            LocalDirectoryProvider::new(registry_dir.path()),
+        )
+        .with_offline(true)
+        .with_max_bytes(1024)
+        .build()
+        .unwrap();
+
+        let result = registry.register(RegistryKey::new("hp", Version::Latest, FileType::Obo));
+
+        assert!(matches!(result, Err(OntologyRegistryError::Offline { .. })));
+    }
+
+    #[test]
+    fn test_build_rejects_file_as_registry_path() {
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("registry");
+        fs::write(&file_path, "").unwrap();
+
+        let result = FileSystemOntologyRegistry::builder(
+            file_path,
+            metadata_provider(),
+            LocalDirectoryProvider::new(temp_dir.path()),
+        )
+        .build();
+
+        assert!(matches!(
+            result,
+            Err(OntologyRegistryError::NotADirectory { .. })
+        ));
+    }
+}
@@ -0,0 +1,462 @@
+use crate::blocking::provider_client::ProviderClient;
+use crate::blocking::retry_policy::RetryPolicy;
+use crate::blocking::{DEFAULT_USER_AGENT, redirect_error, unfollowed_redirect};
+use crate::error::OntologyRegistryError;
+use crate::traits::OntologyProviding;
+use crate::{FileType, Version};
+use reqwest::StatusCode;
+use reqwest::Url;
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::header::{ACCEPT, AUTHORIZATION, USER_AGENT};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+use std::time::Duration;
+
+#[derive(Debug, Deserialize)]
+struct GitHubRelease {
+    tag_name: String,
+    #[serde(default)]
+    assets: Vec<GitHubAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubAsset {
+    name: String,
+    url: String,
+}
+
+/// Downloads ontology files attached as assets to the releases of a GitHub repository.
+///
+/// Every ontology id must be mapped to its `owner/repo` with [`Self::with_repository`]. A
+/// declared version is looked up as the release tagged `<tag_prefix><version>` (`v2024-04-26`
+/// by default), `Version::Latest` and `Version::Unversioned` as the repository's latest
+/// release. The asset named like the requested file is downloaded; if there is none, the only
+/// asset of the requested file type is used instead.
+///
+/// Unauthenticated requests to the GitHub API are heavily rate limited, so configure a token
+/// with [`Self::with_token`] for anything but occasional use.
+#[derive(Clone)]
+pub struct GitHubReleaseProvider {
+    api_url: String,
+    repositories: HashMap<String, String>,
+    tag_prefix: String,
+    token: Option<String>,
+    client: ProviderClient,
+    retry_policy: RetryPolicy,
+    user_agent: String,
+    timeout: Option<Duration>,
+}
+
+impl Default for GitHubReleaseProvider {
+    fn default() -> Self {
+        GitHubReleaseProvider::new("https://api.github.com")
+    }
+}
+
+impl GitHubReleaseProvider {
+    pub fn new(api_url: &str) -> Self {
+        GitHubReleaseProvider {
+            api_url: api_url.trim_end_matches('/').to_string(),
+            repositories: HashMap::new(),
+            tag_prefix: "v".to_string(),
+            token: None,
+            client: ProviderClient::default(),
+            retry_policy: RetryPolicy::default(),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            timeout: None,
+        }
+    }
+
+    /// Fetches `ontology_id` from the releases of `repository`, given as `owner/repo`.
+    ///
+    /// Ontology ids are matched case-insensitively.
+    pub fn with_repository(
+        mut self,
+        ontology_id: impl AsRef<str>,
+        repository: impl Into<String>,
+    ) -> Self {
+        self.repositories
+            .insert(ontology_id.as_ref().to_ascii_lowercase(), repository.into());
+        self
+    }
+
+    /// Sets the prefix put in front of a declared version to form the release tag. Defaults
+    /// to `v`.
+    pub fn with_tag_prefix(mut self, tag_prefix: impl Into<String>) -> Self {
+        self.tag_prefix = tag_prefix.into();
+        self
+    }
+
+    /// Authenticates every request with `token`, sent as `Authorization: Bearer <token>`.
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Uses `client` for all requests, regardless of the redirect settings; see
+    /// [`ProviderClient`].
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = self.client.with_client(client);
+        self
+    }
+
+    /// Follows at most `limit` redirects per request instead of reqwest's default of 10. A
+    /// limit of `0` disables following redirects.
+    ///
+    /// Exceeding the limit fails with `OntologyRegistryError::ProvidingOntology`; with
+    /// redirects disabled, so does any redirect, naming its `Location`. Not applied to a client
+    /// passed to [`Self::with_client`]; see [`ProviderClient`].
+    pub fn with_redirect_policy(mut self, limit: usize) -> Self {
+        self.client = self.client.with_redirect_limit(limit);
+        self
+    }
+
+    /// Sets the User-Agent header sent with every request. Defaults to `ontology-registry/<version>`.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Aborts a request that has not completed within `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Retries failed requests up to `max_retries` times with exponential backoff starting at `base_delay`.
+    pub fn with_retries(mut self, max_retries: u32, base_delay: Duration) -> Self {
+        self.retry_policy = self.retry_policy.with_backoff(max_retries, base_delay);
+        self
+    }
+
+    /// Retries a request answered with `429 Too Many Requests` up to `max_retries` times after
+    /// its `Retry-After` delay. Disabled by default; see [`RetryPolicy`].
+    pub fn with_rate_limit_retries(mut self, max_retries: u32) -> Self {
+        self.retry_policy = self.retry_policy.with_rate_limit_retries(max_retries);
+        self
+    }
+
+    /// Fails instead of waiting when a `Retry-After` delay exceeds `max_retry_after`. Defaults
+    /// to 60 seconds; see [`RetryPolicy`].
+    pub fn with_max_retry_after(mut self, max_retry_after: Duration) -> Self {
+        self.retry_policy = self.retry_policy.with_max_retry_after(max_retry_after);
+        self
+    }
+
+    fn request(&self, url: &str, accept: &str) -> RequestBuilder {
+        let request = self
+            .client
+            .get(url)
+            .header(USER_AGENT, &self.user_agent)
+            .header(ACCEPT, accept);
+        let request = match &self.token {
+            Some(token) => request.header(AUTHORIZATION, format!("Bearer {token}")),
+            None => request,
+        };
+        match self.timeout {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        }
+    }
+
+    /// Sends a GET request for `url`, mapping a 404 to `NotFound` and other failures to errors.
+    fn get(
+        &self,
+        url: &str,
+        accept: &str,
+        ontology_id: &str,
+        version: &Version,
+    ) -> Result<Response, OntologyRegistryError> {
+        let response = match self.retry_policy.send(|| self.request(url, accept))? {
+            Ok(response) => response,
+            Err(err) if err.is_timeout() => {
+                return Err(OntologyRegistryError::ProvidingOntology {
+                    reason: format!("Request timed out for {url}"),
+                });
+            }
+            Err(err) => {
+                return Err(redirect_error(&err, url, self.client.redirect_limit())
+                    .unwrap_or_else(|| err.into()));
+            }
+        };
+        if let Some(err) = unfollowed_redirect(&response, url) {
+            return Err(err);
+        }
+
+        match response.status() {
+            status if status.is_success() => Ok(response),
+            StatusCode::NOT_FOUND => Err(OntologyRegistryError::NotFound {
+                ontology_id: ontology_id.to_string(),
+                version: version.to_string(),
+            }),
+            status => Err(OntologyRegistryError::HttpError {
+                status: status.as_u16(),
+                url: url.to_string(),
+            }),
+        }
+    }
+
+    /// Builds the API URL of the release `version` refers to in `repository`.
+    fn release_url(&self, repository: &str, version: &Version) -> String {
+        let tag = match version {
+            Version::Latest | Version::Unversioned => None,
+            Version::Declared(v) => Some(format!("{}{}", self.tag_prefix, v)),
+        };
+        let Ok(mut url) = Url::parse(&self.api_url) else {
+            return match tag {
+                Some(tag) => format!("{}/repos/{repository}/releases/tags/{tag}", self.api_url),
+                None => format!("{}/repos/{repository}/releases/latest", self.api_url),
+            };
+        };
+        if let Ok(mut path) = url.path_segments_mut() {
+            path.pop_if_empty()
+                .push("repos")
+                .extend(repository.split('/'));
+            match &tag {
+                Some(tag) => path.extend(["releases", "tags", tag.as_str()]),
+                None => path.extend(["releases", "latest"]),
+            };
+        }
+        url.into()
+    }
+
+    /// Picks the asset named `file_name`, or else the only asset of its file type.
+    fn find_asset<'a>(release: &'a GitHubRelease, file_name: &str) -> Option<&'a GitHubAsset> {
+        if let Some(asset) = release.assets.iter().find(|asset| asset.name == file_name) {
+            return Some(asset);
+        }
+
+        let file_type = FileType::ALL
+            .iter()
+            .find(|ft| file_name.ends_with(ft.as_file_ending()))?;
+        let mut candidates = release
+            .assets
+            .iter()
+            .filter(|asset| asset.name.ends_with(file_type.as_file_ending()));
+        match (candidates.next(), candidates.next()) {
+            (Some(asset), None) => Some(asset),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Debug for GitHubReleaseProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GitHubReleaseProvider")
+            .field("api_url", &self.api_url)
+            .field("repositories", &self.repositories)
+            .field("tag_prefix", &self.tag_prefix)
+            .field("token", &self.token.as_ref().map(|_| "<redacted>"))
+            .field("retry_policy", &self.retry_policy)
+            .field("user_agent", &self.user_agent)
+            .field("timeout", &self.timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+impl OntologyProviding for GitHubReleaseProvider {
+    fn provide_ontology(
+        &self,
+        ontology_id: &str,
+        file_name: &str,
+        version: &Version,
+    ) -> Result<impl Read, OntologyRegistryError> {
+        let repository = self
+            .repositories
+            .get(&ontology_id.to_ascii_lowercase())
+            .ok_or_else(|| OntologyRegistryError::ProvidingOntology {
+                reason: format!("No GitHub repository configured for {ontology_id}"),
+            })?;
+
+        let release_url = self.release_url(repository, version);
+        let release: GitHubRelease = self
+            .get(
+                &release_url,
+                "application/vnd.github+json",
+                ontology_id,
+                version,
+            )?
+            .json()
+            .map_err(|err| OntologyRegistryError::ProvidingOntology {
+                reason: format!("Unable to parse release {release_url}: {err}"),
+            })?;
+
+        let asset = Self::find_asset(&release, file_name).ok_or_else(|| {
+            OntologyRegistryError::NotFound {
+                ontology_id: ontology_id.to_string(),
+                version: release.tag_name.clone(),
+            }
+        })?;
+
+        self.get(&asset.url, "application/octet-stream", ontology_id, version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Matcher, Server, ServerGuard};
+
+    fn release_body(server: &ServerGuard, tag_name: &str, asset_names: &[&str]) -> String {
+        let assets: Vec<String> = asset_names
+            .iter()
+            .enumerate()
+            .map(|(id, name)| {
+                format!(
+                    r#"{{"name": "{name}", "url": "{}/repos/obophenotype/hpo/releases/assets/{id}"}}"#,
+                    server.url()
+                )
+            })
+            .collect();
+        format!(
+            r#"{{"tag_name": "{tag_name}", "assets": [{}]}}"#,
+            assets.join(", ")
+        )
+    }
+
+    #[test]
+    fn test_provide_ontology_downloads_matching_asset() {
+        let mut server = Server::new();
+        let release = server
+            .mock("GET", "/repos/obophenotype/hpo/releases/tags/v2024-04-26")
+            .match_header("accept", "application/vnd.github+json")
+            .with_status(200)
+            .with_body(release_body(
+                &server,
+                "v2024-04-26",
+                &["hp.json", "hp.obo", "hp-base.obo"],
+            ))
+            .create();
+        let asset = server
+            .mock("GET", "/repos/obophenotype/hpo/releases/assets/1")
+            .match_header("accept", "application/octet-stream")
+            .with_status(200)
+            .with_body("format-version: 1.2")
+            .create();
+
+        let provider =
+            GitHubReleaseProvider::new(&server.url()).with_repository("HP", "obophenotype/hpo");
+        let mut content = String::new();
+        provider
+            .provide_ontology("hp", "hp.obo", &Version::from("2024-04-26"))
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+
+        release.assert();
+        asset.assert();
+        assert_eq!(content, "format-version: 1.2");
+    }
+
+    #[test]
+    fn test_redirect_policy_disabled() {
+        let mut server = Server::new();
+        let _release = server
+            .mock("GET", "/repos/obophenotype/hpo/releases/tags/v2024-04-26")
+            .with_status(200)
+            .with_body(release_body(&server, "v2024-04-26", &["hp.obo"]))
+            .create();
+        let _asset = server
+            .mock("GET", "/repos/obophenotype/hpo/releases/assets/0")
+            .with_status(302)
+            .with_header("location", "/storage/hp.obo")
+            .create();
+
+        let provider = GitHubReleaseProvider::new(&server.url())
+            .with_repository("HP", "obophenotype/hpo")
+            .with_redirect_policy(0);
+        let version = Version::from("2024-04-26");
+        let result = provider.provide_ontology("hp", "hp.obo", &version);
+
+        match result {
+            Err(OntologyRegistryError::ProvidingOntology { reason }) => {
+                assert!(reason.contains("/storage/hp.obo"), "{reason}");
+            }
+            _ => panic!("Expected ProvidingOntology"),
+        }
+    }
+
+    #[test]
+    fn test_provide_ontology_latest_falls_back_to_file_type() {
+        let mut server = Server::new();
+        let release = server
+            .mock("GET", "/repos/obophenotype/hpo/releases/latest")
+            .with_status(200)
+            .with_body(release_body(
+                &server,
+                "v2025-01-01",
+                &["hpo.owl", "README.md"],
+            ))
+            .create();
+        let asset = server
+            .mock("GET", "/repos/obophenotype/hpo/releases/assets/0")
+            .match_header("authorization", "Bearer secret")
+            .with_status(200)
+            .with_body("<rdf/>")
+            .create();
+
+        let provider = GitHubReleaseProvider::new(&server.url())
+            .with_repository("hp", "obophenotype/hpo")
+            .with_token("secret");
+        let mut content = String::new();
+        provider
+            .provide_ontology("hp", "hp.owl", &Version::Latest)
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+
+        release.assert();
+        asset.assert();
+        assert_eq!(content, "<rdf/>");
+    }
+
+    #[test]
+    fn test_provide_ontology_maps_missing_release_and_asset_to_not_found() {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/repos/obophenotype/hpo/releases/tags/v1999-01-01")
+            .with_status(404)
+            .create();
+        server
+            .mock("GET", "/repos/obophenotype/hpo/releases/tags/v2024-04-26")
+            .with_status(200)
+            .with_body(release_body(&server, "v2024-04-26", &["hp.obo"]))
+            .create();
+        let download = server
+            .mock("GET", Matcher::Regex("/assets/".to_string()))
+            .expect(0)
+            .create();
+
+        let provider =
+            GitHubReleaseProvider::new(&server.url()).with_repository("hp", "obophenotype/hpo");
+
+        assert!(matches!(
+            provider.provide_ontology("hp", "hp.obo", &Version::from("1999-01-01")),
+            Err(OntologyRegistryError::NotFound { .. })
+        ));
+        assert!(matches!(
+            provider.provide_ontology("hp", "hp.json", &Version::from("2024-04-26")),
+            Err(OntologyRegistryError::NotFound { .. })
+        ));
+        download.assert();
+    }
+
+    #[test]
+    fn test_provide_ontology_requires_repository() {
+        let provider = GitHubReleaseProvider::default();
+
+        assert!(matches!(
+            provider.provide_ontology("mondo", "mondo.obo", &Version::Latest),
+            Err(OntologyRegistryError::ProvidingOntology { .. })
+        ));
+    }
+
+    #[test]
+    fn test_debug_redacts_token() {
+        let provider = GitHubReleaseProvider::default().with_token("secret");
+
+        assert!(!format!("{provider:?}").contains("secret"));
+    }
+}
@@ -0,0 +1,131 @@
+use crate::Version;
+use crate::error::OntologyRegistryError;
+use crate::traits::OntologyProviding;
+use std::fs::File;
+use std::io::{ErrorKind, Read};
+use std::path::{Path, PathBuf};
+
+/// Path pattern used unless configured otherwise, relative to the base directory.
+pub const DEFAULT_PATH_PATTERN: &str = "{ontology_id}/{file_name}";
+
+/// Provides ontology files from a local directory, e.g. a mirror on a network share.
+///
+/// Files are located by expanding a path pattern relative to `base_dir`. The placeholders
+/// `{ontology_id}`, `{version}` and `{file_name}` are replaced by the values passed to
+/// `provide_ontology`. The default pattern is [`DEFAULT_PATH_PATTERN`].
+///
+/// Together with a local metadata source this allows a `FileSystemOntologyRegistry` to be
+/// populated without network access.
+#[derive(Clone, Debug)]
+pub struct LocalDirectoryProvider {
+    base_dir: PathBuf,
+    path_pattern: String,
+}
+
+impl LocalDirectoryProvider {
+    pub fn new(base_dir: impl Into<PathBuf>) -> Self {
+        LocalDirectoryProvider {
+            base_dir: base_dir.into(),
+            path_pattern: DEFAULT_PATH_PATTERN.to_string(),
+        }
+    }
+
+    /// Sets the pattern used to locate files below the base directory,
+    /// e.g. `{ontology_id}/releases/{version}/{file_name}`.
+    pub fn with_path_pattern(mut self, path_pattern: impl Into<String>) -> Self {
+        self.path_pattern = path_pattern.into();
+        self
+    }
+
+    pub fn base_dir(&self) -> &Path {
+        &self.base_dir
+    }
+
+    fn file_path(&self, ontology_id: &str, file_name: &str, version: &Version) -> PathBuf {
+        let relative_path = self
+            .path_pattern
+            .replace("{ontology_id}", ontology_id)
+            .replace("{version}", &version.to_string())
+            .replace("{file_name}", file_name);
+        self.base_dir.join(relative_path)
+    }
+}
+
+impl OntologyProviding for LocalDirectoryProvider {
+    fn provide_ontology(
+        &self,
+        ontology_id: &str,
+        file_name: &str,
+        version: &Version,
+    ) -> Result<impl Read, OntologyRegistryError> {
+        let path = self.file_path(ontology_id, file_name, version);
+
+        File::open(&path).map_err(|err| match err.kind() {
+            ErrorKind::NotFound => OntologyRegistryError::NotFound {
+                ontology_id: ontology_id.to_string(),
+                version: version.to_string(),
+            },
+            _ => OntologyRegistryError::ProvidingOntology {
+                reason: format!("Unable to open '{}': {}", path.display(), err),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_provide_ontology_from_default_pattern() {
+        let source = tempdir().unwrap();
+        fs::create_dir(source.path().join("hp")).unwrap();
+        fs::write(
+            source.path().join("hp").join("hp.obo"),
+            "format-version: 1.2",
+        )
+        .unwrap();
+
+        let provider = LocalDirectoryProvider::new(source.path());
+        let mut content = String::new();
+        provider
+            .provide_ontology("hp", "hp.obo", &Version::from("2024-04-26"))
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+
+        assert_eq!(content, "format-version: 1.2");
+    }
+
+    #[test]
+    fn test_provide_ontology_from_custom_pattern() {
+        let source = tempdir().unwrap();
+        let release_dir = source.path().join("hp").join("2024-04-26");
+        fs::create_dir_all(&release_dir).unwrap();
+        fs::write(release_dir.join("hp.json"), "{}").unwrap();
+
+        let provider = LocalDirectoryProvider::new(source.path())
+            .with_path_pattern("{ontology_id}/{version}/{file_name}");
+
+        assert!(
+            provider
+                .provide_ontology("hp", "hp.json", &Version::from("2024-04-26"))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_provide_ontology_missing_file() {
+        let source = tempdir().unwrap();
+        let provider = LocalDirectoryProvider::new(source.path());
+
+        let result = provider.provide_ontology("hp", "hp.obo", &Version::Latest);
+
+        assert!(matches!(
+            result,
+            Err(OntologyRegistryError::NotFound { .. })
+        ));
+    }
+}
@@ -11,20 +11,59 @@
 //!   versions and metadata. It maps `Version::Latest` to the most recent release
 //!   date available in the registry.
 //!
+//! * **[`bioportal_metadata_provider`]:**
+//!   Resolves metadata from the latest submission of an ontology on
+//!   [BioPortal](https://bioportal.bioontology.org). Requires a BioPortal API key.
+//!
+//! * **[`caching_metadata_provider`]:**
+//!   Wraps any metadata provider and memoizes its results per ontology id for a
+//!   configurable TTL, so resolving `Version::Latest` does not hit the network every time.
+//!
+//! * **[`decompressing_provider`]:**
+//!   Wraps any ontology provider and transparently inflates gzip, zstd or bzip2 compressed
+//!   downloads.
+//!
+//! * **[`fallback_provider`]:**
+//!   Chains several ontology or metadata providers and returns the first successful result,
+//!   reporting every underlying failure if none succeeds.
+//!
+//! * **[`github_release_provider`]:**
+//!   Downloads ontology files attached to the GitHub releases of the repository an
+//!   ontology is published in.
+//!
+//! * **[`local_directory_provider`]:**
+//!   Reads ontology files from a local directory, e.g. an offline mirror on a network share.
+//!
+//! * **[`obolib_metadata_provider`]:**
+//!   Resolves metadata from the [OBO Foundry](https://obofoundry.org) registry
+//!   (`ontologies.jsonld`). Download locations come from each ontology's products.
+//!
 //! * **[`obolib_ontology_provider`]:**
 //!   Downloads ontology files directly from the [OBO Library](https://obolibrary.org).
 //!   It constructs URLs based on the OBO library's standard release structure
 //!   (e.g., `.../obo/mondo/releases/2024-01-01/mondo.owl`).
 //!
+//! * **[`static_metadata_provider`]:**
+//!   Serves metadata from a fixed map or a local JSON file, e.g. for tests and offline runs.
+//!
+//! * **[`url_ontology_provider`]:**
+//!   Downloads ontology files from complete URLs, e.g. download locations from metadata.
+//!   Available with the `http` feature.
+//!
 //! * **[`file_system_ontology_registry`]:**
 //!   The main coordinator. It persists downloaded ontologies to a local directory.
 //!   It includes robust handling for:
 //!     * **Concurrency:** Uses `Mutex` locks to prevent race conditions when multiple threads try to download the same ontology.
 //!     * **Atomic Writes:** Downloads to temporary files (`.tmp`) and renames them only upon successful completion to ensure data integrity.
 //!
+//! * **[`file_system_ontology_registry_builder`]:**
+//!   Collects the registry's options in one place before creating it.
+//!
 //! ## Example Configuration
 //!
 //! ```rust,no_run
+//! # #[cfg(all(feature = "bioregistry", feature = "obolibrary"))]
+//! # {
 //! use ontology_registry::blocking::bio_registry_metadata_provider::BioRegistryMetadataProvider;
 //! use ontology_registry::blocking::obolib_ontology_provider::OboLibraryProvider;
 //! use ontology_registry::blocking::file_system_ontology_registry::FileSystemOntologyRegistry;
@@ -35,8 +74,121 @@
 //!     BioRegistryMetadataProvider::default(),
 //!     OboLibraryProvider::default(),
 //! );
+//! # }
 //! ```
 
+#[cfg(feature = "bioregistry")]
 pub mod bio_registry_metadata_provider;
+#[cfg(feature = "bioportal")]
+pub mod bioportal_metadata_provider;
+pub mod caching_metadata_provider;
+pub(crate) mod cancellable_reader;
+#[cfg(any(feature = "bioregistry", feature = "obolibrary"))]
+pub(crate) mod credentials;
+pub mod decompressing_provider;
+pub mod fallback_provider;
 pub mod file_system_ontology_registry;
+pub mod file_system_ontology_registry_builder;
+#[cfg(feature = "github")]
+pub mod github_release_provider;
+pub mod local_directory_provider;
+#[cfg(feature = "obolibrary")]
+pub mod obolib_metadata_provider;
+#[cfg(feature = "obolibrary")]
 pub mod obolib_ontology_provider;
+pub(crate) mod progress_reader;
+#[cfg(feature = "http")]
+pub mod provider_client;
+pub(crate) mod registry_manifest;
+#[cfg(feature = "http")]
+pub mod retry_policy;
+#[cfg(feature = "obolibrary")]
+pub(crate) mod size_limited_reader;
+pub mod static_metadata_provider;
+#[cfg(feature = "http")]
+pub mod url_ontology_provider;
+
+/// User-Agent sent by the HTTP providers unless configured otherwise.
+#[cfg(feature = "http")]
+pub(crate) const DEFAULT_USER_AGENT: &str =
+    concat!("ontology-registry/", env!("CARGO_PKG_VERSION"));
+
+/// Parses `proxy_url` into a proxy for [`http_client`].
+///
+/// Credentials embedded in `proxy_url` are redacted in the error.
+#[cfg(any(feature = "bioregistry", feature = "obolibrary"))]
+pub(crate) fn parse_proxy(
+    proxy_url: &str,
+) -> Result<reqwest::Proxy, crate::error::OntologyRegistryError> {
+    reqwest::Proxy::all(proxy_url).map_err(|err| {
+        let redacted_url = match (proxy_url.split_once("://"), proxy_url.rsplit_once('@')) {
+            (Some((scheme, _)), Some((_, host))) => format!("{scheme}://<redacted>@{host}"),
+            (None, Some((_, host))) => format!("<redacted>@{host}"),
+            _ => proxy_url.to_string(),
+        };
+        crate::error::OntologyRegistryError::InvalidProxy {
+            proxy_url: redacted_url,
+            reason: err.to_string(),
+        }
+    })
+}
+
+/// Builds an HTTP client that sends every request through `proxy`, if set, and follows at most
+/// `redirect_limit` redirects instead of reqwest's default of 10. A limit of 0 disables
+/// following redirects.
+///
+/// # Panics
+/// Like `reqwest::blocking::Client::new`, if the TLS backend cannot be initialized.
+#[cfg(feature = "http")]
+pub(crate) fn http_client(
+    proxy: Option<reqwest::Proxy>,
+    redirect_limit: Option<usize>,
+) -> reqwest::blocking::Client {
+    let builder = reqwest::blocking::Client::builder();
+    let builder = match proxy {
+        Some(proxy) => builder.proxy(proxy),
+        None => builder,
+    };
+    let builder = match redirect_limit {
+        Some(0) => builder.redirect(reqwest::redirect::Policy::none()),
+        Some(limit) => builder.redirect(reqwest::redirect::Policy::limited(limit)),
+        None => builder,
+    };
+    builder.build().expect("TLS backend cannot be initialized")
+}
+
+/// Describes a request for `url` that failed because it was redirected more often than
+/// `redirect_limit` allows, or returns `None` for any other error. The limit is left out of
+/// the description if it is unknown.
+#[cfg(feature = "http")]
+pub(crate) fn redirect_error(
+    err: &reqwest::Error,
+    url: &str,
+    redirect_limit: Option<usize>,
+) -> Option<crate::error::OntologyRegistryError> {
+    err.is_redirect()
+        .then(|| crate::error::OntologyRegistryError::ProvidingOntology {
+            reason: match redirect_limit {
+                Some(limit) => format!("Too many redirects for {url}, the limit is {limit}"),
+                None => format!("Too many redirects for {url}"),
+            },
+        })
+}
+
+/// Describes a redirect answer from `url` that was not followed because following redirects is
+/// disabled, or returns `None` for any other answer.
+#[cfg(feature = "http")]
+pub(crate) fn unfollowed_redirect(
+    response: &reqwest::blocking::Response,
+    url: &str,
+) -> Option<crate::error::OntologyRegistryError> {
+    let location = response.headers().get(reqwest::header::LOCATION)?;
+    (response.status().is_redirection() && response.status() != reqwest::StatusCode::NOT_MODIFIED)
+        .then(|| crate::error::OntologyRegistryError::ProvidingOntology {
+            reason: format!(
+                "{url} redirects to {} (HTTP {}), but following redirects is disabled",
+                location.to_str().unwrap_or("an unreadable location"),
+                response.status().as_u16()
+            ),
+        })
+}
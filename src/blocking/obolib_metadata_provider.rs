@@ -0,0 +1,349 @@
+use crate::Version;
+use crate::blocking::DEFAULT_USER_AGENT;
+use crate::blocking::provider_client::ProviderClient;
+use crate::blocking::retry_policy::RetryPolicy;
+use crate::error::OntologyRegistryError;
+use crate::ontology_metadata::OntologyMetadata;
+use crate::traits::OntologyMetadataProviding;
+use reqwest::blocking::{Client, RequestBuilder};
+use reqwest::header::USER_AGENT;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct Registry {
+    ontologies: Vec<ObolibOntology>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct ObolibOntology {
+    id: String,
+    title: Option<String>,
+    version: Option<String>,
+    #[serde(default)]
+    products: Vec<Product>,
+    #[serde(default)]
+    is_obsolete: bool,
+    replaced_by: Option<String>,
+    #[serde(default)]
+    dependencies: Vec<Dependency>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct Dependency {
+    id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct Product {
+    id: String,
+    ontology_purl: Option<String>,
+}
+
+/// Resolves ontology metadata from the OBO Foundry registry (`ontologies.jsonld`).
+///
+/// Download locations are taken from the `products` of the matching ontology. The OBO Foundry
+/// registry usually carries no version, so the version is derived from a product PURL of the
+/// form `.../releases/<version>/...`. If neither is available, `provide_metadata` fails.
+#[derive(Clone, Debug)]
+pub struct ObolibMetadataProvider {
+    registry_url: String,
+    client: ProviderClient,
+    retry_policy: RetryPolicy,
+    user_agent: String,
+    timeout: Option<Duration>,
+}
+
+impl ObolibMetadataProvider {
+    pub fn new(registry_url: &str) -> Self {
+        ObolibMetadataProvider {
+            registry_url: registry_url.to_string(),
+            client: ProviderClient::default(),
+            retry_policy: RetryPolicy::default(),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            timeout: None,
+        }
+    }
+
+    pub fn with_default_url() -> Self {
+        ObolibMetadataProvider::new("https://purl.obolibrary.org/meta/ontologies.jsonld")
+    }
+
+    /// Uses `client` for all requests, regardless of the redirect settings; see
+    /// [`ProviderClient`].
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = self.client.with_client(client);
+        self
+    }
+
+    /// Follows at most `limit` redirects per request instead of reqwest's default of 10. A
+    /// limit of `0` disables following redirects, so that a redirect fails with
+    /// `OntologyRegistryError::HttpError`. Not applied to a client passed to
+    /// [`Self::with_client`]; see [`ProviderClient`].
+    pub fn with_redirect_policy(mut self, limit: usize) -> Self {
+        self.client = self.client.with_redirect_limit(limit);
+        self
+    }
+
+    /// Sets the User-Agent header sent with every request. Defaults to `ontology-registry/<version>`.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Aborts a request that has not completed within `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Retries failed requests up to `max_retries` times with exponential backoff starting at `base_delay`.
+    pub fn with_retries(mut self, max_retries: u32, base_delay: Duration) -> Self {
+        self.retry_policy = self.retry_policy.with_backoff(max_retries, base_delay);
+        self
+    }
+
+    /// Retries a request answered with `429 Too Many Requests` up to `max_retries` times after
+    /// its `Retry-After` delay. Disabled by default; see [`RetryPolicy`].
+    pub fn with_rate_limit_retries(mut self, max_retries: u32) -> Self {
+        self.retry_policy = self.retry_policy.with_rate_limit_retries(max_retries);
+        self
+    }
+
+    /// Fails instead of waiting when a `Retry-After` delay exceeds `max_retry_after`. Defaults
+    /// to 60 seconds; see [`RetryPolicy`].
+    pub fn with_max_retry_after(mut self, max_retry_after: Duration) -> Self {
+        self.retry_policy = self.retry_policy.with_max_retry_after(max_retry_after);
+        self
+    }
+
+    fn request(&self, url: &str) -> RequestBuilder {
+        let request = self.client.get(url).header(USER_AGENT, &self.user_agent);
+        match self.timeout {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        }
+    }
+
+    fn fetch_registry(&self) -> Result<Registry, OntologyRegistryError> {
+        let response = self
+            .retry_policy
+            .send(|| self.request(&self.registry_url))??;
+
+        if !response.status().is_success() {
+            return Err(OntologyRegistryError::HttpError {
+                status: response.status().as_u16(),
+                url: self.registry_url.clone(),
+            });
+        }
+
+        response
+            .json()
+            .map_err(|_| OntologyRegistryError::ProvidingMetadata {
+                reason: format!("Cant convert to json for {}", self.registry_url),
+            })
+    }
+
+    fn metadata_from_registry(
+        registry: Registry,
+        ontology_id: &str,
+    ) -> Result<OntologyMetadata, OntologyRegistryError> {
+        let ontology = registry
+            .ontologies
+            .into_iter()
+            .find(|o| o.id.eq_ignore_ascii_case(ontology_id))
+            .ok_or_else(|| OntologyRegistryError::NotFound {
+                ontology_id: ontology_id.to_string(),
+                version: Version::Latest.to_string(),
+            })?;
+
+        let product_purl = |ending: &str| {
+            ontology
+                .products
+                .iter()
+                .find(|p| p.id.ends_with(ending))
+                .and_then(|p| p.ontology_purl.clone())
+        };
+        let json_file_location = product_purl(".json");
+        let owl_file_location = product_purl(".owl");
+        let obo_file_location = product_purl(".obo");
+
+        let version = ontology
+            .version
+            .clone()
+            .or_else(|| {
+                [&json_file_location, &owl_file_location, &obo_file_location]
+                    .into_iter()
+                    .flatten()
+                    .find_map(|purl| Self::version_from_purl(purl))
+            })
+            .ok_or_else(|| OntologyRegistryError::ProvidingMetadata {
+                reason: format!("Version not found for {ontology_id}"),
+            })?;
+
+        Ok(OntologyMetadata {
+            ontology_id: ontology.id,
+            version,
+            json_file_location,
+            owl_file_location,
+            obo_file_location,
+            title: ontology.title,
+            is_obsolete: ontology.is_obsolete,
+            replaced_by: ontology.replaced_by,
+            dependencies: ontology.dependencies.into_iter().map(|d| d.id).collect(),
+        })
+    }
+
+    fn version_from_purl(purl: &str) -> Option<String> {
+        let mut segments = purl.split('/');
+        segments.find(|segment| *segment == "releases")?;
+        segments
+            .next()
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    }
+}
+
+impl Default for ObolibMetadataProvider {
+    fn default() -> Self {
+        ObolibMetadataProvider::with_default_url()
+    }
+}
+
+impl OntologyMetadataProviding for ObolibMetadataProvider {
+    fn provide_metadata(
+        &self,
+        ontology_id: &str,
+    ) -> Result<OntologyMetadata, OntologyRegistryError> {
+        let registry = self.fetch_registry()?;
+        Self::metadata_from_registry(registry, ontology_id)
+    }
+
+    /// Returns the ids of all ontologies in the OBO Foundry registry, in registry order.
+    fn list_available(&self) -> Result<Vec<String>, OntologyRegistryError> {
+        let registry = self.fetch_registry()?;
+        Ok(registry.ontologies.into_iter().map(|o| o.id).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Server;
+
+    fn registry_fixture() -> String {
+        r#"{
+            "@context": "http://obofoundry.github.io/registry/context.jsonld",
+            "ontologies": [
+                {
+                    "id": "uo",
+                    "title": "Units of measurement ontology",
+                    "products": [
+                        {"id": "uo.owl", "ontology_purl": "http://purl.obolibrary.org/obo/uo/releases/2023-05-25/uo.owl"},
+                        {"id": "uo.obo", "ontology_purl": "http://purl.obolibrary.org/obo/uo/releases/2023-05-25/uo.obo"}
+                    ]
+                },
+                {
+                    "id": "hp",
+                    "title": "Human Phenotype Ontology",
+                    "products": [
+                        {"id": "hp.owl", "ontology_purl": "http://purl.obolibrary.org/obo/hp.owl"},
+                        {"id": "hp.json", "ontology_purl": "http://purl.obolibrary.org/obo/hp.json"}
+                    ]
+                }
+            ]
+        }"#
+        .to_string()
+    }
+
+    fn registry() -> Registry {
+        serde_json::from_str(&registry_fixture()).unwrap()
+    }
+
+    #[test]
+    fn test_metadata_from_registry_case_insensitive_lookup() {
+        let metadata = ObolibMetadataProvider::metadata_from_registry(registry(), "UO").unwrap();
+
+        assert_eq!(metadata.ontology_id, "uo");
+        assert_eq!(metadata.version, "2023-05-25");
+        assert_eq!(
+            metadata.obo_file_location.as_deref(),
+            Some("http://purl.obolibrary.org/obo/uo/releases/2023-05-25/uo.obo")
+        );
+        assert!(metadata.json_file_location.is_none());
+        assert_eq!(
+            metadata.title.as_deref(),
+            Some("Units of measurement ontology")
+        );
+        assert!(!metadata.is_obsolete);
+    }
+
+    #[test]
+    fn test_metadata_from_registry_obsolete_ontology() {
+        let registry: Registry = serde_json::from_str(
+            r#"{"ontologies": [
+                {"id": "mp", "version": "2024-02-07", "is_obsolete": true, "replaced_by": "upheno",
+                 "dependencies": [{"id": "bfo"}, {"id": "pato"}]}
+            ]}"#,
+        )
+        .unwrap();
+        let metadata = ObolibMetadataProvider::metadata_from_registry(registry, "mp").unwrap();
+
+        assert!(metadata.is_obsolete);
+        assert_eq!(metadata.replaced_by.as_deref(), Some("upheno"));
+        assert_eq!(metadata.dependencies, vec!["bfo", "pato"]);
+    }
+
+    #[test]
+    fn test_metadata_from_registry_missing_version() {
+        let result = ObolibMetadataProvider::metadata_from_registry(registry(), "hp");
+
+        match result.unwrap_err() {
+            OntologyRegistryError::ProvidingMetadata { reason } => {
+                assert!(reason.contains("Version not found"));
+            }
+            _ => panic!("Wrong error type"),
+        }
+    }
+
+    #[test]
+    fn test_metadata_from_registry_unknown_ontology() {
+        let result = ObolibMetadataProvider::metadata_from_registry(registry(), "chebi");
+
+        assert!(matches!(
+            result,
+            Err(OntologyRegistryError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_provide_metadata_success() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/ontologies.jsonld")
+            .with_status(200)
+            .with_body(registry_fixture())
+            .create();
+
+        let provider = ObolibMetadataProvider::new(&format!("{}/ontologies.jsonld", server.url()));
+        let metadata = provider.provide_metadata("uo").unwrap();
+
+        mock.assert();
+        assert_eq!(metadata.version, "2023-05-25");
+    }
+
+    #[test]
+    fn test_list_available() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/ontologies.jsonld")
+            .with_status(200)
+            .with_body(registry_fixture())
+            .create();
+
+        let provider = ObolibMetadataProvider::new(&format!("{}/ontologies.jsonld", server.url()));
+
+        assert_eq!(provider.list_available().unwrap(), vec!["uo", "hp"]);
+        mock.assert();
+    }
+}
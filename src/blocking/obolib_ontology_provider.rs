@@ -1,22 +1,50 @@
-use crate::Version;
+use crate::blocking::credentials::{self, Credentials};
+use crate::blocking::provider_client::ProviderClient;
+use crate::blocking::retry_policy::RetryPolicy;
+use crate::blocking::size_limited_reader::SizeLimitedReader;
+use crate::blocking::{DEFAULT_USER_AGENT, parse_proxy, redirect_error, unfollowed_redirect};
+use crate::cache_validators::{CacheValidators, ConditionalFetch};
 use crate::error::OntologyRegistryError;
-use crate::traits::OntologyProviding;
+use crate::traits::{OntologyMetadataProviding, OntologyProviding};
+use crate::{FileType, Version};
+use reqwest::blocking::RequestBuilder;
+use reqwest::blocking::Response;
+use reqwest::header::{
+    ACCEPT, CONTENT_LENGTH, CONTENT_TYPE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+    USER_AGENT,
+};
+use reqwest::{Method, Url};
+use std::fmt;
 use std::io::Read;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// What a `HEAD` request revealed about a release file, see [`OboLibraryProvider::head`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RemoteFileInfo {
+    /// Whether any of the release URLs serves the file.
+    pub exists: bool,
+    /// Size of the file in bytes, if the server announced it.
+    pub content_length: Option<u64>,
+    /// Entity tag of the file, if the server sent one.
+    pub etag: Option<String>,
+}
 
-#[derive(Debug)]
 pub struct OboLibraryProvider {
     base_url: String,
-    client: reqwest::blocking::Client,
+    client: ProviderClient,
+    retry_policy: RetryPolicy,
+    user_agent: String,
+    timeout: Option<Duration>,
+    max_download_bytes: Option<u64>,
+    metadata_provider: Option<Arc<dyn OntologyMetadataProviding + Send + Sync>>,
+    content_type_check: bool,
+    release_template: Option<String>,
+    credentials: Option<Credentials>,
 }
 impl Default for OboLibraryProvider {
     fn default() -> Self {
-        OboLibraryProvider {
-            base_url: "https://purl.obolibrary.org/obo".to_string(),
-            client: reqwest::blocking::Client::builder()
-                .user_agent("Mozilla/5.0 (compatible; ontology-registry/1.0)")
-                .build()
-                .expect("Failed to build HTTP client"),
-        }
+        OboLibraryProvider::new("https://purl.obolibrary.org/obo".to_string())
     }
 }
 
@@ -24,77 +52,585 @@ impl OboLibraryProvider {
     pub fn new(base_url: String) -> Self {
         OboLibraryProvider {
             base_url,
-            client: reqwest::blocking::Client::builder()
-                .user_agent("Mozilla/5.0 (compatible; ontology-registry/1.0)")
-                .build()
-                .expect("Failed to build HTTP client"),
+            client: ProviderClient::default(),
+            retry_policy: RetryPolicy::default(),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            timeout: None,
+            max_download_bytes: None,
+            metadata_provider: None,
+            content_type_check: false,
+            release_template: None,
+            credentials: None,
         }
     }
-}
 
-impl OntologyProviding for OboLibraryProvider {
-    fn provide_ontology(
+    /// Prefers the download locations published by `metadata_provider` over the release URLs
+    /// constructed from the base URL.
+    ///
+    /// Many ontologies publish at PURLs that do not follow the OBO Library release layout. With
+    /// this option, the location for the requested file type is tried first, as long as the
+    /// metadata describes the requested version. The constructed URLs remain the fallback if
+    /// the metadata has no location, describes another release, or cannot be fetched.
+    pub fn with_metadata_urls(
+        mut self,
+        metadata_provider: impl OntologyMetadataProviding + Send + Sync + 'static,
+    ) -> Self {
+        self.metadata_provider = Some(Arc::new(metadata_provider));
+        self
+    }
+
+    /// Aborts a download that has not completed within `timeout`.
+    ///
+    /// Without this option the client's own default applies. A timed out download surfaces as
+    /// `OntologyRegistryError::ProvidingOntology`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Aborts downloads larger than `max_download_bytes` with
+    /// `OntologyRegistryError::DownloadTooLarge`. Unlimited by default.
+    ///
+    /// A `Content-Length` above the limit fails before the body is read; otherwise the limit is
+    /// enforced while streaming, so the registry never commits a truncated file.
+    pub fn with_max_download_bytes(mut self, max_download_bytes: u64) -> Self {
+        self.max_download_bytes = Some(max_download_bytes);
+        self
+    }
+
+    /// Controls whether responses with an HTML `Content-Type` are rejected. Disabled by default,
+    /// as some mirrors send unusual content types for valid files.
+    ///
+    /// Servers that cannot serve the requested format often answer with an HTML page instead,
+    /// which would otherwise be stored as the ontology. Such responses fail with
+    /// `OntologyRegistryError::ProvidingOntology`.
+    pub fn with_content_type_check(mut self, content_type_check: bool) -> Self {
+        self.content_type_check = content_type_check;
+        self
+    }
+
+    /// Builds the URL of a declared release from `template` instead of the OBO Library layout.
+    ///
+    /// `{base}` is replaced by the base URL, `{id}`, `{version}` and `{file}` by the
+    /// percent-encoded ontology id, version and file name, e.g.
+    /// `{base}/{id}/{version}/{file}`. Without a template, `{base}/{id}/releases/{version}/{file}`
+    /// is tried first and `{base}/{id}/{version}/{file}` second. `Version::Latest` and
+    /// `Version::Unversioned` are always fetched from `{base}/{id}/{file}`.
+    pub fn with_release_template(mut self, template: impl Into<String>) -> Self {
+        self.release_template = Some(template.into());
+        self
+    }
+
+    /// Authenticates every request with `token`, sent as `Authorization: Bearer <token>`, e.g.
+    /// for an institutional mirror. Replaces any basic auth credentials.
+    ///
+    /// The token is never logged. A `401` or `403` answer fails with
+    /// `OntologyRegistryError::HttpError` and logs a warning about the rejected credentials.
+    pub fn with_bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.credentials = Some(Credentials::Bearer(token.into()));
+        self
+    }
+
+    /// Authenticates every request with HTTP basic auth. Replaces any bearer token.
+    ///
+    /// The password is never logged. A `401` or `403` answer fails with
+    /// `OntologyRegistryError::HttpError` and logs a warning about the rejected credentials.
+    pub fn with_basic_auth(
+        mut self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        self.credentials = Some(Credentials::Basic {
+            username: username.into(),
+            password: password.into(),
+        });
+        self
+    }
+
+    /// Sends every request through the HTTP(S) proxy at `proxy_url`, e.g.
+    /// `http://proxy.example.com:3128`. Not applied to a client passed to [`Self::with_client`];
+    /// see [`ProviderClient`].
+    ///
+    /// # Errors
+    /// Returns `OntologyRegistryError::InvalidProxy` if `proxy_url` is not a valid proxy URL.
+    pub fn with_proxy(mut self, proxy_url: &str) -> Result<Self, OntologyRegistryError> {
+        self.client = self.client.with_proxy(parse_proxy(proxy_url)?);
+        Ok(self)
+    }
+
+    /// Follows at most `limit` redirects per request instead of reqwest's default of 10, e.g.
+    /// to give up early on a proxy that redirects in a loop. A limit of `0` disables following
+    /// redirects.
+    ///
+    /// Exceeding the limit fails with `OntologyRegistryError::ProvidingOntology`; with
+    /// redirects disabled, so does any redirect, naming its `Location`. Not applied to a client
+    /// passed to [`Self::with_client`]; see [`ProviderClient`].
+    pub fn with_redirect_policy(mut self, limit: usize) -> Self {
+        self.client = self.client.with_redirect_limit(limit);
+        self
+    }
+
+    /// Uses `client` for all requests, regardless of the proxy and redirect settings; see
+    /// [`ProviderClient`].
+    pub fn with_client(mut self, client: reqwest::blocking::Client) -> Self {
+        self.client = self.client.with_client(client);
+        self
+    }
+
+    /// Sets the User-Agent header sent with every request. Defaults to `ontology-registry/<version>`.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Retries failed downloads up to `max_retries` times with exponential backoff starting at `base_delay`.
+    ///
+    /// Connection errors and retryable status codes (5xx, 408, 429) are retried. A 404 moves on to the
+    /// next release URL pattern immediately.
+    pub fn with_retries(mut self, max_retries: u32, base_delay: Duration) -> Self {
+        self.retry_policy = self.retry_policy.with_backoff(max_retries, base_delay);
+        self
+    }
+
+    /// Retries a request answered with `429 Too Many Requests` up to `max_retries` times after
+    /// its `Retry-After` delay. Disabled by default; see [`RetryPolicy`].
+    pub fn with_rate_limit_retries(mut self, max_retries: u32) -> Self {
+        self.retry_policy = self.retry_policy.with_rate_limit_retries(max_retries);
+        self
+    }
+
+    /// Fails instead of waiting when a `Retry-After` delay exceeds `max_retry_after`. Defaults
+    /// to 60 seconds; see [`RetryPolicy`].
+    pub fn with_max_retry_after(mut self, max_retry_after: Duration) -> Self {
+        self.retry_policy = self.retry_policy.with_max_retry_after(max_retry_after);
+        self
+    }
+
+    /// Checks whether a release file exists and learns its size without downloading it.
+    ///
+    /// Sends a `HEAD` request to the same URLs [`OntologyProviding::provide_ontology`] would
+    /// try, in the same order, and reports the first that answers with success. If every URL
+    /// answers `404 Not Found`, the file is reported as absent rather than as an error.
+    pub fn head(
         &self,
         ontology_id: &str,
         file_name: &str,
         version: &Version,
-    ) -> Result<impl Read, OntologyRegistryError> {
-        let urls = match version {
-            Version::Latest => {
-                vec![format!("{}/{}/{}", self.base_url, ontology_id, file_name)]
+    ) -> Result<RemoteFileInfo, OntologyRegistryError> {
+        let Some(response) = self.head_response(ontology_id, file_name, version)? else {
+            return Ok(RemoteFileInfo::default());
+        };
+
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+        };
+        Ok(RemoteFileInfo {
+            exists: true,
+            content_length: header(CONTENT_LENGTH).and_then(|length| length.parse().ok()),
+            etag: header(ETAG).map(str::to_string),
+        })
+    }
+
+    /// Sends the `HEAD` requests of [`Self::head`] and returns the first successful response, or
+    /// `None` if every URL answers `404 Not Found`.
+    fn head_response(
+        &self,
+        ontology_id: &str,
+        file_name: &str,
+        version: &Version,
+    ) -> Result<Option<Response>, OntologyRegistryError> {
+        let file_type = Self::file_type(file_name);
+        for url in self.urls(ontology_id, file_name, version) {
+            let response = match self
+                .retry_policy
+                .send(|| self.request(Method::HEAD, &url, file_type))?
+            {
+                Ok(response) => response,
+                Err(err) if err.is_timeout() => {
+                    return Err(OntologyRegistryError::ProvidingOntology {
+                        reason: format!("Request timed out for {}", url),
+                    });
+                }
+                Err(err) => {
+                    return Err(redirect_error(&err, &url, self.client.redirect_limit())
+                        .unwrap_or_else(|| err.into()));
+                }
+            };
+
+            if response.status() == reqwest::StatusCode::NOT_FOUND {
+                continue;
+            }
+            credentials::warn_if_rejected(self.credentials.as_ref(), &url, response.status());
+            if let Some(err) = unfollowed_redirect(&response, &url) {
+                return Err(err);
+            }
+            if !response.status().is_success() {
+                return Err(OntologyRegistryError::HttpError {
+                    status: response.status().as_u16(),
+                    url,
+                });
+            }
+            return Ok(Some(response));
+        }
+
+        Ok(None)
+    }
+
+    /// Reads the release version from the URL a `Version::Latest` request was redirected to,
+    /// e.g. `2024-04-26` from `.../hp/releases/2024-04-26/hp.obo` or from a GitHub release
+    /// download `.../releases/download/v2024-04-26/hp.obo`.
+    fn release_version(url: &Url) -> Result<String, OntologyRegistryError> {
+        let segments = url.path_segments().map(Iterator::collect::<Vec<_>>);
+        match segments.as_deref() {
+            Some([.., "releases" | "download", version, _file_name]) if !version.is_empty() => {
+                let version = version
+                    .strip_prefix('v')
+                    .filter(|version| version.starts_with(|c: char| c.is_ascii_digit()))
+                    .unwrap_or(version);
+                Ok(version.to_string())
             }
-            Version::Declared(v) => {
-                vec![
-                    format!(
-                        "{}/{}/releases/{}/{}",
-                        self.base_url, ontology_id, v, file_name
-                    ),
-                    format!("{}/{}/{}/{}", self.base_url, ontology_id, v, file_name),
-                ]
+            _ => Err(OntologyRegistryError::ProvidingOntology {
+                reason: format!("Cannot tell the release version from {url}"),
+            }),
+        }
+    }
+
+    /// Builds a request for `url`. The `Accept` header prefers the media type of `file_type`,
+    /// if known, while still allowing anything else.
+    fn request(&self, method: Method, url: &str, file_type: Option<FileType>) -> RequestBuilder {
+        let request = self
+            .client
+            .request(method, url)
+            .header(USER_AGENT, &self.user_agent);
+        let request = match file_type {
+            Some(file_type) => {
+                request.header(ACCEPT, format!("{}, */*;q=0.1", file_type.media_type()))
             }
+            None => request,
         };
+        let request = match &self.credentials {
+            Some(credentials) => credentials.apply(request),
+            None => request,
+        };
+        match self.timeout {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        }
+    }
+
+    fn with_validators(request: RequestBuilder, validators: &CacheValidators) -> RequestBuilder {
+        let request = match &validators.etag {
+            Some(etag) => request.header(IF_NONE_MATCH, etag),
+            None => request,
+        };
+        match &validators.last_modified {
+            Some(last_modified) => request.header(IF_MODIFIED_SINCE, last_modified),
+            None => request,
+        }
+    }
+
+    /// Looks up the metadata download location of `file_name` for `version`, if configured.
+    fn metadata_url(
+        &self,
+        ontology_id: &str,
+        file_name: &str,
+        version: &Version,
+    ) -> Option<String> {
+        let metadata = self
+            .metadata_provider
+            .as_ref()?
+            .provide_metadata(ontology_id)
+            .ok()?;
+
+        if let Version::Declared(v) = version
+            && *v != metadata.version
+        {
+            return None;
+        }
+
+        metadata
+            .file_location(Self::file_type(file_name)?)
+            .map(str::to_string)
+    }
+
+    /// Returns the URLs `file_name` may be served at, in the order they are tried.
+    fn urls(&self, ontology_id: &str, file_name: &str, version: &Version) -> Vec<String> {
+        let mut urls: Vec<String> = self
+            .metadata_url(ontology_id, file_name, version)
+            .into_iter()
+            .collect();
+        urls.extend(match version {
+            Version::Latest | Version::Unversioned => vec![self.url(&[ontology_id, file_name])],
+            Version::Declared(v) => match &self.release_template {
+                Some(template) => vec![self.render_template(template, ontology_id, v, file_name)],
+                None => vec![
+                    self.url(&[ontology_id, "releases", v, file_name]),
+                    self.url(&[ontology_id, v, file_name]),
+                ],
+            },
+        });
+        urls
+    }
 
-        let mut last_status = None;
+    /// Fills the placeholders of a release URL template.
+    fn render_template(
+        &self,
+        template: &str,
+        ontology_id: &str,
+        version: &str,
+        file_name: &str,
+    ) -> String {
+        template
+            .replace("{id}", &Self::encode_segment(ontology_id))
+            .replace("{version}", &Self::encode_segment(version))
+            .replace("{file}", &Self::encode_segment(file_name))
+            .replace("{base}", self.base_url.trim_end_matches('/'))
+    }
+
+    /// Percent-encodes everything but unreserved characters, so `value` stays one path segment.
+    fn encode_segment(value: &str) -> String {
+        value
+            .bytes()
+            .map(|byte| match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                    char::from(byte).to_string()
+                }
+                _ => format!("%{byte:02X}"),
+            })
+            .collect()
+    }
+
+    /// Appends `segments` to the base URL, percent-encoding each of them.
+    fn url(&self, segments: &[&str]) -> String {
+        let Ok(mut url) = Url::parse(&self.base_url) else {
+            return format!("{}/{}", self.base_url, segments.join("/"));
+        };
+        if let Ok(mut path) = url.path_segments_mut() {
+            path.pop_if_empty().extend(segments);
+        }
+        url.into()
+    }
+
+    fn file_type(file_name: &str) -> Option<FileType> {
+        FileType::ALL
+            .iter()
+            .copied()
+            .find(|ft| file_name.ends_with(ft.as_file_ending()))
+    }
 
-        for url in &urls {
-            let resp = self.client.get(url).send();
+    /// Rejects a response that is an HTML page rather than an ontology, if enabled.
+    fn check_content_type(
+        &self,
+        response: &Response,
+        file_type: Option<FileType>,
+        url: &str,
+    ) -> Result<(), OntologyRegistryError> {
+        if !self.content_type_check {
+            return Ok(());
+        }
+        let Some(content_type) = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+        else {
+            return Ok(());
+        };
+
+        let media_type = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        if media_type == "text/html" || media_type == "application/xhtml+xml" {
+            let expected = file_type.map_or("an ontology file", |ft| ft.media_type());
+            return Err(OntologyRegistryError::ProvidingOntology {
+                reason: format!("Expected {expected} from {url}, but received {content_type}"),
+            });
+        }
+        Ok(())
+    }
+
+    /// Requests `file_name` from each candidate URL until one is found. The reader does not
+    /// borrow the arguments, unlike the one returned through `OntologyProviding`.
+    fn fetch(
+        &self,
+        ontology_id: &str,
+        file_name: &str,
+        version: &Version,
+        validators: &CacheValidators,
+    ) -> Result<ConditionalFetch<SizeLimitedReader<Response>>, OntologyRegistryError> {
+        let file_type = Self::file_type(file_name);
+        for url in &self.urls(ontology_id, file_name, version) {
+            let resp = self.retry_policy.send(|| {
+                Self::with_validators(self.request(Method::GET, url, file_type), validators)
+            })?;
 
             match resp {
                 Ok(response) => {
                     if response.status().is_success() {
-                        return Ok(response);
+                        if let (Some(limit), Some(length)) =
+                            (self.max_download_bytes, response.content_length())
+                            && length > limit
+                        {
+                            return Err(OntologyRegistryError::DownloadTooLarge {
+                                url: url.clone(),
+                                limit,
+                            });
+                        }
+                        self.check_content_type(&response, file_type, url)?;
+                        let header = |name| {
+                            response
+                                .headers()
+                                .get(name)
+                                .and_then(|value| value.to_str().ok())
+                                .map(str::to_string)
+                        };
+                        let validators = CacheValidators {
+                            etag: header(ETAG),
+                            last_modified: header(LAST_MODIFIED),
+                        };
+                        let content_length = response.content_length();
+                        return Ok(ConditionalFetch::Modified {
+                            reader: SizeLimitedReader::new(response, self.max_download_bytes, url),
+                            validators,
+                            content_length,
+                        });
+                    } else if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+                        return Ok(ConditionalFetch::NotModified);
                     } else if response.status() == reqwest::StatusCode::NOT_FOUND {
-                        last_status = Some(response.status());
                         continue;
+                    } else if let Some(err) = unfollowed_redirect(&response, url) {
+                        return Err(err);
                     } else {
-                        return Err(OntologyRegistryError::ProvidingOntology {
-                            reason: format!("HTTP Error {} for {}", response.status(), url),
+                        credentials::warn_if_rejected(
+                            self.credentials.as_ref(),
+                            url,
+                            response.status(),
+                        );
+                        return Err(OntologyRegistryError::HttpError {
+                            status: response.status().as_u16(),
+                            url: url.clone(),
                         });
                     }
                 }
-                Err(err) => {
+                Err(err) if err.is_timeout() => {
                     return Err(OntologyRegistryError::ProvidingOntology {
-                        reason: format!("Network Error: {}", err),
+                        reason: format!("Request timed out for {}", url),
                     });
                 }
+                Err(err) => {
+                    return Err(redirect_error(&err, url, self.client.redirect_limit())
+                        .unwrap_or_else(|| err.into()));
+                }
             }
         }
 
-        Err(OntologyRegistryError::ProvidingOntology {
-            reason: format!(
-                "Ontology not found after trying {} patterns. Last status: {:?}",
-                urls.len(),
-                last_status.unwrap_or(reqwest::StatusCode::NOT_FOUND)
-            ),
+        Err(OntologyRegistryError::NotFound {
+            ontology_id: ontology_id.to_string(),
+            version: version.to_string(),
         })
     }
 }
 
+impl fmt::Debug for OboLibraryProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OboLibraryProvider")
+            .field("base_url", &self.base_url)
+            .field("client", &self.client)
+            .field("retry_policy", &self.retry_policy)
+            .field("user_agent", &self.user_agent)
+            .field("timeout", &self.timeout)
+            .field("max_download_bytes", &self.max_download_bytes)
+            .field("metadata_urls", &self.metadata_provider.is_some())
+            .field("content_type_check", &self.content_type_check)
+            .field("release_template", &self.release_template)
+            .field("credentials", &self.credentials)
+            .finish()
+    }
+}
+
+impl OntologyProviding for OboLibraryProvider {
+    fn provide_ontology(
+        &self,
+        ontology_id: &str,
+        file_name: &str,
+        version: &Version,
+    ) -> Result<impl Read, OntologyRegistryError> {
+        match self.fetch(ontology_id, file_name, version, &CacheValidators::default())? {
+            ConditionalFetch::Modified { reader, .. } => Ok(reader),
+            ConditionalFetch::NotModified => Err(OntologyRegistryError::ProvidingOntology {
+                reason: format!(
+                    "Unexpected 304 Not Modified for unconditional request of {file_name}"
+                ),
+            }),
+        }
+    }
+
+    /// Downloads `{base}/{id}/{file}` and reads the version from the release URL it redirects
+    /// to, as the OBO PURLs of most ontologies do. Fails with
+    /// `OntologyRegistryError::ProvidingOntology` if the final URL names no release.
+    fn provide_latest(
+        &self,
+        ontology_id: &str,
+        file_name: &str,
+    ) -> Result<(String, impl Read), OntologyRegistryError> {
+        match self.fetch(
+            ontology_id,
+            file_name,
+            &Version::Latest,
+            &CacheValidators::default(),
+        )? {
+            ConditionalFetch::Modified { reader, .. } => {
+                let version = Self::release_version(reader.get_ref().url())?;
+                Ok((version, reader))
+            }
+            ConditionalFetch::NotModified => Err(OntologyRegistryError::ProvidingOntology {
+                reason: format!(
+                    "Unexpected 304 Not Modified for unconditional request of {file_name}"
+                ),
+            }),
+        }
+    }
+
+    /// Like [`provide_latest`](OntologyProviding::provide_latest), but follows the redirects
+    /// with a `HEAD` request, so nothing is downloaded.
+    fn latest_version(
+        &self,
+        ontology_id: &str,
+        file_name: &str,
+    ) -> Result<String, OntologyRegistryError> {
+        let response = self
+            .head_response(ontology_id, file_name, &Version::Latest)?
+            .ok_or_else(|| OntologyRegistryError::NotFound {
+                ontology_id: ontology_id.to_string(),
+                version: Version::Latest.to_string(),
+            })?;
+        Self::release_version(response.url())
+    }
+
+    /// Sends `validators` as `If-None-Match`/`If-Modified-Since` and reports a
+    /// `304 Not Modified` as [`ConditionalFetch::NotModified`].
+    fn provide_ontology_if_modified(
+        &self,
+        ontology_id: &str,
+        file_name: &str,
+        version: &Version,
+        validators: &CacheValidators,
+    ) -> Result<ConditionalFetch<impl Read>, OntologyRegistryError> {
+        self.fetch(ontology_id, file_name, version, validators)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ontology_metadata::OntologyMetadata;
     use crate::traits::OntologyProviding;
-    use mockito::Server;
+    use mockito::{Matcher, Server};
 
     #[test]
     fn test_provide_ontology_latest_success() {
@@ -161,11 +697,378 @@ mod tests {
     }
 
     #[test]
-    fn test_provide_ontology_server_error() {
+    fn test_provide_ontology_renders_release_template() {
+        let mut server = Server::new();
+
+        let mock = server
+            .mock("GET", "/archive/go/2023-01-01%20rc/go.owl")
+            .with_status(200)
+            .with_body("OWL Content")
+            .create();
+
+        let provider = OboLibraryProvider::new(format!("{}/", server.url()))
+            .with_release_template("{base}/archive/{id}/{version}/{file}");
+        let mut buffer = String::new();
+        provider
+            .provide_ontology("go", "go.owl", &Version::from("2023-01-01 rc"))
+            .unwrap()
+            .read_to_string(&mut buffer)
+            .unwrap();
+
+        mock.assert();
+        assert_eq!(buffer, "OWL Content");
+    }
+
+    #[test]
+    fn test_release_template_replaces_default_fallbacks() {
+        let mut server = Server::new();
+
+        let template_mock = server
+            .mock("GET", "/go/v2023-01-01/go.owl")
+            .with_status(404)
+            .create();
+        let default_mock = server
+            .mock(
+                "GET",
+                Matcher::Regex("/go/(releases/)?2023-01-01/go.owl".to_string()),
+            )
+            .expect(0)
+            .create();
+
+        let provider = OboLibraryProvider::new(server.url())
+            .with_release_template("{base}/{id}/v{version}/{file}");
+        let version = Version::from("2023-01-01");
+        let result = provider.provide_ontology("go", "go.owl", &version);
+
+        assert!(matches!(
+            result,
+            Err(OntologyRegistryError::NotFound { .. })
+        ));
+        template_mock.assert();
+        default_mock.assert();
+    }
+
+    #[test]
+    fn test_provide_ontology_sends_user_agent() {
+        let mut server = Server::new();
+
+        let mock = server
+            .mock("GET", "/go/releases/2023-01-01/go.owl")
+            .match_header("user-agent", "my-lab-pipeline/2.0")
+            .with_status(200)
+            .with_body("OWL Content")
+            .create();
+
+        let provider = OboLibraryProvider::new(server.url()).with_user_agent("my-lab-pipeline/2.0");
+        let version = Version::from("2023-01-01");
+
+        provider.provide_ontology("go", "go.owl", &version).unwrap();
+
+        mock.assert();
+    }
+
+    #[test]
+    fn test_provide_ontology_sends_accept_header() {
+        let mut server = Server::new();
+
+        let mock = server
+            .mock("GET", "/go/releases/2023-01-01/go.owl")
+            .match_header("accept", "application/rdf+xml, */*;q=0.1")
+            .with_status(200)
+            .with_header("content-type", "application/rdf+xml")
+            .with_body("OWL Content")
+            .create();
+
+        let provider = OboLibraryProvider::new(server.url());
+        provider
+            .provide_ontology("go", "go.owl", &Version::from("2023-01-01"))
+            .unwrap();
+
+        mock.assert();
+    }
+
+    #[test]
+    fn test_provide_ontology_rejects_html() {
+        let mut server = Server::new();
+
+        let _m = server
+            .mock("GET", "/go/releases/2023-01-01/go.owl")
+            .with_status(200)
+            .with_header("content-type", "text/html; charset=utf-8")
+            .with_body("<html><body>Not here</body></html>")
+            .create();
+
+        let provider = OboLibraryProvider::new(server.url()).with_content_type_check(true);
+        let version = Version::from("2023-01-01");
+        let result = provider.provide_ontology("go", "go.owl", &version);
+
+        match result {
+            Err(OntologyRegistryError::ProvidingOntology { reason }) => {
+                assert!(reason.contains("application/rdf+xml"));
+                assert!(reason.contains("text/html"));
+            }
+            _ => panic!("Wrong error type returned"),
+        }
+
+        let lenient = OboLibraryProvider::new(server.url());
+        assert!(lenient.provide_ontology("go", "go.owl", &version).is_ok());
+    }
+
+    #[test]
+    fn test_provide_ontology_encodes_path_segments() {
+        let mut server = Server::new();
+
+        let mock = server
+            .mock(
+                "GET",
+                "/cl%20modifier/releases/2024-01-01/cl%20modifier.owl",
+            )
+            .with_status(200)
+            .with_body("OWL Content")
+            .create();
+
+        let provider = OboLibraryProvider::new(format!("{}/", server.url()));
+        provider
+            .provide_ontology(
+                "cl modifier",
+                "cl modifier.owl",
+                &Version::from("2024-01-01"),
+            )
+            .unwrap();
+
+        mock.assert();
+    }
+
+    struct FixedMetadataProvider(OntologyMetadata);
+
+    impl OntologyMetadataProviding for FixedMetadataProvider {
+        fn provide_metadata(
+            &self,
+            _ontology_id: &str,
+        ) -> Result<OntologyMetadata, OntologyRegistryError> {
+            Ok(self.0.clone())
+        }
+    }
+
+    #[test]
+    fn test_provide_ontology_prefers_metadata_url() {
+        let mut server = Server::new();
+
+        let mock = server
+            .mock("GET", "/custom/layout/uo.obo")
+            .with_status(200)
+            .with_body("OBO Content")
+            .create();
+
+        let provider = OboLibraryProvider::new(server.url()).with_metadata_urls(
+            FixedMetadataProvider(OntologyMetadata {
+                ontology_id: "uo".to_string(),
+                version: "2023-05-25".to_string(),
+                obo_file_location: Some(format!("{}/custom/layout/uo.obo", server.url())),
+                ..Default::default()
+            }),
+        );
+
+        let version = Version::from("2023-05-25");
+        let mut result = provider.provide_ontology("uo", "uo.obo", &version).unwrap();
+
+        mock.assert();
+        let mut buffer = String::new();
+        result.read_to_string(&mut buffer).unwrap();
+        assert_eq!(buffer, "OBO Content");
+    }
+
+    #[test]
+    fn test_provide_ontology_falls_back_without_metadata_url() {
+        let mut server = Server::new();
+
+        let mock = server
+            .mock("GET", "/uo/releases/2023-05-25/uo.json")
+            .with_status(200)
+            .with_body("JSON Content")
+            .create();
+
+        let provider = OboLibraryProvider::new(server.url()).with_metadata_urls(
+            FixedMetadataProvider(OntologyMetadata {
+                ontology_id: "uo".to_string(),
+                version: "2023-05-25".to_string(),
+                obo_file_location: Some(format!("{}/custom/layout/uo.obo", server.url())),
+                ..Default::default()
+            }),
+        );
+
+        provider
+            .provide_ontology("uo", "uo.json", &Version::from("2023-05-25"))
+            .unwrap();
+
+        mock.assert();
+    }
+
+    #[test]
+    fn test_provide_ontology_if_modified_200_then_304() {
+        let mut server = Server::new();
+
+        let first = server
+            .mock("GET", "/go/releases/2023-01-01/go.owl")
+            .with_status(200)
+            .with_header("etag", "\"v1\"")
+            .with_header("last-modified", "Sun, 01 Jan 2023 00:00:00 GMT")
+            .with_body("OWL Content")
+            .expect(1)
+            .create();
+
+        let provider = OboLibraryProvider::new(server.url());
+        let version = Version::from("2023-01-01");
+
+        let validators = match provider
+            .provide_ontology_if_modified("go", "go.owl", &version, &CacheValidators::default())
+            .unwrap()
+        {
+            ConditionalFetch::Modified { validators, .. } => validators,
+            ConditionalFetch::NotModified => panic!("Expected a download"),
+        };
+        first.assert();
+        assert_eq!(validators.etag.as_deref(), Some("\"v1\""));
+
+        let second = server
+            .mock("GET", "/go/releases/2023-01-01/go.owl")
+            .match_header("if-none-match", "\"v1\"")
+            .match_header("if-modified-since", "Sun, 01 Jan 2023 00:00:00 GMT")
+            .with_status(304)
+            .expect(1)
+            .create();
+
+        let result = provider
+            .provide_ontology_if_modified("go", "go.owl", &version, &validators)
+            .unwrap();
+
+        second.assert();
+        assert!(matches!(result, ConditionalFetch::NotModified));
+    }
+
+    #[test]
+    fn test_provide_ontology_rejects_content_length_over_limit() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/go/releases/2023-01-01/go.owl")
+            .with_status(200)
+            .with_body("a".repeat(100))
+            .create();
+
+        let provider = OboLibraryProvider::new(server.url()).with_max_download_bytes(10);
+        let version = Version::from("2023-01-01");
+        let result = provider.provide_ontology("go", "go.owl", &version);
+
+        assert!(matches!(
+            result,
+            Err(OntologyRegistryError::DownloadTooLarge { limit: 10, .. })
+        ));
+    }
+
+    #[test]
+    fn test_provide_ontology_timeout() {
+        let mut server = Server::new();
+
+        let _m = server
+            .mock("GET", "/go/releases/2023-01-01/go.owl")
+            .with_status(200)
+            .with_body_from_request(|_| {
+                std::thread::sleep(Duration::from_millis(500));
+                b"OWL Content".to_vec()
+            })
+            .create();
+
+        let provider =
+            OboLibraryProvider::new(server.url()).with_timeout(Duration::from_millis(50));
+        let version = Version::from("2023-01-01");
+
+        let result = provider.provide_ontology("go", "go.owl", &version);
+
+        match result {
+            Err(OntologyRegistryError::ProvidingOntology { reason }) => {
+                assert!(reason.contains("timed out"));
+            }
+            _ => panic!("Wrong error type returned"),
+        }
+    }
+
+    #[test]
+    fn test_provide_ontology_with_shared_client() {
         let mut server = Server::new();
 
         let mock = server
             .mock("GET", "/go/releases/2023-01-01/go.owl")
+            .with_status(200)
+            .with_body("OWL Content")
+            .expect(2)
+            .create();
+
+        let client = reqwest::blocking::Client::new();
+        let first = OboLibraryProvider::new(server.url()).with_client(client.clone());
+        let second = OboLibraryProvider::new(server.url()).with_client(client);
+        let version = Version::from("2023-01-01");
+
+        first.provide_ontology("go", "go.owl", &version).unwrap();
+        second.provide_ontology("go", "go.owl", &version).unwrap();
+
+        mock.assert();
+    }
+
+    #[test]
+    fn test_head_present_file() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("HEAD", "/hp/releases/2024-04-26/hp.obo")
+            .with_status(200)
+            .with_header("content-length", "1234")
+            .with_header("etag", "\"abc\"")
+            .create();
+
+        let provider = OboLibraryProvider::new(server.url());
+        let info = provider
+            .head("hp", "hp.obo", &Version::from("2024-04-26"))
+            .unwrap();
+
+        mock.assert();
+        assert_eq!(
+            info,
+            RemoteFileInfo {
+                exists: true,
+                content_length: Some(1234),
+                etag: Some("\"abc\"".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_head_absent_file() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("HEAD", Matcher::Any)
+            .with_status(404)
+            .expect(2)
+            .create();
+
+        let provider = OboLibraryProvider::new(server.url());
+        let info = provider
+            .head("hp", "hp.obo", &Version::from("1999-01-01"))
+            .unwrap();
+
+        mock.assert();
+        assert!(!info.exists);
+        assert_eq!(info, RemoteFileInfo::default());
+    }
+
+    #[test]
+    fn test_provide_ontology_not_found() {
+        let mut server = Server::new();
+
+        let mock = server
+            .mock("GET", "/go/releases/2023-01-01/go.owl")
+            .with_status(404)
+            .create();
+        let _fallback_mock = server
+            .mock("GET", "/go/2023-01-01/go.owl")
             .with_status(404)
             .create();
 
@@ -179,10 +1082,337 @@ mod tests {
         assert!(result.is_err());
 
         match result {
-            Err(OntologyRegistryError::ProvidingOntology { reason }) => {
-                assert!(!reason.is_empty());
+            Err(OntologyRegistryError::NotFound {
+                ontology_id,
+                version,
+            }) => {
+                assert_eq!(ontology_id, "go");
+                assert_eq!(version, "2023-01-01");
             }
             _ => panic!("Wrong error type returned"),
         }
     }
+
+    #[test]
+    fn test_provide_ontology_server_error() {
+        let mut server = Server::new();
+
+        let mock = server
+            .mock("GET", "/go/releases/2023-01-01/go.owl")
+            .with_status(503)
+            .create();
+
+        let provider = OboLibraryProvider::new(server.url());
+
+        let version = Version::from("2023-01-01");
+
+        let result = provider.provide_ontology("go", "go.owl", &version);
+
+        mock.assert();
+
+        match result {
+            Err(OntologyRegistryError::HttpError { status, url }) => {
+                assert_eq!(status, 503);
+                assert!(url.ends_with("/go/releases/2023-01-01/go.owl"));
+            }
+            _ => panic!("Wrong error type returned"),
+        }
+    }
+
+    #[test]
+    fn test_provide_ontology_retries_until_success() {
+        let mut server = Server::new();
+
+        let failing = server
+            .mock("GET", "/go/releases/2023-01-01/go.owl")
+            .with_status(502)
+            .expect(2)
+            .create();
+        let succeeding = server
+            .mock("GET", "/go/releases/2023-01-01/go.owl")
+            .with_status(200)
+            .with_body("Recovered Content")
+            .expect(1)
+            .create();
+
+        let provider =
+            OboLibraryProvider::new(server.url()).with_retries(3, Duration::from_millis(1));
+        let version = Version::from("2023-01-01");
+
+        let mut result = provider.provide_ontology("go", "go.owl", &version).unwrap();
+
+        failing.assert();
+        succeeding.assert();
+        let mut buffer = String::new();
+        let _ = result.read_to_string(&mut buffer).unwrap();
+        assert_eq!(buffer, "Recovered Content");
+    }
+
+    #[test]
+    fn test_provide_ontology_does_not_retry_not_found() {
+        let mut server = Server::new();
+
+        let mock = server
+            .mock("GET", "/go/releases/2023-01-01/go.owl")
+            .with_status(404)
+            .expect(1)
+            .create();
+
+        let provider =
+            OboLibraryProvider::new(server.url()).with_retries(3, Duration::from_millis(1));
+        let version = Version::from("2023-01-01");
+
+        let result = provider.provide_ontology("go", "go.owl", &version);
+
+        mock.assert();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_provide_ontology_sends_bearer_token() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/hp/hp.obo")
+            .match_header("authorization", "Bearer secret")
+            .with_status(200)
+            .with_body("format-version: 1.2")
+            .create();
+
+        let provider = OboLibraryProvider::new(server.url()).with_bearer_token("secret");
+        let mut content = String::new();
+        provider
+            .provide_ontology("hp", "hp.obo", &Version::Latest)
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+
+        mock.assert();
+        assert_eq!(content, "format-version: 1.2");
+        assert!(!format!("{provider:?}").contains("secret"));
+    }
+
+    #[test]
+    fn test_provide_ontology_reports_rejected_credentials() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/hp/hp.obo")
+            .match_header("authorization", "Basic YWxpY2U6c2VjcmV0")
+            .with_status(401)
+            .create();
+
+        let provider = OboLibraryProvider::new(server.url()).with_basic_auth("alice", "secret");
+        let result = provider.provide_ontology("hp", "hp.obo", &Version::Latest);
+
+        mock.assert();
+        assert!(matches!(
+            result,
+            Err(OntologyRegistryError::HttpError { status: 401, .. })
+        ));
+    }
+
+    #[test]
+    fn test_provide_ontology_through_proxy() {
+        let mut proxy = Server::new();
+        let mock = proxy
+            .mock("GET", Matcher::Any)
+            .match_header("host", "purl.example.org")
+            .with_status(200)
+            .with_body("format-version: 1.2")
+            .create();
+
+        let provider = OboLibraryProvider::new("http://purl.example.org/obo".to_string())
+            .with_proxy(&proxy.url())
+            .unwrap();
+        let mut content = String::new();
+        provider
+            .provide_ontology("hp", "hp.obo", &Version::Latest)
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+
+        mock.assert();
+        assert_eq!(content, "format-version: 1.2");
+    }
+
+    #[test]
+    fn test_with_client_takes_precedence_over_proxy() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/hp/hp.obo")
+            .with_status(200)
+            .with_body("format-version: 1.2")
+            .expect(2)
+            .create();
+        let unreachable_proxy = "http://127.0.0.1:9";
+
+        let client_first = OboLibraryProvider::new(server.url())
+            .with_client(reqwest::blocking::Client::new())
+            .with_proxy(unreachable_proxy)
+            .unwrap();
+        let proxy_first = OboLibraryProvider::new(server.url())
+            .with_proxy(unreachable_proxy)
+            .unwrap()
+            .with_client(reqwest::blocking::Client::new());
+
+        for provider in [client_first, proxy_first] {
+            provider
+                .provide_ontology("hp", "hp.obo", &Version::Latest)
+                .unwrap();
+        }
+        mock.assert();
+    }
+
+    /// Serves `hp.obo` at the end of a chain of two redirects.
+    #[test]
+    fn test_provide_latest_reads_version_from_release_url() {
+        let mut server = Server::new();
+        let _purl = server
+            .mock("GET", "/hp/hp.obo")
+            .with_status(302)
+            .with_header("location", "/hp/releases/download/v2024-04-26/hp.obo")
+            .create();
+        let _release = server
+            .mock("GET", "/hp/releases/download/v2024-04-26/hp.obo")
+            .with_status(200)
+            .with_body("hp content")
+            .create();
+        let provider = OboLibraryProvider::new(server.url());
+
+        let (version, mut reader) = provider.provide_latest("hp", "hp.obo").unwrap();
+        let mut content = String::new();
+        reader.read_to_string(&mut content).unwrap();
+
+        assert_eq!(version, "2024-04-26");
+        assert_eq!(content, "hp content");
+    }
+
+    #[test]
+    fn test_latest_version_follows_redirect_without_download() {
+        let mut server = Server::new();
+        let _purl = server
+            .mock("HEAD", "/mondo/mondo.owl")
+            .with_status(302)
+            .with_header("location", "/mondo/releases/2024-01-03/mondo.owl")
+            .create();
+        let _release = server
+            .mock("HEAD", "/mondo/releases/2024-01-03/mondo.owl")
+            .with_status(200)
+            .create();
+        let download = server.mock("GET", Matcher::Any).expect(0).create();
+        let provider = OboLibraryProvider::new(server.url());
+
+        let version = provider.latest_version("mondo", "mondo.owl").unwrap();
+
+        assert_eq!(version, "2024-01-03");
+        download.assert();
+    }
+
+    #[test]
+    fn test_latest_version_without_release_url() {
+        let mut server = Server::new();
+        let _latest = server.mock("HEAD", "/go/go.owl").with_status(200).create();
+        let provider = OboLibraryProvider::new(server.url());
+
+        let result = provider.latest_version("go", "go.owl");
+
+        assert!(matches!(
+            result,
+            Err(OntologyRegistryError::ProvidingOntology { .. })
+        ));
+    }
+
+    fn redirect_chain(server: &mut Server) -> Vec<mockito::Mock> {
+        vec![
+            server
+                .mock("GET", "/hp/hp.obo")
+                .with_status(302)
+                .with_header("location", "/step/hp.obo")
+                .create(),
+            server
+                .mock("GET", "/step/hp.obo")
+                .with_status(301)
+                .with_header("location", "/final/hp.obo")
+                .create(),
+            server
+                .mock("GET", "/final/hp.obo")
+                .with_status(200)
+                .with_header("content-type", "text/plain")
+                .with_body("format-version: 1.2")
+                .create(),
+        ]
+    }
+
+    #[test]
+    fn test_redirect_chain_within_limit() {
+        let mut server = Server::new();
+        let mocks = redirect_chain(&mut server);
+        let provider = OboLibraryProvider::new(server.url()).with_redirect_policy(2);
+
+        let mut content = String::new();
+        provider
+            .provide_ontology("hp", "hp.obo", &Version::Latest)
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+
+        mocks.iter().for_each(mockito::Mock::assert);
+        assert_eq!(content, "format-version: 1.2");
+    }
+
+    #[test]
+    fn test_redirect_chain_beyond_limit() {
+        let mut server = Server::new();
+        let _mocks = redirect_chain(&mut server);
+        let provider = OboLibraryProvider::new(server.url()).with_redirect_policy(1);
+
+        let result = provider.provide_ontology("hp", "hp.obo", &Version::Latest);
+
+        match result {
+            Err(OntologyRegistryError::ProvidingOntology { reason }) => {
+                assert!(reason.contains("Too many redirects"), "{reason}");
+                assert!(reason.contains("/hp/hp.obo"), "{reason}");
+            }
+            _ => panic!("Expected ProvidingOntology"),
+        }
+    }
+
+    #[test]
+    fn test_disabled_redirects_report_location() {
+        let mut server = Server::new();
+        let _mocks = redirect_chain(&mut server);
+        let provider = OboLibraryProvider::new(server.url()).with_redirect_policy(0);
+
+        let result = provider.provide_ontology("hp", "hp.obo", &Version::Latest);
+
+        match result {
+            Err(OntologyRegistryError::ProvidingOntology { reason }) => {
+                assert!(reason.contains("/step/hp.obo"), "{reason}");
+                assert!(reason.contains("302"), "{reason}");
+            }
+            _ => panic!("Expected ProvidingOntology"),
+        }
+
+        let _head = server
+            .mock("HEAD", "/hp/hp.obo")
+            .with_status(302)
+            .with_header("location", "/step/hp.obo")
+            .create();
+        assert!(matches!(
+            provider.head("hp", "hp.obo", &Version::Latest),
+            Err(OntologyRegistryError::ProvidingOntology { .. })
+        ));
+    }
+
+    #[test]
+    fn test_with_proxy_rejects_invalid_url() {
+        let result = OboLibraryProvider::default().with_proxy("http://user:secret@[::1");
+
+        match result {
+            Err(OntologyRegistryError::InvalidProxy { proxy_url, reason }) => {
+                assert_eq!(proxy_url, "http://<redacted>@[::1");
+                assert!(!reason.contains("secret"));
+            }
+            _ => panic!("Expected InvalidProxy"),
+        }
+    }
 }
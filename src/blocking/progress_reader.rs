@@ -0,0 +1,35 @@
+use std::io::{self, Read};
+
+/// Wraps a download stream and reports the number of bytes read so far after every read.
+pub(crate) struct ProgressReader<'a, R> {
+    inner: R,
+    bytes_read: u64,
+    total_bytes: Option<u64>,
+    on_progress: &'a mut dyn FnMut(u64, Option<u64>),
+}
+
+impl<'a, R> ProgressReader<'a, R> {
+    pub(crate) fn new(
+        inner: R,
+        total_bytes: Option<u64>,
+        on_progress: &'a mut dyn FnMut(u64, Option<u64>),
+    ) -> Self {
+        ProgressReader {
+            inner,
+            bytes_read: 0,
+            total_bytes,
+            on_progress,
+        }
+    }
+}
+
+impl<R: Read> Read for ProgressReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        if read > 0 {
+            self.bytes_read += read as u64;
+            (self.on_progress)(self.bytes_read, self.total_bytes);
+        }
+        Ok(read)
+    }
+}
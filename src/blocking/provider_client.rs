@@ -0,0 +1,110 @@
+use crate::blocking::http_client;
+use log::warn;
+use reqwest::Proxy;
+use reqwest::blocking::Client;
+use std::fmt;
+use std::ops::Deref;
+
+/// Redirects reqwest follows per request unless configured otherwise.
+const DEFAULT_REDIRECT_LIMIT: usize = 10;
+
+/// The HTTP client an HTTP provider sends its requests with.
+///
+/// The providers configure it through their `with_client`, `with_proxy` and
+/// `with_redirect_policy` builders, in any order.
+///
+/// By default, the client is built from the configured proxy and redirect limit. A proxy
+/// replaces the proxy environment variables; a redirect limit replaces reqwest's default of
+/// 10 redirects per request, and `0` disables following redirects.
+///
+/// A client passed to `with_client`, e.g. to share one connection pool between several
+/// providers, takes precedence and is used as given. A proxy or redirect limit configured
+/// before or after is not applied to it, and a warning is logged; configure them on the
+/// client itself instead.
+#[derive(Clone, Default)]
+pub struct ProviderClient {
+    client: Client,
+    custom_client: bool,
+    proxy: Option<Proxy>,
+    redirect_limit: Option<usize>,
+}
+
+impl ProviderClient {
+    /// Uses `client` as given, see [`ProviderClient`].
+    pub(crate) fn with_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self.custom_client = true;
+        self.rebuild()
+    }
+
+    /// Sends every request through `proxy`.
+    #[cfg(any(feature = "bioregistry", feature = "obolibrary"))]
+    pub(crate) fn with_proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self.rebuild()
+    }
+
+    /// Follows at most `limit` redirects per request.
+    pub(crate) fn with_redirect_limit(mut self, limit: usize) -> Self {
+        self.redirect_limit = Some(limit);
+        self.rebuild()
+    }
+
+    /// Returns the redirect limit the client applies, which is unknown for a client passed to
+    /// `with_client`.
+    pub(crate) fn redirect_limit(&self) -> Option<usize> {
+        if self.custom_client {
+            None
+        } else {
+            Some(self.redirect_limit.unwrap_or(DEFAULT_REDIRECT_LIMIT))
+        }
+    }
+
+    fn rebuild(mut self) -> Self {
+        if !self.custom_client {
+            self.client = http_client(self.proxy.clone(), self.redirect_limit);
+        } else if self.proxy.is_some() || self.redirect_limit.is_some() {
+            warn!(
+                "Ignoring the proxy and redirect limit, a client set with with_client takes precedence"
+            );
+        }
+        self
+    }
+}
+
+impl Deref for ProviderClient {
+    type Target = Client;
+
+    fn deref(&self) -> &Client {
+        &self.client
+    }
+}
+
+impl fmt::Debug for ProviderClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProviderClient")
+            .field("client", &self.client)
+            .field("custom_client", &self.custom_client)
+            .field("proxy", &self.proxy.is_some())
+            .field("redirect_limit", &self.redirect_limit)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redirect_limit() {
+        let built = ProviderClient::default();
+        let limited = ProviderClient::default().with_redirect_limit(3);
+        let custom = ProviderClient::default()
+            .with_redirect_limit(3)
+            .with_client(Client::new());
+
+        assert_eq!(built.redirect_limit(), Some(DEFAULT_REDIRECT_LIMIT));
+        assert_eq!(limited.redirect_limit(), Some(3));
+        assert_eq!(custom.redirect_limit(), None);
+    }
+}
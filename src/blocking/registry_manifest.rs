@@ -0,0 +1,104 @@
+use crate::RegistryKey;
+use crate::cache_validators::CacheValidators;
+use crate::error::OntologyRegistryError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Name of the manifest file inside a registry root. Hidden, so `list` skips it.
+pub(crate) const MANIFEST_FILE_NAME: &str = ".ontology_registry_manifest.json";
+
+/// Bookkeeping stored alongside the ontology files of a registry root.
+///
+/// Records the registry key of every file whose name cannot be parsed back into a key,
+/// e.g. because it was shortened to a hash, the HTTP cache validators of downloaded files, when
+/// each file was last written or confirmed unchanged by `refresh`, and the SHA-256 checksum of
+/// every file written by the registry.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct RegistryManifest {
+    #[serde(default)]
+    pub(crate) hashed_file_names: BTreeMap<String, RegistryKey>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) cache_validators: BTreeMap<String, CacheValidators>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) checked_at: BTreeMap<String, SystemTime>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) checksums: BTreeMap<String, String>,
+}
+
+impl RegistryManifest {
+    /// Drops everything recorded about `file_name`. Returns `true` if anything was removed.
+    pub(crate) fn forget(&mut self, file_name: &str) -> bool {
+        let hashed = self.hashed_file_names.remove(file_name).is_some();
+        let validated = self.cache_validators.remove(file_name).is_some();
+        let checked = self.checked_at.remove(file_name).is_some();
+        let checksummed = self.checksums.remove(file_name).is_some();
+        hashed || validated || checked || checksummed
+    }
+}
+
+impl RegistryManifest {
+    /// Loads the manifest of `root`, returning an empty manifest if none exists yet.
+    pub(crate) fn load(root: &Path) -> Result<RegistryManifest, OntologyRegistryError> {
+        let path = root.join(MANIFEST_FILE_NAME);
+        if !path.exists() {
+            return Ok(RegistryManifest::default());
+        }
+
+        let raw =
+            fs::read_to_string(&path).map_err(|err| OntologyRegistryError::UnableToRegister {
+                reason: format!("Unable to read manifest '{}': {}", path.display(), err),
+            })?;
+        serde_json::from_str(&raw).map_err(|err| OntologyRegistryError::UnableToRegister {
+            reason: format!("Unable to parse manifest '{}': {}", path.display(), err),
+        })
+    }
+
+    /// Writes the manifest of `root` to a temporary file first and renames it into place, so an
+    /// interrupted write never leaves a truncated manifest behind.
+    pub(crate) fn save(&self, root: &Path) -> Result<(), OntologyRegistryError> {
+        let path = root.join(MANIFEST_FILE_NAME);
+        let temp_path = root.join(format!("{MANIFEST_FILE_NAME}.tmp"));
+        let raw = serde_json::to_string_pretty(self).map_err(|err| {
+            OntologyRegistryError::UnableToRegister {
+                reason: err.to_string(),
+            }
+        })?;
+        fs::write(&temp_path, raw)
+            .and_then(|()| fs::rename(&temp_path, &path))
+            .map_err(|err| {
+                let _ = fs::remove_file(&temp_path);
+                OntologyRegistryError::UnableToRegister {
+                    reason: format!("Unable to write manifest '{}': {}", path.display(), err),
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_save_replaces_manifest_without_leaving_temporary_file() {
+        let temp_dir = tempdir().unwrap();
+        let mut manifest = RegistryManifest::default();
+        manifest.save(temp_dir.path()).unwrap();
+        manifest
+            .checksums
+            .insert("hp@2024-04-26.obo".to_string(), "abc".to_string());
+
+        manifest.save(temp_dir.path()).unwrap();
+
+        let loaded = RegistryManifest::load(temp_dir.path()).unwrap();
+        assert_eq!(loaded.checksums, manifest.checksums);
+        let names: Vec<_> = fs::read_dir(temp_dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names, vec![MANIFEST_FILE_NAME]);
+    }
+}
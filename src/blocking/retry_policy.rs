@@ -0,0 +1,212 @@
+use crate::error::OntologyRegistryError;
+use reqwest::StatusCode;
+use reqwest::blocking::{RequestBuilder, Response};
+use reqwest::header::RETRY_AFTER;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// Describes how often and how patiently the HTTP providers retry a failed request.
+///
+/// The providers configure it through their `with_retries`, `with_rate_limit_retries` and
+/// `with_max_retry_after` builders. The default policy performs no retries.
+///
+/// # Backoff
+/// Connection errors, timeouts, `408`, `429` and `5xx` responses are retried up to
+/// `max_retries` times, each retry waiting `base_delay * 2^attempt` first.
+///
+/// # Rate limits
+/// A `429 Too Many Requests` carrying a `Retry-After` header, given in seconds or as an HTTP
+/// date, is retried after the time the server asked for, up to `max_rate_limit_retries` times.
+/// These retries are counted separately from the backoff retries.
+///
+/// A server asking to wait longer than `max_retry_after`, 60 seconds by default, is not
+/// waited for: the request fails with `OntologyRegistryError::ProvidingOntology` instead of
+/// blocking the caller.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    base_delay: Duration,
+    max_rate_limit_retries: u32,
+    max_retry_after: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 0,
+            base_delay: Duration::ZERO,
+            max_rate_limit_retries: 0,
+            max_retry_after: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Sets the exponential backoff used for connection errors and retryable status codes.
+    pub(crate) fn with_backoff(mut self, max_retries: u32, base_delay: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Sets how often a rate-limited request is retried after its `Retry-After` delay.
+    pub(crate) fn with_rate_limit_retries(mut self, max_rate_limit_retries: u32) -> Self {
+        self.max_rate_limit_retries = max_rate_limit_retries;
+        self
+    }
+
+    /// Sets the longest `Retry-After` delay that is waited for.
+    pub(crate) fn with_max_retry_after(mut self, max_retry_after: Duration) -> Self {
+        self.max_retry_after = max_retry_after;
+        self
+    }
+
+    /// Sends the request built by `build_request`, retrying on connection errors and
+    /// retryable status codes. Returns the last response or error once retries are exhausted.
+    ///
+    /// # Errors
+    /// Fails with `OntologyRegistryError::ProvidingOntology` if a rate-limited response asks
+    /// to wait longer than `max_retry_after`.
+    pub(crate) fn send(
+        &self,
+        build_request: impl Fn() -> RequestBuilder,
+    ) -> Result<Result<Response, reqwest::Error>, OntologyRegistryError> {
+        let mut attempt = 0;
+        let mut rate_limit_attempt = 0;
+
+        loop {
+            let result = build_request().send();
+
+            if let Ok(response) = &result
+                && response.status() == StatusCode::TOO_MANY_REQUESTS
+                && rate_limit_attempt < self.max_rate_limit_retries
+                && let Some(delay) = Self::retry_after(response)
+            {
+                if delay > self.max_retry_after {
+                    return Err(OntologyRegistryError::ProvidingOntology {
+                        reason: format!(
+                            "{} asked to retry after {}s, longer than the allowed {}s",
+                            response.url(),
+                            delay.as_secs(),
+                            self.max_retry_after.as_secs()
+                        ),
+                    });
+                }
+                thread::sleep(delay);
+                rate_limit_attempt += 1;
+                continue;
+            }
+
+            let retryable = match &result {
+                Ok(response) => Self::is_retryable_status(response.status()),
+                Err(err) => err.is_connect() || err.is_timeout() || err.is_request(),
+            };
+
+            if !retryable || attempt >= self.max_retries {
+                return Ok(result);
+            }
+
+            thread::sleep(self.delay_for(attempt));
+            attempt += 1;
+        }
+    }
+
+    /// Returns the delay requested by the `Retry-After` header of `response`, if any.
+    fn retry_after(response: &Response) -> Option<Duration> {
+        let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?;
+        Self::parse_retry_after(value, SystemTime::now())
+    }
+
+    /// Parses a `Retry-After` value given either in seconds or as an HTTP date. A date in the
+    /// past means no delay.
+    fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+        let value = value.trim();
+        if let Ok(seconds) = value.parse::<u64>() {
+            return Some(Duration::from_secs(seconds));
+        }
+
+        let retry_at = httpdate::parse_http_date(value).ok()?;
+        Some(retry_at.duration_since(now).unwrap_or(Duration::ZERO))
+    }
+
+    fn delay_for(&self, attempt: u32) -> Duration {
+        self.base_delay.saturating_mul(2u32.saturating_pow(attempt))
+    }
+
+    fn is_retryable_status(status: StatusCode) -> bool {
+        status.is_server_error()
+            || status == StatusCode::REQUEST_TIMEOUT
+            || status == StatusCode::TOO_MANY_REQUESTS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay_grows_exponentially() {
+        let policy = RetryPolicy::default().with_backoff(3, Duration::from_millis(100));
+
+        assert_eq!(policy.delay_for(0), Duration::from_millis(100));
+        assert_eq!(policy.delay_for(1), Duration::from_millis(200));
+        assert_eq!(policy.delay_for(2), Duration::from_millis(400));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = httpdate::parse_http_date("Wed, 21 Oct 2015 07:28:00 GMT").unwrap();
+
+        assert_eq!(
+            RetryPolicy::parse_retry_after("120", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            RetryPolicy::parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            RetryPolicy::parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(RetryPolicy::parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn test_retry_after_above_limit_fails() {
+        let mut server = mockito::Server::new();
+        let rate_limited = server
+            .mock("GET", "/hp.obo")
+            .with_status(429)
+            .with_header("retry-after", "3600")
+            .expect(1)
+            .create();
+        let client = reqwest::blocking::Client::new();
+        let url = format!("{}/hp.obo", server.url());
+        let policy = RetryPolicy::default()
+            .with_rate_limit_retries(2)
+            .with_max_retry_after(Duration::from_secs(10));
+
+        let result = policy.send(|| client.get(&url));
+
+        rate_limited.assert();
+        match result {
+            Err(OntologyRegistryError::ProvidingOntology { reason }) => {
+                assert!(reason.contains("3600s"), "{reason}");
+            }
+            _ => panic!("Expected ProvidingOntology"),
+        }
+    }
+
+    #[test]
+    fn test_retryable_status() {
+        assert!(RetryPolicy::is_retryable_status(
+            StatusCode::SERVICE_UNAVAILABLE
+        ));
+        assert!(RetryPolicy::is_retryable_status(
+            StatusCode::TOO_MANY_REQUESTS
+        ));
+        assert!(!RetryPolicy::is_retryable_status(StatusCode::NOT_FOUND));
+        assert!(!RetryPolicy::is_retryable_status(StatusCode::OK));
+    }
+}
@@ -0,0 +1,75 @@
+use crate::error::OntologyRegistryError;
+use std::io::{self, Read};
+
+/// Wraps a download stream and fails once more than `limit` bytes have been read.
+///
+/// The error is an `io::Error` wrapping `OntologyRegistryError::DownloadTooLarge`, which the
+/// registry unwraps again when the stream is copied to disk.
+pub(crate) struct SizeLimitedReader<R> {
+    inner: R,
+    remaining: u64,
+    limit: u64,
+    url: String,
+}
+
+impl<R> SizeLimitedReader<R> {
+    pub(crate) fn new(inner: R, limit: Option<u64>, url: &str) -> Self {
+        let limit = limit.unwrap_or(u64::MAX);
+        SizeLimitedReader {
+            inner,
+            remaining: limit,
+            limit,
+            url: url.to_string(),
+        }
+    }
+
+    /// Returns the wrapped reader.
+    pub(crate) fn get_ref(&self) -> &R {
+        &self.inner
+    }
+}
+
+impl<R: Read> Read for SizeLimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // Read one byte past the limit so a body of exactly `limit` bytes is still accepted.
+        let max_read = buf.len().min(self.remaining.saturating_add(1) as usize);
+        let read = self.inner.read(&mut buf[..max_read])?;
+
+        if read as u64 > self.remaining {
+            return Err(io::Error::other(OntologyRegistryError::DownloadTooLarge {
+                url: self.url.clone(),
+                limit: self.limit,
+            }));
+        }
+
+        self.remaining -= read as u64;
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accepts_body_at_limit() {
+        let mut reader = SizeLimitedReader::new(&b"12345"[..], Some(5), "http://example.org");
+        let mut content = Vec::new();
+
+        reader.read_to_end(&mut content).unwrap();
+
+        assert_eq!(content, b"12345");
+    }
+
+    #[test]
+    fn test_rejects_body_over_limit() {
+        let mut reader = SizeLimitedReader::new(&b"123456"[..], Some(5), "http://example.org");
+
+        let err = reader.read_to_end(&mut Vec::new()).unwrap_err();
+
+        assert!(matches!(
+            OntologyRegistryError::from_stream_error(err),
+            OntologyRegistryError::DownloadTooLarge { limit: 5, .. }
+        ));
+    }
+}
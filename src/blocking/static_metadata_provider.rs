@@ -0,0 +1,146 @@
+use crate::error::OntologyRegistryError;
+use crate::ontology_metadata::OntologyMetadata;
+use crate::traits::OntologyMetadataProviding;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+/// Serves ontology metadata from a fixed map instead of a remote registry, e.g. for tests,
+/// offline runs or pinned builds.
+///
+/// The map is passed in directly or loaded from a JSON object keyed by ontology id, whose
+/// values have the shape of [`OntologyMetadata`]:
+///
+/// ```json
+/// {"hp": {"ontology_id": "hp", "version": "2024-04-26", "obo_file_location": null}}
+/// ```
+///
+/// Ids are looked up exactly first and case-insensitively second. Unknown ids fail with
+/// `OntologyRegistryError::ProvidingMetadata`.
+#[derive(Clone, Debug, Default)]
+pub struct StaticMetadataProvider {
+    metadata: HashMap<String, OntologyMetadata>,
+}
+
+impl StaticMetadataProvider {
+    pub fn new(metadata: HashMap<String, OntologyMetadata>) -> Self {
+        StaticMetadataProvider { metadata }
+    }
+
+    /// Loads the metadata map from the JSON file at `path`.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, OntologyRegistryError> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|err| OntologyRegistryError::ProvidingMetadata {
+            reason: format!("Unable to open '{}': {}", path.display(), err),
+        })?;
+        Self::from_reader(BufReader::new(file))
+    }
+
+    /// Loads the metadata map from JSON read from `reader`.
+    pub fn from_reader(reader: impl Read) -> Result<Self, OntologyRegistryError> {
+        let metadata = serde_json::from_reader(reader).map_err(|err| {
+            OntologyRegistryError::ProvidingMetadata {
+                reason: format!("Invalid metadata JSON: {err}"),
+            }
+        })?;
+        Ok(Self::new(metadata))
+    }
+
+    /// Adds or replaces the metadata of `metadata.ontology_id`.
+    pub fn with_metadata(mut self, metadata: OntologyMetadata) -> Self {
+        self.metadata.insert(metadata.ontology_id.clone(), metadata);
+        self
+    }
+}
+
+impl OntologyMetadataProviding for StaticMetadataProvider {
+    fn provide_metadata(
+        &self,
+        ontology_id: &str,
+    ) -> Result<OntologyMetadata, OntologyRegistryError> {
+        self.metadata
+            .get(ontology_id)
+            .or_else(|| {
+                self.metadata
+                    .iter()
+                    .find(|(id, _)| id.eq_ignore_ascii_case(ontology_id))
+                    .map(|(_, metadata)| metadata)
+            })
+            .cloned()
+            .ok_or_else(|| OntologyRegistryError::ProvidingMetadata {
+                reason: format!("No metadata for {ontology_id}"),
+            })
+    }
+
+    /// Returns the ids of all ontologies in the map, sorted.
+    fn list_available(&self) -> Result<Vec<String>, OntologyRegistryError> {
+        let mut ids: Vec<String> = self.metadata.keys().cloned().collect();
+        ids.sort();
+        Ok(ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn fixture() -> &'static str {
+        r#"{
+            "hp": {
+                "ontology_id": "hp",
+                "version": "2024-04-26",
+                "json_file_location": null,
+                "owl_file_location": null,
+                "obo_file_location": "http://purl.obolibrary.org/obo/hp/releases/2024-04-26/hp.obo",
+                "title": "Human Phenotype Ontology"
+            },
+            "mondo": {
+                "ontology_id": "mondo",
+                "version": "2024-01-04",
+                "json_file_location": null,
+                "owl_file_location": null,
+                "obo_file_location": null,
+                "title": null
+            }
+        }"#
+    }
+
+    #[test]
+    fn test_resolves_versions_from_fixture_file() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("metadata.json");
+        fs::write(&path, fixture()).unwrap();
+
+        let provider = StaticMetadataProvider::from_file(&path).unwrap();
+
+        let hp = provider.provide_metadata("HP").unwrap();
+        assert_eq!(hp.version, "2024-04-26");
+        assert_eq!(
+            hp.obo_file_location.as_deref(),
+            Some("http://purl.obolibrary.org/obo/hp/releases/2024-04-26/hp.obo")
+        );
+        assert_eq!(
+            provider.provide_metadata("mondo").unwrap().version,
+            "2024-01-04"
+        );
+        assert_eq!(provider.list_available().unwrap(), vec!["hp", "mondo"]);
+    }
+
+    #[test]
+    fn test_unknown_id_and_invalid_json() {
+        let provider = StaticMetadataProvider::from_reader(fixture().as_bytes()).unwrap();
+
+        assert!(matches!(
+            provider.provide_metadata("go"),
+            Err(OntologyRegistryError::ProvidingMetadata { .. })
+        ));
+        assert!(matches!(
+            StaticMetadataProvider::from_reader(&b"[]"[..]),
+            Err(OntologyRegistryError::ProvidingMetadata { .. })
+        ));
+        assert!(StaticMetadataProvider::from_file("/nonexistent/metadata.json").is_err());
+    }
+}
@@ -0,0 +1,292 @@
+use crate::Version;
+use crate::blocking::provider_client::ProviderClient;
+use crate::blocking::retry_policy::RetryPolicy;
+use crate::blocking::{DEFAULT_USER_AGENT, redirect_error, unfollowed_redirect};
+use crate::error::OntologyRegistryError;
+use crate::traits::OntologyProviding;
+use reqwest::blocking::{Client, RequestBuilder};
+use reqwest::header::USER_AGENT;
+use reqwest::{StatusCode, Url};
+use std::io::Read;
+use std::time::Duration;
+
+/// Downloads ontology files from complete URLs instead of assembling one from the id and
+/// version, e.g. download locations taken from metadata, a manifest or user configuration.
+///
+/// `provide_ontology` treats its `file_name` as the absolute URL to fetch; anything else is
+/// rejected without a request. A `404 Not Found` is reported as `NotFound` for the requested
+/// id and version, any other unsuccessful status as `HttpError`.
+///
+/// ```rust,no_run
+/// use ontology_registry::blocking::url_ontology_provider::UrlOntologyProvider;
+/// use ontology_registry::traits::OntologyProviding;
+/// use ontology_registry::Version;
+///
+/// let provider = UrlOntologyProvider::default();
+/// let version = Version::from("2024-04-26");
+/// let _reader = provider.provide_ontology(
+///     "hp",
+///     "https://github.com/obophenotype/human-phenotype-ontology/releases/download/v2024-04-26/hp.obo",
+///     &version,
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct UrlOntologyProvider {
+    client: ProviderClient,
+    retry_policy: RetryPolicy,
+    user_agent: String,
+    timeout: Option<Duration>,
+}
+
+impl Default for UrlOntologyProvider {
+    fn default() -> Self {
+        UrlOntologyProvider {
+            client: ProviderClient::default(),
+            retry_policy: RetryPolicy::default(),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            timeout: None,
+        }
+    }
+}
+
+impl UrlOntologyProvider {
+    /// Uses `client` for all requests, regardless of the redirect settings; see
+    /// [`ProviderClient`].
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = self.client.with_client(client);
+        self
+    }
+
+    /// Sets the User-Agent header sent with every request. Defaults to `ontology-registry/<version>`.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Aborts a request that has not completed within `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Follows at most `limit` redirects per request instead of reqwest's default of 10. A
+    /// limit of `0` disables following redirects.
+    ///
+    /// Exceeding the limit fails with `OntologyRegistryError::ProvidingOntology`; with
+    /// redirects disabled, so does any redirect, naming its `Location`. Not applied to a client
+    /// passed to [`Self::with_client`]; see [`ProviderClient`].
+    pub fn with_redirect_policy(mut self, limit: usize) -> Self {
+        self.client = self.client.with_redirect_limit(limit);
+        self
+    }
+
+    /// Retries failed requests up to `max_retries` times with exponential backoff starting at `base_delay`.
+    pub fn with_retries(mut self, max_retries: u32, base_delay: Duration) -> Self {
+        self.retry_policy = self.retry_policy.with_backoff(max_retries, base_delay);
+        self
+    }
+
+    /// Retries a request answered with `429 Too Many Requests` up to `max_retries` times after
+    /// its `Retry-After` delay. Disabled by default; see [`RetryPolicy`].
+    pub fn with_rate_limit_retries(mut self, max_retries: u32) -> Self {
+        self.retry_policy = self.retry_policy.with_rate_limit_retries(max_retries);
+        self
+    }
+
+    /// Fails instead of waiting when a `Retry-After` delay exceeds `max_retry_after`. Defaults
+    /// to 60 seconds; see [`RetryPolicy`].
+    pub fn with_max_retry_after(mut self, max_retry_after: Duration) -> Self {
+        self.retry_policy = self.retry_policy.with_max_retry_after(max_retry_after);
+        self
+    }
+
+    fn request(&self, url: &Url) -> RequestBuilder {
+        let request = self
+            .client
+            .get(url.clone())
+            .header(USER_AGENT, &self.user_agent);
+        match self.timeout {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        }
+    }
+}
+
+impl OntologyProviding for UrlOntologyProvider {
+    fn provide_ontology(
+        &self,
+        ontology_id: &str,
+        file_name: &str,
+        version: &Version,
+    ) -> Result<impl Read, OntologyRegistryError> {
+        let url =
+            Url::parse(file_name).map_err(|err| OntologyRegistryError::ProvidingOntology {
+                reason: format!("{file_name} is not a URL: {err}"),
+            })?;
+
+        let response = match self.retry_policy.send(|| self.request(&url))? {
+            Ok(response) => response,
+            Err(err) if err.is_timeout() => {
+                return Err(OntologyRegistryError::ProvidingOntology {
+                    reason: format!("Request timed out for {url}"),
+                });
+            }
+            Err(err) => {
+                return Err(
+                    redirect_error(&err, url.as_str(), self.client.redirect_limit())
+                        .unwrap_or_else(|| err.into()),
+                );
+            }
+        };
+        if let Some(err) = unfollowed_redirect(&response, url.as_str()) {
+            return Err(err);
+        }
+
+        match response.status() {
+            status if status.is_success() => Ok(response),
+            StatusCode::NOT_FOUND => Err(OntologyRegistryError::NotFound {
+                ontology_id: ontology_id.to_string(),
+                version: version.to_string(),
+            }),
+            status => Err(OntologyRegistryError::HttpError {
+                status: status.as_u16(),
+                url: url.to_string(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Server;
+
+    #[test]
+    fn test_provide_ontology_fetches_url() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/custom/layout/hp.obo")
+            .match_header("user-agent", "test-agent")
+            .with_status(200)
+            .with_body("hp content")
+            .create();
+        let provider = UrlOntologyProvider::default().with_user_agent("test-agent");
+        let url = format!("{}/custom/layout/hp.obo", server.url());
+
+        let mut reader = provider
+            .provide_ontology("hp", &url, &Version::Latest)
+            .unwrap();
+
+        let mut content = String::new();
+        reader.read_to_string(&mut content).unwrap();
+        mock.assert();
+        assert_eq!(content, "hp content");
+    }
+
+    #[test]
+    fn test_provide_ontology_not_found() {
+        let mut server = Server::new();
+        let mock = server.mock("GET", "/missing.obo").with_status(404).create();
+        let provider = UrlOntologyProvider::default();
+        let url = format!("{}/missing.obo", server.url());
+        let version = Version::from("2024-04-26");
+
+        let result = provider.provide_ontology("hp", &url, &version);
+
+        mock.assert();
+        match result {
+            Err(OntologyRegistryError::NotFound {
+                ontology_id,
+                version,
+            }) => {
+                assert_eq!(ontology_id, "hp");
+                assert_eq!(version, "2024-04-26");
+            }
+            _ => panic!("expected NotFound"),
+        }
+    }
+
+    #[test]
+    fn test_provide_ontology_maps_server_error() {
+        let mut server = Server::new();
+        let _mock = server.mock("GET", "/hp.obo").with_status(500).create();
+        let provider = UrlOntologyProvider::default();
+        let url = format!("{}/hp.obo", server.url());
+
+        let result = provider.provide_ontology("hp", &url, &Version::Latest);
+
+        assert!(matches!(
+            result,
+            Err(OntologyRegistryError::HttpError { status: 500, .. })
+        ));
+    }
+
+    #[test]
+    fn test_redirect_limit() {
+        let mut server = Server::new();
+        let _redirect = server
+            .mock("GET", "/hp.obo")
+            .with_status(302)
+            .with_header("location", "/mirror/hp.obo")
+            .create();
+        let _target = server
+            .mock("GET", "/mirror/hp.obo")
+            .with_status(200)
+            .with_body("hp content")
+            .create();
+        let url = format!("{}/hp.obo", server.url());
+
+        let following = UrlOntologyProvider::default().with_redirect_policy(1);
+        let not_following = UrlOntologyProvider::default().with_redirect_policy(0);
+
+        let followed = following.provide_ontology("hp", &url, &Version::Latest);
+        let disabled = not_following.provide_ontology("hp", &url, &Version::Latest);
+
+        assert!(followed.is_ok());
+        match disabled {
+            Err(OntologyRegistryError::ProvidingOntology { reason }) => {
+                assert!(reason.contains("/mirror/hp.obo"), "{reason}");
+            }
+            _ => panic!("Expected ProvidingOntology"),
+        }
+    }
+
+    #[test]
+    fn test_with_client_takes_precedence_over_redirect_policy() {
+        let mut server = Server::new();
+        let _redirect = server
+            .mock("GET", "/hp.obo")
+            .with_status(302)
+            .with_header("location", "/mirror/hp.obo")
+            .create();
+        let target = server
+            .mock("GET", "/mirror/hp.obo")
+            .with_status(200)
+            .with_body("hp content")
+            .create();
+        let url = format!("{}/hp.obo", server.url());
+
+        let provider = UrlOntologyProvider::default()
+            .with_redirect_policy(0)
+            .with_client(Client::new());
+
+        assert!(
+            provider
+                .provide_ontology("hp", &url, &Version::Latest)
+                .is_ok()
+        );
+        target.assert();
+    }
+
+    #[test]
+    fn test_provide_ontology_rejects_file_name() {
+        let provider = UrlOntologyProvider::default();
+
+        let result = provider.provide_ontology("hp", "hp.obo", &Version::Latest);
+
+        assert!(matches!(
+            result,
+            Err(OntologyRegistryError::ProvidingOntology { .. })
+        ));
+    }
+}
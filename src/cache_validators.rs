@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+/// HTTP cache validators of a previously downloaded ontology file.
+///
+/// Sent back to the server as `If-None-Match` and `If-Modified-Since`, so an unchanged release
+/// is answered with `304 Not Modified` instead of being transferred again.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheValidators {
+    /// Value of the `ETag` response header.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    /// Value of the `Last-Modified` response header.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+}
+
+impl CacheValidators {
+    /// Returns `true` if neither validator is known.
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
+/// Outcome of a conditional download.
+#[derive(Debug)]
+pub enum ConditionalFetch<R> {
+    /// The content changed (or no validators were sent) and is available from `reader`.
+    Modified {
+        reader: R,
+        /// Validators to send with the next request for the same file.
+        validators: CacheValidators,
+        /// Size of the content in bytes, if announced by the source (e.g. `Content-Length`).
+        content_length: Option<u64>,
+    },
+    /// The content is unchanged since the validators were issued.
+    NotModified,
+}
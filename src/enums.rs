@@ -1,12 +1,22 @@
 use crate::OntologyRegistryError;
-use serde::{Deserialize, Serialize};
+use crate::RegistryKey;
+use crate::traits::FileNamingStrategy;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt::{Display, Formatter};
 
-#[derive(Debug, PartialEq, Eq, Clone, Hash, Default, PartialOrd, Ord, Serialize, Deserialize)]
+/// Serialized as the plain version string, with `Version::Latest` as `"latest"` and
+/// `Version::Unversioned` as `"unversioned"`.
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Default, PartialOrd, Ord)]
 pub enum Version {
     #[default]
     Latest,
     Declared(String),
+    /// Whatever the provider currently serves, stored without a version, e.g. as `mondo.owl`.
+    ///
+    /// Unlike `Latest`, which pins the release current at registration time under its
+    /// version, an unversioned file is never tied to a release. Once registered, it is served
+    /// from the registry until it is refreshed or unregistered.
+    Unversioned,
 }
 
 impl From<&str> for Version {
@@ -20,39 +30,87 @@ impl Display for Version {
         match self {
             Version::Latest => f.write_str("latest"),
             Version::Declared(v) => f.write_str(v),
+            Version::Unversioned => f.write_str("unversioned"),
         }
     }
 }
 
+impl Serialize for Version {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        /// Also accepts the form written by earlier releases (`"Latest"` and
+        /// `{"Declared": "<version>"}`), e.g. in existing registry manifests.
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Plain(String),
+            Tagged {
+                #[serde(rename = "Declared")]
+                declared: String,
+            },
+        }
+
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Plain(version) if version == "latest" || version == "Latest" => Version::Latest,
+            Repr::Plain(version) if version == "unversioned" => Version::Unversioned,
+            Repr::Plain(version) => Version::Declared(version),
+            Repr::Tagged { declared } => Version::Declared(declared),
+        })
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Copy, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum FileType {
     Json,
     Obo,
     Owl,
+    /// Turtle, the RDF serialization used by many RDF-native ontologies.
+    Ttl,
 }
 
 impl FileType {
+    /// Every supported format, in declaration order.
+    pub const ALL: &[FileType] = &[FileType::Json, FileType::Obo, FileType::Owl, FileType::Ttl];
+
     pub fn as_file_ending(&self) -> &'static str {
         match self {
             FileType::Json => ".json",
             FileType::Obo => ".obo",
             FileType::Owl => ".owl",
+            FileType::Ttl => ".ttl",
         }
     }
+    /// Parses a file ending such as `.obo`.
     pub fn from_file_ending(filename: &str) -> Result<FileType, OntologyRegistryError> {
         match filename {
             f if f == FileType::Json.as_file_ending() => Ok(FileType::Json),
             f if f == FileType::Obo.as_file_ending() => Ok(FileType::Obo),
             f if f == FileType::Owl.as_file_ending() => Ok(FileType::Owl),
+            f if f == FileType::Ttl.as_file_ending() => Ok(FileType::Ttl),
             _ => Err(OntologyRegistryError::CantParseFileFormat {
                 raw_format: filename.to_string(),
             }),
         }
     }
 
+    /// Returns the media type servers use for this format, e.g. for an `Accept` header.
+    pub fn media_type(&self) -> &'static str {
+        match self {
+            FileType::Json => "application/json",
+            FileType::Obo => "text/obo",
+            FileType::Owl => "application/rdf+xml",
+            FileType::Ttl => "text/turtle",
+        }
+    }
+
     pub fn all() -> Vec<FileType> {
-        vec![FileType::Json, FileType::Obo, FileType::Owl]
+        FileType::ALL.to_vec()
     }
 }
 
@@ -62,6 +120,59 @@ impl Display for FileType {
     }
 }
 
+/// How a `FileSystemOntologyRegistry` arranges ontology files below its root directory.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default, Serialize, Deserialize)]
+pub enum RegistryLayout {
+    /// All files side by side, e.g. `<root>/mondo@2024-01-01.json`. Unversioned files have no
+    /// version segment, e.g. `<root>/mondo.json`.
+    #[default]
+    Flat,
+    /// One directory per ontology, e.g. `<root>/mondo/2024-01-01.json`. Unversioned files are
+    /// stored as e.g. `<root>/mondo/unversioned.json`.
+    Nested,
+}
+
+impl FileNamingStrategy for RegistryLayout {
+    fn file_name(&self, ontology_id: &str, version: &str, file_type: &FileType) -> String {
+        let ontology_id = ontology_id.to_lowercase();
+        let file_ending = file_type.as_file_ending();
+        match self {
+            RegistryLayout::Flat => format!("{ontology_id}@{version}{file_ending}"),
+            RegistryLayout::Nested => format!("{ontology_id}/{version}{file_ending}"),
+        }
+    }
+
+    fn parse_file_name(&self, file_name: &str) -> Result<RegistryKey, OntologyRegistryError> {
+        match self {
+            RegistryLayout::Flat => RegistryKey::from_file_name(file_name),
+            RegistryLayout::Nested => {
+                let (ontology_id, release) = file_name.split_once('/').ok_or_else(|| {
+                    OntologyRegistryError::CantParseRegistryKey {
+                        raw_key: file_name.to_string(),
+                    }
+                })?;
+                RegistryKey::from_file_name(&format!("{ontology_id}@{release}"))
+            }
+        }
+    }
+
+    fn unversioned_file_name(&self, ontology_id: &str, file_type: &FileType) -> String {
+        match self {
+            RegistryLayout::Flat => {
+                RegistryKey::new(ontology_id, Version::Unversioned, *file_type).as_file_name()
+            }
+            RegistryLayout::Nested => self.file_name(ontology_id, "unversioned", file_type),
+        }
+    }
+
+    fn directory_depth(&self) -> usize {
+        match self {
+            RegistryLayout::Flat => 0,
+            RegistryLayout::Nested => 1,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Copy, Hash, Eq, Serialize, Deserialize)]
 /// This enum contains ontologies that have been validated to work with ontology registry. Others might also work.
 pub enum SupportedOntology {
@@ -100,3 +211,85 @@ impl From<SupportedOntology> for String {
         value.to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_file_endings_are_unique() {
+        let file_endings: HashSet<&str> =
+            FileType::ALL.iter().map(FileType::as_file_ending).collect();
+
+        assert_eq!(file_endings.len(), FileType::ALL.len());
+        for file_type in FileType::ALL {
+            assert_eq!(
+                FileType::from_file_ending(file_type.as_file_ending()).unwrap(),
+                *file_type
+            );
+        }
+        assert!(FileType::from_file_ending(".owl.gz").is_err());
+        assert!(FileType::from_file_ending(".gz").is_err());
+    }
+
+    #[test]
+    fn test_file_types_in_hash_set() {
+        let mut file_types = HashSet::new();
+        for file_type in FileType::ALL {
+            assert!(file_types.insert(*file_type));
+        }
+
+        assert!(!file_types.insert(FileType::Obo));
+        assert_eq!(file_types.len(), FileType::ALL.len());
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Request {
+        version: Version,
+        file_type: FileType,
+    }
+
+    #[test]
+    fn test_version_serde_round_trip() {
+        for (json, version) in [
+            (r#"{"version":"latest","file_type":"obo"}"#, Version::Latest),
+            (
+                r#"{"version":"2024-01-04","file_type":"obo"}"#,
+                Version::from("2024-01-04"),
+            ),
+        ] {
+            let request: Request = serde_json::from_str(json).unwrap();
+            assert_eq!(
+                request,
+                Request {
+                    version,
+                    file_type: FileType::Obo
+                }
+            );
+            assert_eq!(serde_json::to_string(&request).unwrap(), json);
+        }
+    }
+
+    #[test]
+    fn test_version_deserializes_legacy_form() {
+        assert_eq!(
+            serde_json::from_str::<Version>(r#"{"Declared":"2024-01-04"}"#).unwrap(),
+            Version::from("2024-01-04")
+        );
+        assert_eq!(
+            serde_json::from_str::<Version>(r#""Latest""#).unwrap(),
+            Version::Latest
+        );
+    }
+
+    #[test]
+    fn test_file_type_serde() {
+        for file_type in FileType::ALL {
+            let json = serde_json::to_string(file_type).unwrap();
+            assert_eq!(json, format!("\"{}\"", &file_type.as_file_ending()[1..]));
+            assert_eq!(serde_json::from_str::<FileType>(&json).unwrap(), *file_type);
+        }
+        assert!(serde_json::from_str::<FileType>(r#""rdf""#).is_err());
+    }
+}
@@ -4,7 +4,7 @@ use thiserror::Error;
 pub enum OntologyRegistryError {
     #[error("Unable to provide Metadata: {reason}")]
     ProvidingMetadata { reason: String },
-    #[error("Unable to provide Metadata: {reason}")]
+    #[error("Unable to provide Ontology: {reason}")]
     ProvidingOntology { reason: String },
     #[error("Unable to create registry")]
     NoRegistry,
@@ -14,6 +14,131 @@ pub enum OntologyRegistryError {
     UnableToUnregister { reason: String },
     #[error("Expected format: ontology_id@version.file_type. Found: {raw_key}")]
     CantParseRegistryKey { raw_key: String },
-    #[error("Expected format: .json, .owl. obo. Found: {raw_format}")]
+    #[error("Expected format: .json, .owl, .obo, .ttl. Found: {raw_format}")]
     CantParseFileFormat { raw_format: String },
+    #[error("Ontology {ontology_id} with version {version} not found")]
+    NotFound {
+        ontology_id: String,
+        version: String,
+    },
+    #[error("HTTP error {status} for {url}")]
+    HttpError { status: u16, url: String },
+    #[error("Unable to read OBO header: {reason}")]
+    ReadingOboHeader { reason: String },
+    #[error("Download of {url} exceeds the limit of {limit} bytes")]
+    DownloadTooLarge { url: String, limit: u64 },
+    #[error("File name {file_name} would leave its place in the registry directory")]
+    UnsafeFileName { file_name: String },
+    #[error(
+        "Ontology {ontology_id} with version {version} is not available while the registry is offline"
+    )]
+    Offline {
+        ontology_id: String,
+        version: String,
+    },
+    #[error("Registry path {path} exists but is not a directory")]
+    NotADirectory { path: String },
+    #[error("Registration was cancelled")]
+    Cancelled,
+    #[error("Invalid ontology id {ontology_id:?}: {reason}")]
+    InvalidOntologyId { ontology_id: String, reason: String },
+    #[error("Invalid proxy {proxy_url}: {reason}")]
+    InvalidProxy { proxy_url: String, reason: String },
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+    #[error("I/O error at {path}")]
+    IoAt {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[cfg(feature = "http")]
+    #[error("HTTP request failed: {0}")]
+    Http(#[from] reqwest::Error),
+}
+
+impl OntologyRegistryError {
+    /// Wraps an I/O error with the path it occurred at.
+    #[cfg(feature = "blocking")]
+    pub(crate) fn io_at(path: impl AsRef<std::path::Path>, source: std::io::Error) -> Self {
+        OntologyRegistryError::IoAt {
+            path: path.as_ref().display().to_string(),
+            source,
+        }
+    }
+
+    /// Converts an error raised while reading a download stream.
+    ///
+    /// Readers handed out by providers can only fail with `io::Error`. If such an error wraps an
+    /// `OntologyRegistryError`, that error is returned instead of `Io`.
+    #[cfg(feature = "blocking")]
+    pub(crate) fn from_stream_error(err: std::io::Error) -> Self {
+        if err
+            .get_ref()
+            .is_some_and(|inner| inner.is::<OntologyRegistryError>())
+        {
+            let inner = err.into_inner().expect("checked above");
+            return *inner
+                .downcast::<OntologyRegistryError>()
+                .expect("checked above");
+        }
+        OntologyRegistryError::Io(err)
+    }
+}
+
+/// Lets entry points bound by `TryInto<OntologyId, Error: Into<OntologyRegistryError>>` accept
+/// an `OntologyId`, whose conversion into itself cannot fail.
+impl From<std::convert::Infallible> for OntologyRegistryError {
+    fn from(never: std::convert::Infallible) -> Self {
+        match never {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn test_providing_errors_display_differently() {
+        let metadata_err = OntologyRegistryError::ProvidingMetadata {
+            reason: "offline".to_string(),
+        };
+        let ontology_err = OntologyRegistryError::ProvidingOntology {
+            reason: "offline".to_string(),
+        };
+
+        assert_eq!(
+            metadata_err.to_string(),
+            "Unable to provide Metadata: offline"
+        );
+        assert_eq!(
+            ontology_err.to_string(),
+            "Unable to provide Ontology: offline"
+        );
+    }
+
+    #[test]
+    fn test_io_error_is_chained_as_source() {
+        let err = OntologyRegistryError::from(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            "read-only filesystem",
+        ));
+
+        assert!(matches!(err, OntologyRegistryError::Io(_)));
+        assert_eq!(err.to_string(), "I/O error");
+        assert_eq!(err.source().unwrap().to_string(), "read-only filesystem");
+    }
+
+    #[test]
+    #[cfg(feature = "blocking")]
+    fn test_io_error_names_path() {
+        let err = OntologyRegistryError::io_at(
+            "/registry/hp@2024-04-26.obo",
+            std::io::Error::new(std::io::ErrorKind::PermissionDenied, "read-only filesystem"),
+        );
+
+        assert_eq!(err.to_string(), "I/O error at /registry/hp@2024-04-26.obo");
+        assert_eq!(err.source().unwrap().to_string(), "read-only filesystem");
+    }
 }
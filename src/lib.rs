@@ -3,7 +3,7 @@
 //! A robust, thread-safe library for managing the lifecycle of biological ontologies.
 //!
 //! This crate provides a unified interface to resolve, download, cache, and load ontologies
-//! (such as Mondo, GO, NCIT) using standard formats (JSON, OBO, OWL). It is designed
+//! (such as Mondo, GO, NCIT) using standard formats (JSON, OBO, OWL, Turtle). It is designed
 //! with a modular architecture that separates metadata resolution, content provision,
 //! and local storage management.
 //!
@@ -23,12 +23,17 @@
 //!
 //! ```rust,no_run
 //! use std::path::PathBuf;
+//! # #[cfg(all(feature = "bioregistry", feature = "obolibrary"))]
 //! use ontology_registry::blocking::bio_registry_metadata_provider::BioRegistryMetadataProvider;
+//! # #[cfg(all(feature = "bioregistry", feature = "obolibrary"))]
 //! use ontology_registry::blocking::obolib_ontology_provider::OboLibraryProvider;
 //! use ontology_registry::blocking::file_system_ontology_registry::FileSystemOntologyRegistry;
 //! use ontology_registry::enums::{FileType, Version};
 //! use ontology_registry::traits::OntologyRegistration;
 //!
+//! # #[cfg(not(all(feature = "bioregistry", feature = "obolibrary")))]
+//! # fn main() {}
+//! # #[cfg(all(feature = "bioregistry", feature = "obolibrary"))]
 //! fn main() -> Result<(), Box<dyn std::error::Error>> {
 //!     // 1. Configure the storage location
 //!     use ontology_registry::{RegistryKey, SupportedOntology};
@@ -70,22 +75,78 @@
 //! ## Modules
 //!
 //! * [`blocking`]: Contains concrete implementations of the providers and registry for synchronous (blocking) operations.
+//! * [`ontology_id`]: The validated [`OntologyId`] newtype.
 //! * [`ontology_metadata`]: Structs representing ontology metadata.
+//! * [`cache_validators`]: HTTP validators used to skip downloads of unchanged releases.
+//! * [`obo_header`]: Parser for the header block of cached OBO files.
+//! * [`prelude`]: Re-exports of the commonly used types for `use ontology_registry::prelude::*`.
 //! * [`enums`]: Enumerations for `Version` strategies and `FileType` formats.
 //! * [`error`]: Crate-specific error types.
 //! * [`traits`]: The core definitions ensuring modularity and extensibility.
+//!
+//! ## Feature Flags
+//!
+//! * `blocking` (default): The [`blocking`] module with [`FileSystemOntologyRegistry`] and the
+//!   providers that need no network access.
+//! * `bioregistry` (default): [`BioRegistryMetadataProvider`].
+//! * `obolibrary` (default): [`OboLibraryProvider`] and [`ObolibMetadataProvider`].
+//! * `bioportal` (default): [`BioPortalMetadataProvider`].
+//! * `github` (default): [`GitHubReleaseProvider`].
+//! * `http`: The HTTP client shared by the providers above, enabled by each of them, and
+//!   [`UrlOntologyProvider`]. Enables `blocking`. Without it, `reqwest` is not compiled at all.
+//! * `gzip`, `zstd`, `bzip2`: The compression formats [`DecompressingProvider`] can inflate.
+//!   `gzip` also enables compressed storage in [`FileSystemOntologyRegistry`].
+//! * `otel`: Records registrations, cache hits/misses, errors and download statistics through the
+//!   OpenTelemetry metrics API.
 
+#[cfg(feature = "blocking")]
 pub mod blocking;
+pub mod cache_validators;
 pub mod enums;
 pub mod error;
+#[cfg(feature = "blocking")]
+mod metrics;
+pub mod obo_header;
+pub mod ontology_id;
 pub mod ontology_metadata;
+#[cfg(feature = "blocking")]
+pub mod prelude;
 pub mod registry_key;
 pub mod traits;
 
+#[cfg(feature = "bioregistry")]
 pub use blocking::bio_registry_metadata_provider::BioRegistryMetadataProvider;
-pub use blocking::file_system_ontology_registry::FileSystemOntologyRegistry;
-pub use blocking::obolib_ontology_provider::OboLibraryProvider;
+#[cfg(feature = "bioportal")]
+pub use blocking::bioportal_metadata_provider::BioPortalMetadataProvider;
+#[cfg(feature = "blocking")]
+pub use blocking::caching_metadata_provider::CachingMetadataProvider;
+#[cfg(feature = "blocking")]
+pub use blocking::decompressing_provider::DecompressingProvider;
+#[cfg(feature = "blocking")]
+pub use blocking::fallback_provider::{FallbackMetadataProvider, FallbackOntologyProvider};
+#[cfg(feature = "blocking")]
+pub use blocking::file_system_ontology_registry::{
+    FileSystemOntologyRegistry, LockEntry, ManifestEntry, RegisterPlan, RegisteredOntology,
+    VerifyReport, validate_not_empty_or_html,
+};
+#[cfg(feature = "blocking")]
+pub use blocking::file_system_ontology_registry_builder::FileSystemOntologyRegistryBuilder;
+#[cfg(feature = "github")]
+pub use blocking::github_release_provider::GitHubReleaseProvider;
+#[cfg(feature = "blocking")]
+pub use blocking::local_directory_provider::LocalDirectoryProvider;
+#[cfg(feature = "obolibrary")]
+pub use blocking::obolib_metadata_provider::ObolibMetadataProvider;
+#[cfg(feature = "obolibrary")]
+pub use blocking::obolib_ontology_provider::{OboLibraryProvider, RemoteFileInfo};
+#[cfg(feature = "blocking")]
+pub use blocking::static_metadata_provider::StaticMetadataProvider;
+#[cfg(feature = "http")]
+pub use blocking::url_ontology_provider::UrlOntologyProvider;
+pub use cache_validators::{CacheValidators, ConditionalFetch};
 pub use enums::*;
 pub use error::*;
+pub use obo_header::OboHeader;
+pub use ontology_id::OntologyId;
 pub use registry_key::RegistryKey;
 pub use traits::*;
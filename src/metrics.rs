@@ -0,0 +1,155 @@
+//! # Metrics
+//!
+//! Records registry activity through the OpenTelemetry metrics API when the `otel` feature is
+//! enabled. Without the feature every function is a no-op, so call sites need no `cfg` guards.
+//!
+//! Instruments are created from the global meter provider on first use. Install your meter
+//! provider via `opentelemetry::global::set_meter_provider` before the first registration.
+//!
+//! Recorded instruments:
+//!
+//! * `ontology_registry.registrations` (counter): ontologies downloaded and stored.
+//! * `ontology_registry.cache_hits` / `ontology_registry.cache_misses` (counters).
+//! * `ontology_registry.errors` (counter): failed operations, labelled by error `kind`.
+//! * `ontology_registry.download.duration` (histogram, seconds).
+//! * `ontology_registry.download.bytes` (histogram, bytes).
+
+use crate::RegistryKey;
+use crate::error::OntologyRegistryError;
+use std::time::Duration;
+
+#[cfg(feature = "otel")]
+mod otel {
+    use crate::RegistryKey;
+    use crate::error::OntologyRegistryError;
+    use opentelemetry::KeyValue;
+    use opentelemetry::global;
+    use opentelemetry::metrics::{Counter, Histogram};
+    use std::sync::OnceLock;
+
+    pub(super) struct Instruments {
+        pub(super) registrations: Counter<u64>,
+        pub(super) cache_hits: Counter<u64>,
+        pub(super) cache_misses: Counter<u64>,
+        pub(super) errors: Counter<u64>,
+        pub(super) download_duration: Histogram<f64>,
+        pub(super) download_bytes: Histogram<u64>,
+    }
+
+    pub(super) fn instruments() -> &'static Instruments {
+        static INSTRUMENTS: OnceLock<Instruments> = OnceLock::new();
+        INSTRUMENTS.get_or_init(|| {
+            let meter = global::meter("ontology-registry");
+            Instruments {
+                registrations: meter
+                    .u64_counter("ontology_registry.registrations")
+                    .with_description("Ontologies downloaded and stored in the registry")
+                    .build(),
+                cache_hits: meter
+                    .u64_counter("ontology_registry.cache_hits")
+                    .with_description("Registrations served from an existing local file")
+                    .build(),
+                cache_misses: meter
+                    .u64_counter("ontology_registry.cache_misses")
+                    .with_description("Registrations that required a download")
+                    .build(),
+                errors: meter
+                    .u64_counter("ontology_registry.errors")
+                    .with_description("Failed registry operations by error kind")
+                    .build(),
+                download_duration: meter
+                    .f64_histogram("ontology_registry.download.duration")
+                    .with_unit("s")
+                    .build(),
+                download_bytes: meter
+                    .u64_histogram("ontology_registry.download.bytes")
+                    .with_unit("By")
+                    .build(),
+            }
+        })
+    }
+
+    pub(super) fn key_attributes(registry_key: &RegistryKey) -> [KeyValue; 2] {
+        [
+            KeyValue::new("ontology_id", registry_key.ontology_id().to_string()),
+            KeyValue::new("file_type", registry_key.file_type().to_string()),
+        ]
+    }
+
+    pub(super) fn error_kind(err: &OntologyRegistryError) -> &'static str {
+        match err {
+            OntologyRegistryError::ProvidingMetadata { .. } => "providing_metadata",
+            OntologyRegistryError::ProvidingOntology { .. } => "providing_ontology",
+            OntologyRegistryError::NoRegistry => "no_registry",
+            OntologyRegistryError::UnableToRegister { .. } => "unable_to_register",
+            OntologyRegistryError::UnableToUnregister { .. } => "unable_to_unregister",
+            OntologyRegistryError::CantParseRegistryKey { .. } => "cant_parse_registry_key",
+            OntologyRegistryError::CantParseFileFormat { .. } => "cant_parse_file_format",
+            OntologyRegistryError::NotFound { .. } => "not_found",
+            OntologyRegistryError::HttpError { .. } => "http_error",
+            OntologyRegistryError::ReadingOboHeader { .. } => "reading_obo_header",
+            OntologyRegistryError::DownloadTooLarge { .. } => "download_too_large",
+            OntologyRegistryError::UnsafeFileName { .. } => "unsafe_file_name",
+            OntologyRegistryError::Offline { .. } => "offline",
+            OntologyRegistryError::NotADirectory { .. } => "not_a_directory",
+            OntologyRegistryError::Cancelled => "cancelled",
+            OntologyRegistryError::InvalidOntologyId { .. } => "invalid_ontology_id",
+            OntologyRegistryError::InvalidProxy { .. } => "invalid_proxy",
+            OntologyRegistryError::Io(_) | OntologyRegistryError::IoAt { .. } => "io",
+            #[cfg(feature = "http")]
+            OntologyRegistryError::Http(_) => "http",
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_error_kind() {
+            let err = OntologyRegistryError::ProvidingOntology {
+                reason: "offline".to_string(),
+            };
+            assert_eq!(error_kind(&err), "providing_ontology");
+            assert_eq!(
+                error_kind(&OntologyRegistryError::NoRegistry),
+                "no_registry"
+            );
+        }
+    }
+}
+
+pub(crate) fn record_cache_hit(_registry_key: &RegistryKey) {
+    #[cfg(feature = "otel")]
+    otel::instruments()
+        .cache_hits
+        .add(1, &otel::key_attributes(_registry_key));
+}
+
+pub(crate) fn record_cache_miss(_registry_key: &RegistryKey) {
+    #[cfg(feature = "otel")]
+    otel::instruments()
+        .cache_misses
+        .add(1, &otel::key_attributes(_registry_key));
+}
+
+pub(crate) fn record_download(_registry_key: &RegistryKey, _duration: Duration, _bytes: u64) {
+    #[cfg(feature = "otel")]
+    {
+        let instruments = otel::instruments();
+        let attributes = otel::key_attributes(_registry_key);
+        instruments.registrations.add(1, &attributes);
+        instruments
+            .download_duration
+            .record(_duration.as_secs_f64(), &attributes);
+        instruments.download_bytes.record(_bytes, &attributes);
+    }
+}
+
+pub(crate) fn record_error(_err: &OntologyRegistryError) {
+    #[cfg(feature = "otel")]
+    otel::instruments().errors.add(
+        1,
+        &[opentelemetry::KeyValue::new("kind", otel::error_kind(_err))],
+    );
+}
@@ -0,0 +1,100 @@
+use crate::error::OntologyRegistryError;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Read};
+
+/// The header block of an OBO file, i.e. every `tag: value` line before the first stanza.
+///
+/// Common provenance tags are exposed as fields. All header tags, including the common ones,
+/// are kept in `tags` in file order.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OboHeader {
+    pub format_version: Option<String>,
+    pub data_version: Option<String>,
+    pub ontology: Option<String>,
+    pub date: Option<String>,
+    pub saved_by: Option<String>,
+    pub tags: Vec<(String, String)>,
+}
+
+impl OboHeader {
+    /// Parses the header from an OBO document. Reading stops at the first stanza (`[Term]`, ...),
+    /// so term definitions are never loaded.
+    pub fn from_reader(reader: impl Read) -> Result<OboHeader, OntologyRegistryError> {
+        let mut header = OboHeader::default();
+
+        for line in BufReader::new(reader).lines() {
+            let line = line.map_err(|err| OntologyRegistryError::ReadingOboHeader {
+                reason: err.to_string(),
+            })?;
+            let line = line.trim();
+
+            if line.starts_with('[') {
+                break;
+            }
+            if line.is_empty() || line.starts_with('!') {
+                continue;
+            }
+
+            let Some((tag, value)) = line.split_once(':') else {
+                return Err(OntologyRegistryError::ReadingOboHeader {
+                    reason: format!("Malformed header line '{line}'"),
+                });
+            };
+            let (tag, value) = (tag.trim(), value.trim());
+
+            match tag {
+                "format-version" => header.format_version = Some(value.to_string()),
+                "data-version" => header.data_version = Some(value.to_string()),
+                "ontology" => header.ontology = Some(value.to_string()),
+                "date" => header.date = Some(value.to_string()),
+                "saved-by" => header.saved_by = Some(value.to_string()),
+                _ => {}
+            }
+            header.tags.push((tag.to_string(), value.to_string()));
+        }
+
+        Ok(header)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_reader_parses_header_fields() {
+        let obo = "format-version: 1.2\n\
+                   data-version: hp/releases/2024-04-26/hp.obo\n\
+                   saved-by: Peter Robinson\n\
+                   date: 26:04:2024 12:00\n\
+                   subsetdef: hposlim_core \"Core clinical terminology\"\n\
+                   ontology: hp\n\
+                   \n\
+                   [Term]\n\
+                   id: HP:0000001\n\
+                   name: All\n";
+
+        let header = OboHeader::from_reader(obo.as_bytes()).unwrap();
+
+        assert_eq!(header.format_version.as_deref(), Some("1.2"));
+        assert_eq!(
+            header.data_version.as_deref(),
+            Some("hp/releases/2024-04-26/hp.obo")
+        );
+        assert_eq!(header.saved_by.as_deref(), Some("Peter Robinson"));
+        assert_eq!(header.date.as_deref(), Some("26:04:2024 12:00"));
+        assert_eq!(header.ontology.as_deref(), Some("hp"));
+        assert_eq!(header.tags.len(), 6);
+        assert!(!header.tags.iter().any(|(tag, _)| tag == "id"));
+    }
+
+    #[test]
+    fn test_from_reader_malformed_line() {
+        let result = OboHeader::from_reader("format-version 1.2\n".as_bytes());
+
+        assert!(matches!(
+            result,
+            Err(OntologyRegistryError::ReadingOboHeader { .. })
+        ));
+    }
+}
@@ -0,0 +1,190 @@
+use crate::{OntologyRegistryError, SupportedOntology};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// A validated, lowercased ontology id such as `hp` or `mondo`.
+///
+/// Ids follow the shape of OBO prefixes: an ASCII letter followed by ASCII letters, digits,
+/// `_`, `-` or `.`. Spaces are allowed inside an id, e.g. `cl modifier`, since providers
+/// percent-encode them. Anything else, in particular path separators, is rejected before it
+/// can reach a provider URL or a file name. Casing is normalized to lowercase, the form OBO
+/// Library file names use, so `HP` and `hp` are the same id.
+///
+/// The id-based entry points of `FileSystemOntologyRegistry`, e.g. `get_or_register`, accept
+/// anything that converts into an `OntologyId`, including `&str`. `OntologyId` also converts
+/// into `String`, so it can be passed wherever the API accepts `impl Into<String>`, e.g.
+/// [`RegistryKey::new`](crate::RegistryKey::new).
+///
+/// ```rust
+/// use ontology_registry::OntologyId;
+///
+/// let ontology_id: OntologyId = "HP".parse().unwrap();
+/// assert_eq!(ontology_id.as_str(), "hp");
+/// assert!(OntologyId::new("hp/../etc").is_err());
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct OntologyId(String);
+
+impl OntologyId {
+    /// Validates `ontology_id` and lowercases it.
+    ///
+    /// # Errors
+    /// Returns `OntologyRegistryError::InvalidOntologyId` if the id is empty, does not start
+    /// with an ASCII letter, ends with a space or contains a character other than ASCII
+    /// letters, digits, `_`, `-`, `.` and spaces.
+    pub fn new(ontology_id: impl AsRef<str>) -> Result<Self, OntologyRegistryError> {
+        let ontology_id = ontology_id.as_ref();
+        let invalid = |reason: &str| OntologyRegistryError::InvalidOntologyId {
+            ontology_id: ontology_id.to_string(),
+            reason: reason.to_string(),
+        };
+
+        let mut chars = ontology_id.chars();
+        match chars.next() {
+            None => return Err(invalid("the id is empty")),
+            Some(first) if !first.is_ascii_alphabetic() => {
+                return Err(invalid("the id must start with an ASCII letter"));
+            }
+            Some(_) => {}
+        }
+        if let Some(c) =
+            chars.find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | ' ')))
+        {
+            return Err(invalid(&format!("{c:?} is not allowed in an ontology id")));
+        }
+        if ontology_id.ends_with(' ') {
+            return Err(invalid("the id must not end with a space"));
+        }
+
+        Ok(OntologyId(ontology_id.to_ascii_lowercase()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for OntologyId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for OntologyId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for OntologyId {
+    type Err = OntologyRegistryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl TryFrom<&str> for OntologyId {
+    type Error = OntologyRegistryError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl TryFrom<String> for OntologyId {
+    type Error = OntologyRegistryError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl From<SupportedOntology> for OntologyId {
+    fn from(value: SupportedOntology) -> Self {
+        OntologyId(value.to_string())
+    }
+}
+
+impl From<OntologyId> for String {
+    fn from(value: OntologyId) -> Self {
+        value.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileType, RegistryKey, Version};
+
+    #[test]
+    fn test_valid_ids() {
+        for ontology_id in [
+            "hp",
+            "mondo",
+            "go",
+            "ncbitaxon",
+            "my_ontology",
+            "mesh.2012",
+            "x-1",
+            "cl modifier",
+        ] {
+            assert_eq!(OntologyId::new(ontology_id).unwrap().as_str(), ontology_id);
+        }
+    }
+
+    #[test]
+    fn test_rejected_ids() {
+        for ontology_id in [
+            "",
+            "hp/../etc",
+            "/hp",
+            "hp\\go",
+            "hp ",
+            "h\tp",
+            "1hp",
+            "_hp",
+            "hp@1.0",
+            "hö",
+        ] {
+            assert!(
+                matches!(
+                    OntologyId::new(ontology_id),
+                    Err(OntologyRegistryError::InvalidOntologyId { .. })
+                ),
+                "{ontology_id:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_casing_is_normalized() {
+        assert_eq!(
+            OntologyId::new("HP").unwrap(),
+            OntologyId::new("hp").unwrap()
+        );
+        assert_eq!("NCIT".parse::<OntologyId>().unwrap().to_string(), "ncit");
+        assert_eq!(
+            OntologyId::from(SupportedOntology::MONDO),
+            OntologyId::new("mondo").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_usable_as_registry_key_id() {
+        let ontology_id = OntologyId::new("Uberon").unwrap();
+
+        let reg_key = RegistryKey::new(ontology_id, Version::Latest, FileType::Owl);
+
+        assert_eq!(reg_key.ontology_id(), "uberon");
+    }
+
+    #[test]
+    fn test_deserialize_validates() {
+        let ontology_id: OntologyId = serde_json::from_str("\"GO\"").unwrap();
+        assert_eq!(ontology_id.as_str(), "go");
+        assert!(serde_json::from_str::<OntologyId>("\"go/x\"").is_err());
+    }
+}
@@ -1,4 +1,7 @@
+use crate::enums::FileType;
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, Eq, Hash)]
 pub struct OntologyMetadata {
@@ -8,4 +11,130 @@ pub struct OntologyMetadata {
     pub owl_file_location: Option<String>,
     pub obo_file_location: Option<String>,
     pub title: Option<String>,
+    /// Whether the metadata source marks the ontology as obsolete.
+    #[serde(default)]
+    pub is_obsolete: bool,
+    /// The id of the ontology that replaces an obsolete one, if the source names one.
+    #[serde(default)]
+    pub replaced_by: Option<String>,
+    /// The ids of the ontologies this ontology imports or otherwise depends on.
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+}
+
+impl OntologyMetadata {
+    /// Returns the download location for the given file type, if the metadata source provides one.
+    pub fn file_location(&self, file_type: FileType) -> Option<&str> {
+        match file_type {
+            FileType::Json => self.json_file_location.as_deref(),
+            FileType::Obo => self.obo_file_location.as_deref(),
+            FileType::Owl => self.owl_file_location.as_deref(),
+            // None of the metadata sources publish Turtle locations.
+            FileType::Ttl => None,
+        }
+    }
+
+    /// Returns the file types for which the metadata source provides a download location.
+    pub fn available_formats(&self) -> Vec<FileType> {
+        FileType::ALL
+            .iter()
+            .copied()
+            .filter(|file_type| self.file_location(*file_type).is_some())
+            .collect()
+    }
+}
+
+/// A one-line summary for logs, e.g. `mondo "Mondo Disease Ontology" @ 2024-01-04 [json,obo]`.
+///
+/// The title and format list are left out if unknown, and obsolete ontologies are marked.
+impl Display for OntologyMetadata {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.ontology_id)?;
+        if let Some(title) = &self.title {
+            write!(f, " \"{title}\"")?;
+        }
+        write!(f, " @ {}", self.version)?;
+
+        let formats = self.available_formats();
+        if !formats.is_empty() {
+            let formats = formats
+                .iter()
+                .map(|file_type| file_type.as_file_ending().trim_start_matches('.'))
+                .join(",");
+            write!(f, " [{formats}]")?;
+        }
+        if self.is_obsolete {
+            write!(f, " (obsolete)")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_available_formats() {
+        let metadata = OntologyMetadata {
+            ontology_id: "mondo".to_string(),
+            version: "2024-01-04".to_string(),
+            json_file_location: Some("http://purl.obolibrary.org/obo/mondo.json".to_string()),
+            owl_file_location: None,
+            obo_file_location: Some("http://purl.obolibrary.org/obo/mondo.obo".to_string()),
+            title: None,
+            is_obsolete: false,
+            replaced_by: None,
+            dependencies: Vec::new(),
+        };
+
+        assert_eq!(
+            metadata.available_formats(),
+            vec![FileType::Json, FileType::Obo]
+        );
+        assert!(OntologyMetadata::default().available_formats().is_empty());
+    }
+
+    #[test]
+    fn test_display_summary() {
+        let metadata = OntologyMetadata {
+            ontology_id: "mondo".to_string(),
+            version: "2024-01-04".to_string(),
+            json_file_location: Some("http://purl.obolibrary.org/obo/mondo.json".to_string()),
+            owl_file_location: Some("http://purl.obolibrary.org/obo/mondo.owl".to_string()),
+            title: Some("Mondo Disease Ontology".to_string()),
+            ..OntologyMetadata::default()
+        };
+
+        assert_eq!(
+            metadata.to_string(),
+            r#"mondo "Mondo Disease Ontology" @ 2024-01-04 [json,owl]"#
+        );
+
+        let obsolete = OntologyMetadata {
+            ontology_id: "mp".to_string(),
+            version: "2024-02-07".to_string(),
+            is_obsolete: true,
+            ..OntologyMetadata::default()
+        };
+        assert_eq!(obsolete.to_string(), "mp @ 2024-02-07 (obsolete)");
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let metadata = OntologyMetadata {
+            ontology_id: "hp".to_string(),
+            version: "2024-04-26".to_string(),
+            obo_file_location: Some("http://purl.obolibrary.org/obo/hp.obo".to_string()),
+            title: Some("Human Phenotype Ontology".to_string()),
+            ..OntologyMetadata::default()
+        };
+
+        let json = serde_json::to_string(&metadata).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<OntologyMetadata>(&json).unwrap(),
+            metadata
+        );
+    }
 }
@@ -0,0 +1,32 @@
+//! # Prelude
+//!
+//! Re-exports the types needed for everyday use of the registry, so a single import suffices:
+//!
+//! ```rust,no_run
+//! # #[cfg(all(feature = "bioregistry", feature = "obolibrary"))]
+//! # {
+//! use ontology_registry::prelude::*;
+//! use std::path::PathBuf;
+//!
+//! let registry = FileSystemOntologyRegistry::new(
+//!     PathBuf::from("./local_ontology_cache"),
+//!     BioRegistryMetadataProvider::default(),
+//!     OboLibraryProvider::default(),
+//! );
+//! let reg_key = RegistryKey::new(SupportedOntology::HP, Version::Latest, FileType::Obo);
+//! let _file = registry.register(reg_key);
+//! # }
+//! ```
+
+#[cfg(feature = "bioregistry")]
+pub use crate::blocking::bio_registry_metadata_provider::BioRegistryMetadataProvider;
+pub use crate::blocking::file_system_ontology_registry::FileSystemOntologyRegistry;
+#[cfg(feature = "obolibrary")]
+pub use crate::blocking::obolib_ontology_provider::OboLibraryProvider;
+pub use crate::enums::{FileType, RegistryLayout, SupportedOntology, Version};
+pub use crate::error::OntologyRegistryError;
+pub use crate::ontology_id::OntologyId;
+pub use crate::registry_key::RegistryKey;
+pub use crate::traits::{
+    FileNamingStrategy, OntologyMetadataProviding, OntologyProviding, OntologyRegistration,
+};
@@ -34,27 +34,48 @@ impl RegistryKey {
     pub fn into_parts(self) -> (String, Version, FileType) {
         (self.ontology_id, self.version, self.file_type)
     }
+    /// Returns the file name under which this key is stored, e.g. `go@2024-01-01.json`, or
+    /// `go.json` for `Version::Unversioned`.
+    ///
+    /// The ontology id is lowercased so that keys differing only in casing map to the same file
+    /// on case-sensitive and case-insensitive filesystems alike.
     pub fn as_file_name(&self) -> String {
-        format!(
-            "{}@{}{}",
-            self.ontology_id,
-            self.version,
-            self.file_type.as_file_ending()
-        )
+        let ontology_id = self.ontology_id.to_lowercase();
+        let file_ending = self.file_type.as_file_ending();
+        match self.version {
+            Version::Unversioned => format!("{ontology_id}{file_ending}"),
+            _ => format!("{ontology_id}@{}{file_ending}", self.version),
+        }
     }
 
+    /// Parses a file name produced by [`Self::as_file_name`]. A name without `@` is read as
+    /// `Version::Unversioned`.
     pub fn from_file_name(file_name: &str) -> Result<RegistryKey, OntologyRegistryError> {
         let parse_err = || OntologyRegistryError::CantParseRegistryKey {
             raw_key: file_name.to_string(),
         };
 
+        if !file_name.contains('@') {
+            let file_type = FileType::ALL
+                .iter()
+                .copied()
+                .find(|ft| file_name.ends_with(ft.as_file_ending()))
+                .ok_or_else(parse_err)?;
+            let ontology_id = file_name
+                .strip_suffix(file_type.as_file_ending())
+                .filter(|ontology_id| !ontology_id.is_empty())
+                .ok_or_else(parse_err)?;
+            return Ok(Self::new(ontology_id, Version::Unversioned, file_type));
+        }
+
         let (ontology_id, rest) = file_name
             .splitn(2, '@')
             .collect_tuple()
             .ok_or_else(parse_err)?;
 
-        let file_type = FileType::all()
-            .into_iter()
+        let file_type = FileType::ALL
+            .iter()
+            .copied()
             .find(|ft| rest.ends_with(ft.as_file_ending()))
             .ok_or_else(parse_err)?;
 
@@ -64,6 +85,7 @@ impl RegistryKey {
 
         let version = match version_str {
             "latest" => Version::Latest,
+            "unversioned" => Version::Unversioned,
             v => Version::Declared(v.to_string()),
         };
 
@@ -109,6 +131,7 @@ impl FromStr for RegistryKey {
 
         let version = match parts[1] {
             "latest" => Version::Latest,
+            "unversioned" => Version::Unversioned,
             v => Version::Declared(v.to_string()),
         };
 
@@ -116,6 +139,7 @@ impl FromStr for RegistryKey {
             "json" => FileType::Json,
             "owl" => FileType::Owl,
             "obo" => FileType::Obo,
+            "ttl" => FileType::Ttl,
             _ => {
                 return Err(OntologyRegistryError::CantParseFileFormat {
                     raw_format: parts[2].to_string(),
@@ -188,6 +212,25 @@ mod tests {
         assert_eq!(key.version(), &Version::Declared("2024-01-15".to_string()));
     }
 
+    #[test]
+    fn test_as_file_name_normalizes_ontology_id_casing() {
+        let upper = RegistryKey::new("GO", Version::from("1.0"), FileType::Json);
+        let lower = RegistryKey::new("go", Version::from("1.0"), FileType::Json);
+
+        assert_eq!(upper.as_file_name(), "go@1.0.json");
+        assert_eq!(upper.as_file_name(), lower.as_file_name());
+    }
+
+    #[test]
+    fn test_unversioned_file_name_round_trip() {
+        let reg_key = RegistryKey::new("mondo", Version::Unversioned, FileType::Owl);
+
+        assert_eq!(reg_key.as_file_name(), "mondo.owl");
+        assert_eq!(RegistryKey::from_file_name("mondo.owl").unwrap(), reg_key);
+        assert!(RegistryKey::from_file_name(".owl").is_err());
+        assert!(RegistryKey::from_file_name("mondo.txt").is_err());
+    }
+
     #[test]
     fn test_as_file_name() {
         let reg_key = RegistryKey::new("uo", Version::Declared("4v".to_string()), FileType::Json);
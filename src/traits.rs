@@ -11,11 +11,15 @@
 //! or a custom internal metadata server) while keeping the rest of the application logic unchanged.
 
 use crate::RegistryKey;
-use crate::enums::Version;
+use crate::cache_validators::{CacheValidators, ConditionalFetch};
+use crate::enums::{FileType, Version};
 use crate::error::OntologyRegistryError;
 use crate::ontology_metadata::OntologyMetadata;
+use std::fmt::Debug;
 use std::fs::File;
 use std::io::Read;
+use std::path::Path;
+use std::time::Duration;
 
 /// Defines how to retrieve metadata about an ontology.
 ///
@@ -35,6 +39,32 @@ pub trait OntologyMetadataProviding {
         &self,
         ontology_id: &str,
     ) -> Result<OntologyMetadata, OntologyRegistryError>;
+
+    /// Fetches metadata for several ontologies at once and returns one result per id, in the
+    /// order of `ontology_ids`.
+    ///
+    /// The default implementation calls [`provide_metadata`](Self::provide_metadata) for each id
+    /// in turn. Providers with a bulk endpoint override it to answer in a single request.
+    fn provide_metadata_batch(
+        &self,
+        ontology_ids: &[&str],
+    ) -> Vec<(String, Result<OntologyMetadata, OntologyRegistryError>)> {
+        ontology_ids
+            .iter()
+            .map(|id| (id.to_string(), self.provide_metadata(id)))
+            .collect()
+    }
+
+    /// Lists the ids of all ontologies this provider can describe, e.g. for discovery.
+    ///
+    /// # Errors
+    /// The default implementation fails with `ProvidingMetadata`, for providers that can only
+    /// look up one ontology at a time.
+    fn list_available(&self) -> Result<Vec<String>, OntologyRegistryError> {
+        Err(OntologyRegistryError::ProvidingMetadata {
+            reason: "Listing available ontologies is not supported by this provider".to_string(),
+        })
+    }
 }
 
 /// Defines how to fetch the raw content (bytes) of an ontology file.
@@ -54,12 +84,184 @@ pub trait OntologyProviding {
         file_name: &str,
         version: &Version,
     ) -> Result<impl Read, OntologyRegistryError>;
+
+    /// Returns the file name to request for `ontology_id` in `file_type`, as passed to
+    /// [`provide_ontology`](Self::provide_ontology) by the registry.
+    ///
+    /// The default implementation takes the last path segment of the download location in
+    /// `metadata`, falling back to `{ontology_id}{file_ending}` (e.g. `go.owl`) if no location
+    /// is known. Providers with a different naming convention override it.
+    fn file_name_for(
+        &self,
+        ontology_id: &str,
+        file_type: &FileType,
+        metadata: Option<&OntologyMetadata>,
+    ) -> String {
+        metadata
+            .and_then(|m| m.file_location(*file_type))
+            .and_then(|location| location.trim_end_matches('/').rsplit('/').next())
+            .filter(|file_name| !file_name.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{ontology_id}{}", file_type.as_file_ending()))
+    }
+
+    /// Fetches the newest release of `ontology_id` without knowing its version in advance.
+    ///
+    /// Returns the concrete version, discovered e.g. from the URL the request was redirected to
+    /// or from the file's header, together with a reader for the content. The default
+    /// implementation reports that the provider does not support it.
+    fn provide_latest(
+        &self,
+        ontology_id: &str,
+        file_name: &str,
+    ) -> Result<(String, impl Read), OntologyRegistryError> {
+        Err::<(String, std::io::Empty), _>(OntologyRegistryError::ProvidingOntology {
+            reason: format!("Provider cannot fetch the latest {file_name} of {ontology_id}"),
+        })
+    }
+
+    /// Returns the version [`provide_latest`](Self::provide_latest) currently serves, so the
+    /// cache can be checked before anything is downloaded.
+    ///
+    /// The default implementation calls `provide_latest` and drops the reader unread. Providers
+    /// that can tell the version without starting the transfer override it.
+    fn latest_version(
+        &self,
+        ontology_id: &str,
+        file_name: &str,
+    ) -> Result<String, OntologyRegistryError> {
+        self.provide_latest(ontology_id, file_name)
+            .map(|(version, _)| version)
+    }
+
+    /// Like [`provide_ontology`](Self::provide_ontology), but skips the transfer if the file is
+    /// unchanged since `validators` were issued.
+    ///
+    /// Returns the validators of the new content along with its reader, so they can be sent
+    /// with the next request. The default implementation always downloads and reports no
+    /// validators.
+    fn provide_ontology_if_modified(
+        &self,
+        ontology_id: &str,
+        file_name: &str,
+        version: &Version,
+        validators: &CacheValidators,
+    ) -> Result<ConditionalFetch<impl Read>, OntologyRegistryError> {
+        let _ = validators;
+        Ok(ConditionalFetch::Modified {
+            reader: self.provide_ontology(ontology_id, file_name, version)?,
+            validators: CacheValidators::default(),
+            content_length: None,
+        })
+    }
+}
+
+/// Object-safe counterpart of [`OntologyProviding`].
+///
+/// `OntologyProviding` returns `impl Read` and therefore cannot be used as a trait object.
+/// This trait is implemented for every `OntologyProviding` type and boxes the reader, so
+/// heterogeneous providers can be stored together, e.g. as `Vec<Box<dyn DynOntologyProviding>>`.
+pub trait DynOntologyProviding {
+    /// Same as [`OntologyProviding::provide_ontology`], with the reader boxed. The reader may
+    /// borrow from the provider and from the arguments.
+    fn provide_ontology_boxed<'a>(
+        &'a self,
+        ontology_id: &'a str,
+        file_name: &'a str,
+        version: &'a Version,
+    ) -> Result<Box<dyn Read + 'a>, OntologyRegistryError>;
+}
+
+impl<T: OntologyProviding> DynOntologyProviding for T {
+    fn provide_ontology_boxed<'a>(
+        &'a self,
+        ontology_id: &'a str,
+        file_name: &'a str,
+        version: &'a Version,
+    ) -> Result<Box<dyn Read + 'a>, OntologyRegistryError> {
+        Ok(Box::new(self.provide_ontology(
+            ontology_id,
+            file_name,
+            version,
+        )?))
+    }
+}
+
+/// Decides where a [`FileSystemOntologyRegistry`](crate::FileSystemOntologyRegistry) stores
+/// each ontology file.
+///
+/// File names are `/`-separated paths relative to the registry root, e.g. `hp/2024-04-26/hp.obo`.
+/// [`RegistryLayout`](crate::RegistryLayout) provides the built-in schemes.
+pub trait FileNamingStrategy: Debug + Send + Sync {
+    /// Returns the path under which the given release is stored. `version` is `"latest"` for
+    /// the `latest` alias.
+    fn file_name(&self, ontology_id: &str, version: &str, file_type: &FileType) -> String;
+
+    /// Parses a path produced by [`file_name`](Self::file_name) back into its registry key.
+    ///
+    /// # Errors
+    /// Returns [`OntologyRegistryError::CantParseRegistryKey`] if `file_name` does not follow
+    /// the scheme.
+    fn parse_file_name(&self, file_name: &str) -> Result<RegistryKey, OntologyRegistryError>;
+
+    /// Returns the path under which the `Version::Unversioned` file of an ontology is stored.
+    ///
+    /// The default treats `"unversioned"` like a version. [`parse_file_name`](Self::parse_file_name)
+    /// must map the result back to `Version::Unversioned`.
+    fn unversioned_file_name(&self, ontology_id: &str, file_type: &FileType) -> String {
+        self.file_name(ontology_id, "unversioned", file_type)
+    }
+
+    /// Number of directories between the registry root and an ontology file.
+    ///
+    /// The default derives it from the number of separators in a sample file name.
+    fn directory_depth(&self) -> usize {
+        self.file_name("ontology", "version", &FileType::Json)
+            .matches('/')
+            .count()
+    }
+}
+
+/// Receives structured notifications about what a
+/// [`FileSystemOntologyRegistry`](crate::FileSystemOntologyRegistry) does, e.g. to feed custom
+/// metrics. Install one with
+/// [`with_observer`](crate::FileSystemOntologyRegistry::with_observer).
+///
+/// Every method defaults to a no-op, so implementors only override the events they care about.
+/// Methods are called synchronously on the registering thread, possibly while the registry's
+/// write lock is held, so they should return quickly and must not call back into the registry.
+pub trait RegistryObserver: Debug + Send + Sync {
+    /// Called after `registry_key` was downloaded and stored, with the number of bytes read from
+    /// the provider and the time the download took.
+    fn on_download(&self, registry_key: &RegistryKey, bytes: u64, duration: Duration) {
+        let _ = (registry_key, bytes, duration);
+    }
+
+    /// Called when a registration or refresh is served by a file already in the registry.
+    fn on_cache_hit(&self, registry_key: &RegistryKey) {
+        let _ = registry_key;
+    }
+
+    /// Called after the file at `path`, of `bytes` bytes, was evicted to stay within the size
+    /// budget.
+    fn on_evict(&self, path: &Path, bytes: u64) {
+        let _ = (path, bytes);
+    }
+
+    /// Called when registering `registry_key` failed.
+    fn on_error(&self, registry_key: &RegistryKey, error: &OntologyRegistryError) {
+        let _ = (registry_key, error);
+    }
 }
 
 /// The primary interface for managing the ontology lifecycle.
 ///
 /// This trait acts as a facade, coordinating the `OntologyMetadataProviding` and
 /// `OntologyProviding` traits to download, cache, and manage files.
+///
+/// Registered ontologies are handed out as open [`File`]s, which implement [`Read`].
+/// Implementations that store ontologies on disk may additionally expose their location,
+/// as [`FileSystemOntologyRegistry::path`](crate::FileSystemOntologyRegistry::path) does.
 pub trait OntologyRegistration {
     /// Downloads and registers an ontology.
     ///